@@ -5,6 +5,7 @@ use core::{
     mem::MaybeUninit,
     ops,
     pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
     time::Duration,
 };
 use pinned_init::*;
@@ -77,6 +78,15 @@ unsafe impl PinInit<CMutex<usize>> for CountInit {
 
 pub static COUNT: StaticInit<CMutex<usize>, CountInit> = StaticInit::new(CountInit);
 
+#[pin_data(init_type)]
+pub struct Stats {
+    finished: AtomicUsize,
+}
+
+pub static STATS: StaticInit<Stats, StatsInit<AtomicUsize>> = StaticInit::new(StatsInit {
+    finished: AtomicUsize::new(0),
+});
+
 #[cfg(not(any(feature = "std", feature = "alloc")))]
 fn main() {}
 
@@ -105,6 +115,7 @@ fn main() {
                         std::thread::sleep(std::time::Duration::from_millis(10));
                         *mtx.lock() += 1;
                     }
+                    STATS.finished.fetch_add(1, Ordering::Relaxed);
                     println!("{i} finished");
                 })
                 .expect("should not fail"),
@@ -115,4 +126,5 @@ fn main() {
     }
     println!("{:?}, {:?}", &*mtx.lock(), &*COUNT.lock());
     assert_eq!(*mtx.lock(), workload * thread_count * 2);
+    assert_eq!(STATS.finished.load(Ordering::Relaxed), thread_count);
 }
@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `#[pin_data(builder)]`.
+
+use crate::defaults::{default_helper, default_helpers, required_markers, unraw};
+use crate::pin_data::{error, error_ident, with_trailing_comma, Struct};
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+
+/// Generates `fn builder` for the struct `name` and the type-state builder it returns.
+///
+/// The builder has a generic parameter for every field, which is either `Unset` or `Provided<I>`
+/// where `I` is the initializer given for the field. A setter is only available while its field is
+/// `Unset` and `build` is only available once every field without a default value is `Provided`.
+pub(crate) fn impl_builder(item: &Struct<'_>) -> TokenStream {
+    let Struct {
+        krate,
+        vis,
+        name,
+        impl_generics,
+        ty_generics,
+        whr,
+        fields,
+    } = *item;
+    if let Some(f) = fields.iter().find(|f| !f.cfgs.is_empty()) {
+        return error(
+            f.name.span(),
+            "`#[cfg(..)]` on fields is not supported by `#[pin_data(builder)]`",
+        );
+    }
+    let impl_generics = with_trailing_comma(impl_generics);
+    let ty_generics = with_trailing_comma(ty_generics);
+    let err = error_ident(&ty_generics);
+    let builder = format_ident!("{}Builder", unraw(name));
+    let states = (0..fields.len())
+        .map(|i| format_ident!("__I{i}"))
+        .collect::<Vec<_>>();
+
+    let required = required_markers(fields);
+
+    let setters = fields.iter().enumerate().map(|(i, f)| {
+        let field = &f.name;
+        let ty = &f.ty;
+        let kind = if f.pinned {
+            quote!(#krate::PinInit)
+        } else {
+            quote!(#krate::Init)
+        };
+        let generics = states
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| i != j)
+            .map(|(_, state)| state);
+        let before = states.iter().enumerate().map(|(j, state)| {
+            if i == j {
+                quote!(#krate::__internal::Unset)
+            } else {
+                quote!(#state)
+            }
+        });
+        let after = states.iter().enumerate().map(|(j, state)| {
+            if i == j {
+                quote!(#krate::__internal::Provided<__Init>)
+            } else {
+                quote!(#state)
+            }
+        });
+        let values = (0..fields.len()).map(|j| {
+            if i == j {
+                quote!(#krate::__internal::Provided(#field))
+            } else {
+                let j = Literal::usize_unsuffixed(j);
+                quote!(self.__fields.#j)
+            }
+        });
+        quote! {
+            impl<#(#impl_generics)* #err, #(#generics,)*>
+                #builder<#(#ty_generics)* #err, #(#before,)*>
+            where #(#whr)*
+            {
+                #[inline]
+                pub fn #field<__Init>(self, #field: __Init)
+                    -> #builder<#(#ty_generics)* #err, #(#after,)*>
+                where
+                    __Init: #kind<#(#ty)*, #err>,
+                {
+                    #builder {
+                        __phantom: ::core::marker::PhantomData,
+                        __fields: (#(#values,)*),
+                    }
+                }
+            }
+        }
+    });
+
+    let helpers = default_helpers(krate, fields);
+
+    let bounds = fields.iter().zip(&states).map(|(f, state)| {
+        let field = &f.name;
+        let ty = &f.ty;
+        let kind = if f.pinned {
+            quote!(#krate::__internal::PinBuilderField)
+        } else {
+            quote!(#krate::__internal::BuilderField)
+        };
+        if f.default.is_some() {
+            quote!(#state: #kind<#(#ty)*, #err>,)
+        } else {
+            quote! {
+                #state: #krate::__internal::Initialized<__required::#field>
+                    + #kind<#(#ty)*, #err>,
+            }
+        }
+    });
+
+    let init_fields = fields.iter().enumerate().map(|(i, f)| {
+        let field = &f.name;
+        let field_name = Literal::string(&unraw(field));
+        let i = Literal::usize_unsuffixed(i);
+        let ty = &f.ty;
+        let guard = format_ident!("__{}_guard", unraw(field));
+        let default = if f.default.is_some() {
+            let helper = default_helper(field);
+            quote!(Self::#helper)
+        } else {
+            quote!(|| -> #(#ty)* { ::core::unreachable!() })
+        };
+        let init = if f.pinned {
+            quote! {
+                #krate::__internal::PinBuilderField::__pinned_init_or(
+                    self.__fields.#i,
+                    ::core::ptr::addr_of_mut!((*slot).#field),
+                    #default,
+                )
+            }
+        } else {
+            quote! {
+                #krate::__internal::BuilderField::__init_or(
+                    self.__fields.#i,
+                    ::core::ptr::addr_of_mut!((*slot).#field),
+                    #default,
+                )
+            }
+        };
+        quote! {
+            let #guard = {
+                // Reports the steps of the field with the `trace` feature, does nothing otherwise.
+                let field = #krate::__internal::TraceField::start(type_name, #field_name, #i);
+                // SAFETY: The field is not yet initialized and `slot` is valid.
+                unsafe { #init? };
+                // SAFETY: We forget the guard once all fields have been initialized.
+                unsafe {
+                    #krate::__internal::DropGuard::traced(
+                        ::core::ptr::addr_of_mut!((*slot).#field),
+                        field,
+                    )
+                }
+            };
+        }
+    });
+    let guards = fields
+        .iter()
+        .map(|f| format_ident!("__{}_guard", unraw(&f.name)));
+
+    let unset = fields
+        .iter()
+        .map(|_| quote!(#krate::__internal::Unset))
+        .collect::<Vec<_>>();
+    let doc = format!(
+        " Returns a builder for [`{name}`].\n\n \
+          Every field without a default value has to be set before calling `build`."
+    );
+
+    quote! {
+        const _: () = {
+            #required
+
+            #[doc(hidden)]
+            #(#vis)* struct #builder<#(#impl_generics)* #err, #(#states,)*>
+            where #(#whr)*
+            {
+                __phantom: ::core::marker::PhantomData<
+                    fn(#name<#(#ty_generics)*>) -> (#name<#(#ty_generics)*>, #err)
+                >,
+                // The state of every field, either `Unset` or `Provided`.
+                __fields: (#(#states,)*),
+            }
+
+            impl<#(#impl_generics)*> #name<#(#ty_generics)*>
+            where #(#whr)*
+            {
+                #[doc = #doc]
+                #[inline]
+                #(#vis)* fn builder<#err>() -> #builder<#(#ty_generics)* #err, #(#unset,)*> {
+                    #builder {
+                        __phantom: ::core::marker::PhantomData,
+                        __fields: (#(#unset,)*),
+                    }
+                }
+            }
+
+            #(#setters)*
+
+            impl<#(#impl_generics)* #err, #(#states,)*> #builder<#(#ty_generics)* #err, #(#states,)*>
+            where #(#whr)*
+            {
+                #helpers
+
+                /// Creates the initializer from the fields that have been set. All other fields
+                /// are initialized with their default value.
+                #[inline]
+                #[allow(private_bounds)]
+                pub fn build(self) -> impl #krate::PinInit<#name<#(#ty_generics)*>, #err>
+                where #(#bounds)*
+                {
+                    let type_name = ::core::any::type_name::<#name<#(#ty_generics)*>>();
+                    let init = move |slot: *mut #name<#(#ty_generics)*>| {
+                        #(#init_fields)*
+                        // All fields have been initialized, so we can dismiss the guards.
+                        #(::core::mem::forget(#guards);)*
+                        ::core::result::Result::Ok(())
+                    };
+                    let init = move |slot| #krate::__internal::trace_result(type_name, init(slot));
+                    // SAFETY: All fields are initialized by `init` and on error, the fields that
+                    // have been initialized so far are dropped by their guards.
+                    unsafe { #krate::pin_init_from_closure(init) }
+                }
+            }
+        };
+    }
+}
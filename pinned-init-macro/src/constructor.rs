@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `#[pin_data(constructor)]`.
+
+use crate::pin_data::{error_ident, with_trailing_comma, Struct};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Generates `fn new` for the struct `name`, which takes an initializer for every field.
+///
+/// Structurally pinned fields take a `PinInit`, all other fields take an `Init`. Marker fields are
+/// initialized by the `..` tail of `try_pin_init!` and do not take a parameter.
+pub(crate) fn impl_constructor(item: &Struct<'_>) -> TokenStream {
+    let Struct {
+        krate,
+        vis,
+        name,
+        impl_generics,
+        ty_generics,
+        whr,
+        fields,
+    } = *item;
+    let impl_generics = with_trailing_comma(impl_generics);
+    let err = error_ident(ty_generics);
+    let markers = fields.iter().any(|f| f.marker).then(|| quote!(..));
+    let fields = fields.iter().filter(|f| !f.marker).collect::<Vec<_>>();
+    let params = fields.iter().map(|f| {
+        let field = &f.name;
+        let ty = &f.ty;
+        let cfgs = f.cfg_attrs();
+        if f.pinned {
+            quote!(#cfgs #field: impl #krate::PinInit<#(#ty)*, #err>)
+        } else {
+            quote!(#cfgs #field: impl #krate::Init<#(#ty)*, #err>)
+        }
+    });
+    let field_inits = fields.iter().map(|f| {
+        let field = &f.name;
+        let cfgs = f.cfg_attrs();
+        quote!(#cfgs #field <- #field,)
+    });
+    let doc = format!(
+        " Creates an initializer for [`{name}`] from an initializer for every field.\n\n \
+          Structurally pinned fields are initialized via `PinInit`, all other fields via `Init`."
+    );
+    quote! {
+        impl<#(#impl_generics)*> #name<#(#ty_generics)*>
+        where #(#whr)*
+        {
+            #[doc = #doc]
+            #[inline]
+            #(#vis)* fn new<#err>(#(#params),*) -> impl #krate::PinInit<Self, #err> {
+                #krate::try_pin_init!(Self {
+                    #(#field_inits)*
+                    #markers
+                }? #err)
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `#[default(..)]` and `#[default_init(..)]` on fields of `#[pin_data]` structs.
+
+use crate::pin_data::{with_trailing_comma, Field, FieldDefault, Struct};
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+/// Generates the `HasDefaults` implementation for the struct `name`.
+///
+/// This creates the `__Defaults` type, which tracks the fields that have been initialized
+/// explicitly in its generic parameters (one for each field, either `Set` or `Unset`). When the
+/// initializer reaches `..Default`, it calls `__init_defaults`, which is only available if all
+/// fields without a default value are `Set`.
+pub(crate) fn impl_has_defaults(item: &Struct<'_>) -> TokenStream {
+    let Struct {
+        krate,
+        vis,
+        name,
+        impl_generics,
+        ty_generics,
+        whr,
+        fields,
+    } = *item;
+    let impl_generics = with_trailing_comma(impl_generics);
+    let ty_generics = with_trailing_comma(ty_generics);
+    let states = (0..fields.len())
+        .map(|i| format_ident!("__F{i}"))
+        .collect::<Vec<_>>();
+
+    let required = required_markers(fields);
+
+    // The methods marking the fields as initialized.
+    let setters = fields.iter().enumerate().map(|(i, f)| {
+        let field = &f.name;
+        let states = states.iter().enumerate().map(|(j, state)| {
+            if i == j {
+                quote!(#krate::__internal::Set)
+            } else {
+                quote!(#state)
+            }
+        });
+        let cfgs = f.cfg_attrs();
+        quote! {
+            #cfgs
+            #[inline]
+            pub fn #field(self) -> __Defaults<#(#ty_generics)* #(#states,)*> {
+                __Defaults {
+                    __phantom: ::core::marker::PhantomData,
+                    __state: ::core::marker::PhantomData,
+                }
+            }
+        }
+    });
+
+    let helpers = default_helpers(krate, fields);
+
+    let bounds = fields.iter().zip(&states).map(|(f, state)| {
+        let field = &f.name;
+        if f.default.is_some() {
+            quote!(#state: #krate::__internal::FieldState,)
+        } else {
+            quote!(#state: #krate::__internal::Initialized<__required::#field>,)
+        }
+    });
+
+    let init_defaults = fields.iter().zip(&states).filter_map(|(f, state)| {
+        let field = &f.name;
+        let helper = default_helper(field);
+        let guard = format_ident!("__{}_guard", unraw(field));
+        let init = match f.default.as_ref()? {
+            FieldDefault::Value(_) => quote! {
+                let value = Self::#helper();
+                // SAFETY: The field is not yet initialized and `slot` is valid.
+                unsafe { ::core::ptr::write(::core::ptr::addr_of_mut!((*slot).#field), value) };
+            },
+            FieldDefault::Init(_) => quote! {
+                let init = Self::#helper();
+                // SAFETY: The field is not yet initialized and `slot` is valid. We use `data` to
+                // require the correct trait (`Init` or `PinInit`) for the field.
+                match unsafe { data.#field().__init(::core::ptr::addr_of_mut!((*slot).#field), init) } {
+                    ::core::result::Result::Ok(()) => {}
+                    ::core::result::Result::Err(e) => match e {},
+                }
+            },
+        };
+        let cfgs = f.cfg_attrs();
+        Some(quote! {
+            #cfgs
+            let #guard = if <#state as #krate::__internal::FieldState>::IS_SET {
+                ::core::option::Option::None
+            } else {
+                #init
+                // SAFETY: We forget the guard once all defaults have been initialized.
+                ::core::option::Option::Some(unsafe {
+                    #krate::__internal::DropGuard::new(
+                        ::core::ptr::addr_of_mut!((*slot).#field),
+                    )
+                })
+            };
+        })
+    });
+    let guards = fields.iter().filter(|f| f.default.is_some()).map(|f| {
+        let cfgs = f.cfg_attrs();
+        let guard = format_ident!("__{}_guard", unraw(&f.name));
+        quote!(#cfgs ::core::mem::forget(#guard);)
+    });
+
+    // Fields that are disabled by `#[cfg(..)]` start out as `Set`, since they do not exist.
+    let unset = fields.iter().map(|f| {
+        if f.cfgs.is_empty() {
+            quote!(#krate::__internal::Unset)
+        } else {
+            let alias = format_ident!("__cfg_state_{}", unraw(&f.name));
+            quote!(#alias)
+        }
+    });
+    // The aliases are not put into a module, since a relative `crate = path` would not resolve
+    // inside of it.
+    let cfg_states = fields.iter().filter(|f| !f.cfgs.is_empty()).map(|f| {
+        let alias = format_ident!("__cfg_state_{}", unraw(&f.name));
+        let cfgs = f.cfg_attrs();
+        let preds = &f.cfgs;
+        quote! {
+            #cfgs
+            #[allow(non_camel_case_types)]
+            type #alias = #krate::__internal::Unset;
+            #[cfg(not(all(#(#preds),*)))]
+            #[allow(non_camel_case_types)]
+            type #alias = #krate::__internal::Set;
+        }
+    });
+
+    quote! {
+        const _: () = {
+            #required
+
+            #(#cfg_states)*
+
+            #[doc(hidden)]
+            #(#vis)* struct __Defaults<#(#impl_generics)* #(#states,)*>
+            where #(#whr)*
+            {
+                __phantom: ::core::marker::PhantomData<
+                    fn(#name<#(#ty_generics)*>) -> #name<#(#ty_generics)*>
+                >,
+                __state: ::core::marker::PhantomData<fn() -> (#(#states,)*)>,
+            }
+
+            impl<#(#impl_generics)* #(#states,)*> __Defaults<#(#ty_generics)* #(#states,)*>
+            where #(#whr)*
+            {
+                #(#setters)*
+
+                #helpers
+
+                /// Initializes all fields that have not been initialized yet with their default
+                /// value.
+                ///
+                /// # Safety
+                ///
+                /// `slot` must be valid and all fields that have been marked as initialized in the
+                /// type of `self` must be initialized. All other fields must be uninitialized.
+                #[inline]
+                #[allow(private_bounds)]
+                pub unsafe fn __init_defaults(self, slot: *mut #name<#(#ty_generics)*>)
+                where #(#bounds)*
+                {
+                    // SAFETY: We only use the pin data to initialize fields.
+                    let data = unsafe {
+                        <#name<#(#ty_generics)*> as #krate::__internal::HasPinData>
+                            ::__pin_data()
+                    };
+                    #(#init_defaults)*
+                    // All defaults have been initialized, so we can dismiss the guards.
+                    #(#guards)*
+                    let _ = data;
+                }
+            }
+
+            // SAFETY: `__init_defaults` only initializes the fields that are not yet initialized.
+            unsafe impl<#(#impl_generics)*> #krate::__internal::HasDefaults
+                for #name<#(#ty_generics)*>
+            where #(#whr)*
+            {
+                type Defaults = __Defaults<#(#ty_generics)* #(#unset,)*>;
+
+                #[inline]
+                fn __defaults() -> Self::Defaults {
+                    __Defaults {
+                        __phantom: ::core::marker::PhantomData,
+                        __state: ::core::marker::PhantomData,
+                    }
+                }
+            }
+        };
+    }
+}
+
+/// Generates the module `__required`, which contains a marker type for every field without a
+/// default value. It shows up in the error message when the field is missing.
+pub(crate) fn required_markers(fields: &[Field]) -> TokenStream {
+    let required = fields
+        .iter()
+        .filter(|f| f.default.is_none())
+        .map(|f| &f.name);
+    quote! {
+        #[allow(non_camel_case_types)]
+        mod __required {
+            #(pub struct #required {})*
+        }
+    }
+}
+
+/// Generates a function named after [`default_helper`] for every field with a default value. It
+/// returns either the default value or the default initializer.
+///
+/// The default values are computed in safe functions, so user code is never placed inside of an
+/// `unsafe` block.
+pub(crate) fn default_helpers(krate: &TokenStream, fields: &[Field]) -> TokenStream {
+    fields
+        .iter()
+        .filter_map(|f| {
+            let helper = default_helper(&f.name);
+            let ty = &f.ty;
+            let (ret, value) = match f.default.as_ref()? {
+                FieldDefault::Value(value) => (quote!(#(#ty)*), value),
+                FieldDefault::Init(init) if f.pinned => {
+                    (quote!(impl #krate::PinInit<#(#ty)*>), init)
+                }
+                FieldDefault::Init(init) => (quote!(impl #krate::Init<#(#ty)*>), init),
+            };
+            let cfgs = f.cfg_attrs();
+            Some(quote! {
+                #cfgs
+                #[inline]
+                fn #helper() -> #ret {
+                    #value
+                }
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn default_helper(field: &Ident) -> Ident {
+    format_ident!("__default_{}", unraw(field))
+}
+
+pub(crate) fn unraw(ident: &Ident) -> String {
+    let ident = ident.to_string();
+    ident.strip_prefix("r#").unwrap_or(&ident).to_owned()
+}
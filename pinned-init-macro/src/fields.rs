@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for omitting marker fields of `#[pin_data]` structs in `pin_init!`.
+
+use crate::defaults::unraw;
+use crate::pin_data::{with_trailing_comma, Struct};
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned};
+
+/// Generates the `HasFields` implementation for the struct `name`.
+///
+/// This creates the `__Fields` type, which has a method for every field that returns a `Mention`
+/// of it. `__check` requires a mention of every field that is not a marker field, so initializers
+/// can omit the marker fields and let `__init_markers` initialize them.
+pub(crate) fn impl_has_fields(item: &Struct<'_>) -> TokenStream {
+    let Struct {
+        krate,
+        vis,
+        name,
+        impl_generics,
+        ty_generics,
+        whr,
+        fields,
+    } = *item;
+    let impl_generics = with_trailing_comma(impl_generics);
+    let ty_generics = with_trailing_comma(ty_generics);
+
+    let names = fields.iter().map(|f| &f.name).collect::<Vec<_>>();
+    let mentions = fields.iter().map(|f| {
+        let field = &f.name;
+        let cfgs = f.cfg_attrs();
+        quote! {
+            #cfgs
+            #[inline]
+            pub fn #field(&self) -> #krate::__internal::Mention<__fields::#field> {
+                #krate::__internal::Mention::new()
+            }
+        }
+    });
+
+    // Fields that are disabled by `#[cfg(..)]` do not need to be mentioned.
+    let required = fields.iter().filter(|f| !f.marker).collect::<Vec<_>>();
+    let cfg_names = required.iter().filter(|f| !f.cfgs.is_empty()).map(|f| {
+        let field = &f.name;
+        let alias = format_ident!("__cfg_{}", unraw(field));
+        let cfgs = f.cfg_attrs();
+        let preds = &f.cfgs;
+        quote! {
+            #cfgs
+            #[allow(non_camel_case_types)]
+            type #alias = __fields::#field;
+            #[cfg(not(all(#(#preds),*)))]
+            #[allow(non_camel_case_types)]
+            type #alias = #krate::__internal::Disabled;
+        }
+    });
+    let indices = (0..required.len())
+        .map(|i| format_ident!("__J{i}"))
+        .collect::<Vec<_>>();
+    let bounds = required.iter().zip(&indices).map(|(f, index)| {
+        let field = &f.name;
+        let name = if f.cfgs.is_empty() {
+            quote!(__fields::#field)
+        } else {
+            let alias = format_ident!("__cfg_{}", unraw(field));
+            quote!(#alias)
+        };
+        // Point the "required by this bound" note at the field.
+        let bound = quote!(__L: #krate::__internal::Mentions<#name, #index>,);
+        respan(bound, field.span())
+    });
+
+    let markers = fields.iter().filter(|f| f.marker).map(|f| {
+        let field = &f.name;
+        let cfgs = f.cfg_attrs();
+        let span = f.ty.first().map_or_else(|| field.span(), |tt| tt.span());
+        let init = quote_spanned! {span=>
+            #krate::__internal::init_marker(::core::ptr::addr_of_mut!((*slot).#field))
+        };
+        quote! {
+            #cfgs
+            // SAFETY: `slot` is valid.
+            unsafe { #init };
+        }
+    });
+
+    quote! {
+        const _: () = {
+            #[allow(non_camel_case_types, dead_code)]
+            mod __fields {
+                #(pub struct #names;)*
+            }
+
+            #(impl #krate::__internal::FieldName for __fields::#names {})*
+
+            #(#cfg_names)*
+
+            #[doc(hidden)]
+            #(#vis)* struct __Fields;
+
+            #[allow(private_interfaces)]
+            impl __Fields {
+                #(#mentions)*
+
+                /// Only compiles if every field that is not a marker field is mentioned in
+                /// `mentions`.
+                #[inline]
+                #[allow(private_bounds)]
+                pub fn __check<__L, #(#indices,)*>(&self, mentions: &__L)
+                where #(#bounds)*
+                {
+                    let _ = mentions;
+                }
+            }
+
+            // SAFETY: `__init_markers` initializes exactly the marker fields and `__check` requires
+            // a mention of every other field.
+            unsafe impl<#(#impl_generics)*> #krate::__internal::HasFields
+                for #name<#(#ty_generics)*>
+            where #(#whr)*
+            {
+                type Fields = __Fields;
+
+                #[inline]
+                fn __fields() -> Self::Fields {
+                    __Fields
+                }
+
+                #[inline]
+                unsafe fn __init_markers(slot: *mut Self) {
+                    #(#markers)*
+                    let _ = slot;
+                }
+            }
+        };
+    }
+}
+
+/// Sets the span of every token in `toks`, including the tokens inside of groups.
+fn respan(toks: TokenStream, span: Span) -> TokenStream {
+    toks.into_iter()
+        .map(|mut tt| {
+            if let TokenTree::Group(group) = &tt {
+                let mut new = Group::new(group.delimiter(), respan(group.stream(), span));
+                new.set_span(span);
+                tt = TokenTree::Group(new);
+            } else {
+                tt.set_span(span);
+            }
+            tt
+        })
+        .collect()
+}
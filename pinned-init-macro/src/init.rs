@@ -0,0 +1,1164 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Implementation of the `[try_][pin_]init!` and `[try_]async_pin_init!` macros.
+//!
+//! The declarative macros in `lib.rs` forward their input together with `$crate` and their own
+//! name to [`init`]. Parsing the input here allows us to report errors at the offending tokens
+//! instead of somewhere inside of a tt-muncher.
+
+use crate::defaults::unraw;
+use crate::pin_data::{closing_angle, error};
+use proc_macro2::{Delimiter, Group, Ident, Literal, Spacing, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned};
+use std::collections::HashSet;
+
+/// The settings shared by the whole initializer.
+struct Ctx {
+    /// `$crate` of the call site.
+    krate: TokenTree,
+    /// Whether the initializer is pinned (`[try_]pin_init!`) or not (`[try_]init!`).
+    pinned: bool,
+    /// Whether the initializer is asynchronous (`[try_]async_pin_init!`), it is also pinned.
+    asynchronous: bool,
+    /// The error type of the initializer.
+    err: TokenStream,
+    /// `? Error with context` wraps the errors in `InitError<Error>`. A nested initializer also
+    /// gets the prefix of its fields, e.g. `inner.`.
+    context: Option<String>,
+}
+
+/// A field of a struct initializer.
+struct InitField {
+    /// The attributes of the field, e.g. `#[cfg(..)]`. They are applied to every statement that
+    /// is generated for the field.
+    attrs: Vec<TokenTree>,
+    name: Ident,
+    kind: FieldKind,
+}
+
+enum FieldKind {
+    /// `name <- init`
+    Init(Vec<TokenTree>),
+    /// `name: Inner { a <- init }`, the nested initializer is generated by [`nested`]
+    Nested(Vec<TokenTree>),
+    /// `name: value`
+    Value(Vec<TokenTree>),
+    /// `name`, short for `name: name`
+    Shorthand,
+}
+
+/// The struct update syntax at the end of a struct initializer.
+enum Update {
+    None,
+    /// `..Zeroable::zeroed()`
+    Zeroed,
+    /// `..Default`
+    Default,
+    /// `..`, the marker fields are initialized with their default value
+    Markers,
+    /// `..base`
+    Base(Vec<TokenTree>),
+}
+
+/// An element of a tuple or array initializer.
+enum Element {
+    /// `<- init`
+    Init(Vec<TokenTree>),
+    /// `value`
+    Value(Vec<TokenTree>),
+}
+
+/// Entry point of the `[try_][pin_]init!` macros.
+///
+/// The input starts with `$crate` and the name of the macro, followed by the input of the user:
+/// `$crate, try_pin_init, Foo { a <- a, b: 0 }? Error`.
+pub(crate) fn init(input: TokenStream) -> TokenStream {
+    let mut toks = input.into_iter();
+    let (Some(krate), Some(_), Some(TokenTree::Ident(name)), Some(_)) =
+        (toks.next(), toks.next(), toks.next(), toks.next())
+    else {
+        return quote!(::core::compile_error!(
+            "use the `[try_][pin_]init!` macros instead of calling `__init!` directly"
+        ));
+    };
+    let name = name.to_string();
+    let mut toks = toks.collect::<Vec<_>>();
+    let mut errs = TokenStream::new();
+
+    // Split off the error type after the `?`.
+    let question = toks.iter().position(|tt| is_punct(tt, '?'));
+    let mut context = None;
+    let err = match (name.starts_with("try_"), question) {
+        (true, Some(pos)) => {
+            let mut err = toks.split_off(pos + 1);
+            let question = toks.pop().unwrap();
+            context = take_context(&mut err, &mut errs);
+            if err.is_empty() {
+                errs.extend(error(question.span(), "expected an error type after `?`"));
+            }
+            err.into_iter().collect()
+        }
+        (true, None) => {
+            let span = toks.last().map_or_else(Span::call_site, TokenTree::span);
+            errs.extend(error(
+                span,
+                &format!("`{name}!` requires an error type, append `? Error` to the initializer"),
+            ));
+            TokenStream::new()
+        }
+        (false, Some(pos)) => {
+            errs.extend(error(
+                toks[pos].span(),
+                &format!("`{name}!` cannot fail, use `try_{name}!` to specify an error type"),
+            ));
+            toks.truncate(pos);
+            quote!(::core::convert::Infallible)
+        }
+        (false, None) => quote!(::core::convert::Infallible),
+    };
+    let ctx = Ctx {
+        krate,
+        pinned: name.ends_with("pin_init"),
+        asynchronous: name.contains("async"),
+        err,
+        context,
+    };
+
+    let res = match &toks[..] {
+        [TokenTree::Group(g)] if ctx.asynchronous && g.delimiter() != Delimiter::Brace => {
+            errs.extend(error(
+                g.span(),
+                &format!("`{name}!` only supports struct initializers `Type {{ .. }}`"),
+            ));
+            TokenStream::new()
+        }
+        [TokenTree::Group(g)] if ctx.context.is_some() && g.delimiter() != Delimiter::Brace => {
+            errs.extend(error(
+                g.span(),
+                "`with context` is only supported by struct initializers `Type { .. }`",
+            ));
+            TokenStream::new()
+        }
+        [TokenTree::Group(g)] if g.delimiter() == Delimiter::Parenthesis => {
+            let elements = parse_elements(g, &mut errs);
+            init_literal(&ctx, false, &elements)
+        }
+        [TokenTree::Group(g)] if g.delimiter() == Delimiter::Bracket => {
+            let elements = parse_elements(g, &mut errs);
+            init_literal(&ctx, true, &elements)
+        }
+        [head @ .., TokenTree::Group(body)] if body.delimiter() == Delimiter::Brace => {
+            // `&this in` gives access to a pointer to the struct.
+            let (this, path) = match head {
+                [TokenTree::Punct(p), TokenTree::Ident(this), TokenTree::Ident(i), path @ ..]
+                    if p.as_char() == '&' && i == "in" =>
+                {
+                    (Some(this), path)
+                }
+                _ => (None, head),
+            };
+            check_path(path, body, &mut errs);
+            let (fields, update) = parse_fields(&ctx, body, &mut errs);
+            init_struct(&ctx, this, path, &fields, &update)
+        }
+        _ => {
+            let span = toks.first().map_or_else(Span::call_site, TokenTree::span);
+            errs.extend(error(
+                span,
+                "expected a struct initializer `Type { .. }`, a tuple `(..)` or an array `[..]`",
+            ));
+            TokenStream::new()
+        }
+    };
+    if errs.is_empty() {
+        res
+    } else {
+        // Only report the parse errors, the generated code would just cause follow-up errors.
+        quote!({
+            #errs
+            ::core::unreachable!()
+        })
+    }
+}
+
+/// Checks that `path` is the name of a type that can be used in a struct expression.
+fn check_path(path: &[TokenTree], body: &Group, errs: &mut TokenStream) {
+    if path.is_empty() {
+        errs.extend(error(
+            body.span_open(),
+            "expected the name of the type in front of `{`",
+        ));
+    }
+    // A qualified path `<T as Trait>::Assoc` starts with `<`.
+    let start = match path.first() {
+        Some(tt) if is_punct(tt, '<') => closing_angle(path, 0).map_or(path.len(), |i| i + 1),
+        _ => 0,
+    };
+    for (i, tt) in path.iter().enumerate().skip(start) {
+        if is_punct(tt, '<') && !is_punct(&path[i - 1], ':') {
+            errs.extend(error(
+                tt.span(),
+                "expected `::` before the generic arguments, e.g. `Foo::<T> { .. }`",
+            ));
+            break;
+        }
+    }
+}
+
+/// Removes `with context` from the end of the error type.
+///
+/// Nested initializers receive the prefix of their fields as `with context("inner.")`.
+fn take_context(err: &mut Vec<TokenTree>, errs: &mut TokenStream) -> Option<String> {
+    let with = err.windows(2).position(|w| {
+        matches!(w, [TokenTree::Ident(w), TokenTree::Ident(c)] if w == "with" && c == "context")
+    })?;
+    let context = err.split_off(with);
+    match &context[2..] {
+        [] => Some(String::new()),
+        [TokenTree::Group(g)] if g.delimiter() == Delimiter::Parenthesis => {
+            Some(g.stream().to_string().trim_matches('"').to_owned())
+        }
+        [tt, ..] => {
+            errs.extend(error(tt.span(), "unexpected token after `with context`"));
+            None
+        }
+    }
+}
+
+/// Parses the fields of a struct initializer.
+fn parse_fields(ctx: &Ctx, body: &Group, errs: &mut TokenStream) -> (Vec<InitField>, Update) {
+    let toks = body.stream().into_iter().collect::<Vec<_>>();
+    let mut pos = 0;
+    let mut fields = vec![];
+    let mut seen = HashSet::new();
+    let mut update = Update::None;
+    while pos < toks.len() {
+        let mut attrs = vec![];
+        while is_punct(&toks[pos], '#') && pos + 1 < toks.len() {
+            attrs.extend_from_slice(&toks[pos..pos + 2]);
+            pos += 2;
+        }
+        let Some(tt) = toks.get(pos) else {
+            errs.extend(error(
+                attrs[0].span(),
+                "expected a field after the attributes",
+            ));
+            break;
+        };
+        if is_dot_dot(&toks[pos..]) {
+            pos += 2;
+            let base = take_expr(&toks, &mut pos, errs);
+            update = match &base[..] {
+                [TokenTree::Ident(i)] if i == "Default" => {
+                    if !ctx.pinned {
+                        errs.extend(error(
+                            i.span(),
+                            "`..Default` is only supported by `pin_init!` and `try_pin_init!`",
+                        ));
+                    }
+                    Update::Default
+                }
+                _ if is_zeroed(&base) => Update::Zeroed,
+                [] => Update::Markers,
+                _ => Update::Base(base),
+            };
+            skip_comma(&toks, &mut pos);
+            if let Some(tt) = toks.get(pos) {
+                errs.extend(error(
+                    tt.span(),
+                    "the struct update syntax `..` has to be at the end of the initializer",
+                ));
+            } else if pos > 0 && is_punct(&toks[pos - 1], ',') {
+                errs.extend(error(
+                    toks[pos - 1].span(),
+                    "cannot use a comma after the struct update syntax `..`",
+                ));
+            }
+            if !attrs.is_empty() {
+                errs.extend(error(
+                    attrs[0].span(),
+                    "attributes are not supported on the struct update syntax",
+                ));
+            }
+            break;
+        }
+        pos += 1;
+        let TokenTree::Ident(name) = tt else {
+            errs.extend(error(tt.span(), "expected the name of a field"));
+            take_expr(&toks, &mut pos, errs);
+            skip_comma(&toks, &mut pos);
+            continue;
+        };
+        let kind = match toks.get(pos) {
+            None => FieldKind::Shorthand,
+            Some(tt) if is_punct(tt, ',') => FieldKind::Shorthand,
+            Some(TokenTree::Punct(p)) if p.as_char() == ':' && p.spacing() == Spacing::Alone => {
+                pos += 1;
+                if is_arrow(&toks[pos..]) {
+                    errs.extend(error(
+                        p.span(),
+                        "use `<-` for initializers instead of `: <-`",
+                    ));
+                    pos += 2;
+                }
+                let value = take_expr(&toks, &mut pos, errs);
+                if value.is_empty() {
+                    errs.extend(error(p.span(), "expected a value after `:`"));
+                }
+                check_missing_comma_before_update(&value, errs);
+                match nested(ctx, name, &value) {
+                    Some(init) => FieldKind::Nested(init),
+                    None => FieldKind::Value(value),
+                }
+            }
+            Some(tt) if is_arrow(&toks[pos..]) => {
+                pos += 2;
+                let init = take_expr(&toks, &mut pos, errs);
+                if init.is_empty() {
+                    errs.extend(error(tt.span(), "expected an initializer after `<-`"));
+                }
+                check_missing_comma_before_update(&init, errs);
+                FieldKind::Init(init)
+            }
+            Some(tt) => {
+                errs.extend(error(
+                    tt.span(),
+                    &format!(
+                        "expected `:` for a value or `<-` for an initializer after the field `{name}`"
+                    ),
+                ));
+                take_expr(&toks, &mut pos, errs);
+                skip_comma(&toks, &mut pos);
+                continue;
+            }
+        };
+        // Fields with different `#[cfg]`s might be configured away, so they are allowed to appear
+        // more than once. The compiler reports them if they are not.
+        if !seen.insert((cfgs(&attrs), name.to_string())) {
+            errs.extend(error(
+                name.span(),
+                &format!("field `{name}` is initialized more than once"),
+            ));
+        }
+        fields.push(InitField {
+            attrs,
+            name: name.clone(),
+            kind,
+        });
+        skip_comma(&toks, &mut pos);
+    }
+    (fields, update)
+}
+
+/// Returns the `#[cfg]` attributes among `attrs` as a string to compare them.
+fn cfgs(attrs: &[TokenTree]) -> String {
+    attrs
+        .iter()
+        .filter_map(|tt| match tt {
+            TokenTree::Group(g) => match g.stream().into_iter().next() {
+                Some(TokenTree::Ident(i)) if i == "cfg" => Some(g.stream().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parses the elements of a tuple or array initializer.
+fn parse_elements(group: &Group, errs: &mut TokenStream) -> Vec<Element> {
+    let toks = group.stream().into_iter().collect::<Vec<_>>();
+    let mut pos = 0;
+    let mut elements = vec![];
+    while pos < toks.len() {
+        let element = if is_arrow(&toks[pos..]) {
+            let arrow = toks[pos].span();
+            pos += 2;
+            let init = take_expr(&toks, &mut pos, errs);
+            if init.is_empty() {
+                errs.extend(error(arrow, "expected an initializer after `<-`"));
+            }
+            Element::Init(init)
+        } else {
+            let value = take_expr(&toks, &mut pos, errs);
+            if value.is_empty() {
+                errs.extend(error(toks[pos].span(), "expected an element before `,`"));
+            }
+            Element::Value(value)
+        };
+        elements.push(element);
+        skip_comma(&toks, &mut pos);
+    }
+    elements
+}
+
+/// Returns the nested initializer if `value` is a struct expression `Inner { .. }` in which at
+/// least one field uses `<-`.
+fn nested(ctx: &Ctx, name: &Ident, value: &[TokenTree]) -> Option<Vec<TokenTree>> {
+    let [TokenTree::Ident(_), generics @ .., TokenTree::Group(body)] = value else {
+        return None;
+    };
+    if body.delimiter() != Delimiter::Brace
+        || !(generics.is_empty() || is_punct(&generics[0], ':') && generics.len() >= 3)
+    {
+        return None;
+    }
+    let toks = body.stream().into_iter().collect::<Vec<_>>();
+    let mut pos = 0;
+    let mut found = false;
+    while pos < toks.len() && !found {
+        while is_punct(&toks[pos], '#') && pos + 2 < toks.len() {
+            pos += 2;
+        }
+        found = matches!(toks[pos], TokenTree::Ident(_)) && is_arrow(&toks[pos + 1..]);
+        // Errors are reported by the nested initializer.
+        take_expr(&toks, &mut pos, &mut TokenStream::new());
+        pos += 1;
+    }
+    if !found {
+        return None;
+    }
+    let Ctx {
+        krate,
+        pinned,
+        asynchronous,
+        err,
+        context,
+    } = ctx;
+    let mac = if *asynchronous {
+        quote!(try_async_pin_init)
+    } else if *pinned {
+        quote!(try_pin_init)
+    } else {
+        quote!(try_init)
+    };
+    // The fields of the nested initializer are reported as fields of this one.
+    let context = context.as_ref().map(|prefix| {
+        let prefix = Literal::string(&format!("{prefix}{}.", unraw(name)));
+        quote!(with context(#prefix))
+    });
+    Some(
+        quote!(#krate::#mac!(#(#value)*? #err #context))
+            .into_iter()
+            .collect(),
+    )
+}
+
+/// Reports a missing `,` in front of `..Zeroable::zeroed()` or `..Default`, which would otherwise
+/// be parsed as a range expression.
+fn check_missing_comma_before_update(expr: &[TokenTree], errs: &mut TokenStream) {
+    for i in 0..expr.len() {
+        let rest = &expr[i..];
+        if is_dot_dot(rest)
+            && (is_zeroed(&rest[2..])
+                || matches!(&rest[2..], [TokenTree::Ident(d)] if d == "Default"))
+        {
+            errs.extend(error(rest[0].span(), "expected `,` before `..`"));
+        }
+    }
+}
+
+/// Takes the tokens of an expression starting at `pos`, up to the next `,` that is not part of
+/// the expression.
+///
+/// Besides in groups, commas can also appear in generic arguments and in the parameters of
+/// closures. If a field (an identifier followed by `:` or `<-`) directly follows an expression,
+/// we report a missing `,` instead of continuing with the expression.
+fn take_expr(toks: &[TokenTree], pos: &mut usize, errs: &mut TokenStream) -> Vec<TokenTree> {
+    let start = *pos;
+    // The nesting depth of generic arguments.
+    let mut generics = 0usize;
+    // Whether we are inside of the parameters of a closure.
+    let mut params = false;
+    // Whether we are inside of a type after `as` or `->`, where `<` always opens generics.
+    let mut ty = false;
+    while let Some(tt) = toks.get(*pos) {
+        let prev = (*pos > start).then(|| &toks[*pos - 1]);
+        match tt {
+            TokenTree::Punct(p) => match p.as_char() {
+                ',' if generics == 0 && !params => break,
+                '|' if params => params = false,
+                '|' if p.spacing() == Spacing::Joint && is_punct_at(toks, *pos + 1, '|') => {
+                    // Either the `||` operator or a closure without parameters.
+                    *pos += 1;
+                }
+                '|' if starts_expr(prev) => params = true,
+                '-' if p.spacing() == Spacing::Joint && is_punct_at(toks, *pos + 1, '>') => {
+                    // `->` is followed by the return type of a closure.
+                    *pos += 1;
+                    ty = generics == 0 || ty;
+                }
+                '<' if generics > 0 || ty || starts_expr(prev) => generics += 1,
+                '>' if generics > 0 => generics -= 1,
+                ':' | '\'' | '&' | '*' | '<' | '>' => {}
+                _ if generics == 0 => ty = false,
+                _ => {}
+            },
+            TokenTree::Ident(i) if i == "as" => ty = true,
+            TokenTree::Ident(i)
+                if generics == 0 && !params && ends_expr(prev) && starts_field(&toks[*pos..]) =>
+            {
+                errs.extend(error(i.span(), &format!("expected `,` before `{i}`")));
+                return toks[start..*pos].to_vec();
+            }
+            _ => {}
+        }
+        *pos += 1;
+    }
+    toks[start..(*pos).min(toks.len())].to_vec()
+}
+
+/// Whether an expression can start after `prev`.
+fn starts_expr(prev: Option<&TokenTree>) -> bool {
+    match prev {
+        None => true,
+        Some(TokenTree::Punct(p)) => p.as_char() != '?',
+        Some(TokenTree::Ident(i)) => is_keyword(i),
+        _ => false,
+    }
+}
+
+/// Whether `prev` can be the last token of an expression.
+fn ends_expr(prev: Option<&TokenTree>) -> bool {
+    match prev {
+        None => false,
+        Some(TokenTree::Punct(p)) => p.as_char() == '?',
+        Some(TokenTree::Ident(i)) => !is_keyword(i),
+        Some(TokenTree::Literal(_) | TokenTree::Group(_)) => true,
+    }
+}
+
+fn is_keyword(ident: &Ident) -> bool {
+    matches!(
+        &*ident.to_string(),
+        "as" | "async"
+            | "box"
+            | "break"
+            | "const"
+            | "dyn"
+            | "else"
+            | "for"
+            | "if"
+            | "impl"
+            | "in"
+            | "let"
+            | "loop"
+            | "match"
+            | "move"
+            | "mut"
+            | "ref"
+            | "return"
+            | "static"
+            | "unsafe"
+            | "while"
+            | "yield"
+    )
+}
+
+/// Whether `toks` starts with `ident:` or `ident <-`.
+fn starts_field(toks: &[TokenTree]) -> bool {
+    match toks.get(1) {
+        Some(TokenTree::Punct(p)) if p.as_char() == ':' => p.spacing() == Spacing::Alone,
+        _ => is_arrow(&toks[1..]),
+    }
+}
+
+/// Skips the `,` after an expression. It is missing if [`take_expr`] reported a missing `,`.
+fn skip_comma(toks: &[TokenTree], pos: &mut usize) {
+    if is_punct_at(toks, *pos, ',') {
+        *pos += 1;
+    }
+}
+
+fn is_punct(tt: &TokenTree, c: char) -> bool {
+    matches!(tt, TokenTree::Punct(p) if p.as_char() == c)
+}
+
+fn is_punct_at(toks: &[TokenTree], pos: usize, c: char) -> bool {
+    toks.get(pos).is_some_and(|tt| is_punct(tt, c))
+}
+
+/// Whether `toks` starts with `<-`.
+fn is_arrow(toks: &[TokenTree]) -> bool {
+    matches!(toks, [TokenTree::Punct(p), ..] if p.as_char() == '<' && p.spacing() == Spacing::Joint)
+        && is_punct_at(toks, 1, '-')
+}
+
+/// Whether `toks` starts with `..`.
+fn is_dot_dot(toks: &[TokenTree]) -> bool {
+    matches!(toks, [TokenTree::Punct(p), ..] if p.as_char() == '.' && p.spacing() == Spacing::Joint)
+        && is_punct_at(toks, 1, '.')
+        && !is_punct_at(toks, 2, '=')
+}
+
+/// Whether `toks` is `Zeroable::zeroed()`.
+fn is_zeroed(toks: &[TokenTree]) -> bool {
+    matches!(
+        toks,
+        [TokenTree::Ident(z), TokenTree::Punct(c1), TokenTree::Punct(c2), TokenTree::Ident(f), TokenTree::Group(g)]
+            if z == "Zeroable"
+                && c1.as_char() == ':'
+                && c2.as_char() == ':'
+                && f == "zeroed"
+                && g.delimiter() == Delimiter::Parenthesis
+                && g.stream().is_empty()
+    )
+}
+
+/// The span used for the code initializing a field, it points at the user-supplied expression.
+fn span_of(toks: &[TokenTree], fallback: &Ident) -> Span {
+    let span = toks
+        .first()
+        .map_or_else(|| fallback.span(), TokenTree::span);
+    Span::mixed_site().located_at(span)
+}
+
+/// Generates the initializer for a struct.
+fn init_struct(
+    ctx: &Ctx,
+    this: Option<&Ident>,
+    path: &[TokenTree],
+    fields: &[InitField],
+    update: &Update,
+) -> TokenStream {
+    let Ctx {
+        krate,
+        pinned,
+        asynchronous,
+        err,
+        context,
+    } = ctx;
+    let (has_data, get_data, get_data_of, data_trait, construct) = if *pinned {
+        (
+            quote!(HasPinData),
+            quote!(__pin_data),
+            quote!(__pin_data_of),
+            quote!(PinData),
+            quote!(pin_init_from_closure),
+        )
+    } else {
+        (
+            quote!(HasInitData),
+            quote!(__init_data),
+            quote!(__init_data_of),
+            quote!(InitData),
+            quote!(init_from_closure),
+        )
+    };
+    // These identifiers are not accessible to the user.
+    let mixed = |name: &str| Ident::new(name, Span::mixed_site());
+    let slot = mixed("slot");
+    let data = mixed("data");
+    let init = mixed("init");
+    let value = mixed("value");
+    let defaults = mixed("defaults");
+    let names = mixed("names");
+    let base_guard = mixed("base_guard");
+    let pin_data = mixed("pin_data");
+    let field_info = mixed("field");
+    // The slot of an asynchronous initializer is wrapped in an `AsyncSlot`, a raw pointer would
+    // make the future `!Send`.
+    let ptr = if *asynchronous {
+        quote!(#slot.ptr())
+    } else {
+        quote!(#slot)
+    };
+    // With a `..` tail, the marker fields are initialized automatically, so they can be omitted.
+    let markers = matches!(update, Update::Markers);
+    // The error type of the closure.
+    let init_err = match context {
+        Some(_) => quote!(#krate::InitError<#err>),
+        None => err.clone(),
+    };
+    let type_name = quote!(#krate::__internal::#data_trait::__type_name(&#data));
+    // Adds the context of the field `field` to the error `e`.
+    let with_context = |field: &str| {
+        context.as_ref().map(|prefix| {
+            let field = Literal::string(&format!("{prefix}{field}"));
+            quote! {
+                .map_err(|e| #krate::__internal::FieldError::<#err, _>::__with_context(
+                    e,
+                    #type_name,
+                    #field,
+                ))
+            }
+        })
+    };
+
+    let zeroed = matches!(update, Update::Zeroed).then(|| {
+        quote! {
+            // Ensure that the struct is indeed `Zeroable`.
+            fn assert_zeroable<T: #krate::Zeroable>(_: *mut T) {}
+            assert_zeroable(#ptr);
+            // SAFETY: The type implements `Zeroable` by the check above.
+            unsafe { ::core::ptr::write_bytes(#ptr, 0, 1) };
+        }
+    });
+    let make_defaults = matches!(update, Update::Default).then(|| {
+        quote! {
+            // Track which fields have been initialized in the type of `defaults`.
+            let #defaults = #krate::__internal::PinData::__defaults(&#data);
+        }
+    });
+    let init_markers = markers.then(|| {
+        quote! {
+            // Track which fields have been mentioned in the type of `names`.
+            let #names = #krate::__internal::#data_trait::__fields(&#data);
+            // SAFETY: `slot` is valid. The marker fields are zero-sized and do not need to be
+            // dropped, so they can be initialized again below.
+            unsafe { #krate::__internal::HasFields::__init_markers(#ptr) };
+        }
+    });
+    let this = this.map(|this| {
+        quote! {
+            // Create the `this` so it can be referenced by the user inside of the expressions
+            // creating the individual fields.
+            let #this = unsafe { ::core::ptr::NonNull::new_unchecked(#ptr) };
+        }
+    });
+    let base = match update {
+        Update::Base(base) => {
+            let base_context = with_context("..");
+            let span = span_of(base, &slot);
+            let init_base = quote_spanned! {span=>
+                #krate::__internal::#data_trait::__init_base(&#data, #ptr, #init)
+            };
+            Some(quote! {
+                // The base initializer initializes the whole struct first, the fields are then
+                // replaced one after the other.
+                let #init = #(#base)*;
+                // SAFETY: `slot` is valid, because we are inside of an initializer closure, we
+                // return when an error/panic occurs.
+                unsafe { #init_base }#base_context?;
+                // SAFETY: We forget the guard later when initialization has succeeded.
+                #[allow(unused_mut)]
+                let mut #base_guard = unsafe { #krate::__internal::BaseGuard::new(#ptr) };
+            })
+        }
+        _ => None,
+    };
+
+    let mut guards = vec![];
+    let init_fields = fields.iter().enumerate().map(|(index, field)| {
+        let InitField { attrs, name, kind } = field;
+        let place = quote!(::core::ptr::addr_of_mut!((*#ptr).#name));
+        let (mut expr, span) = match kind {
+            FieldKind::Init(expr) | FieldKind::Nested(expr) | FieldKind::Value(expr) => {
+                (quote!(#(#expr)*), span_of(expr, name))
+            }
+            FieldKind::Shorthand => (quote!(#name), span_of(&[], name)),
+        };
+        let mut field_context = with_context(&unraw(name));
+        if let Some(prefix) = context {
+            let field = Literal::string(&format!("{prefix}{}", unraw(name)));
+            match kind {
+                // Errors returned via `?` inside of the expression belong to the field.
+                FieldKind::Init(_) | FieldKind::Value(_) => {
+                    expr = if *asynchronous {
+                        quote! {
+                            #krate::__internal::with_context_async::<_, #err>(
+                                #type_name,
+                                #field,
+                                async { ::core::result::Result::Ok(#expr) },
+                            ).await?
+                        }
+                    } else {
+                        quote! {
+                            #krate::__internal::with_context::<_, #err>(
+                                #type_name,
+                                #field,
+                                || ::core::result::Result::Ok(#expr),
+                            )?
+                        }
+                    };
+                }
+                // The fields of a nested initializer are reported as fields of this struct.
+                FieldKind::Nested(_) => {
+                    field_context = Some(quote! {
+                        .map_err(|e: #krate::InitError<#err>| #krate::InitError {
+                            type_name: #type_name,
+                            ..e
+                        })
+                    });
+                }
+                FieldKind::Shorthand => {}
+            }
+        }
+        let (compute, write) = match kind {
+            FieldKind::Init(_) | FieldKind::Nested(_) if *asynchronous => {
+                // Structurally pinned fields can also be initialized via `AsyncPinInit`.
+                let call = quote_spanned!(span=> #data.#name().__init_async(#place, #init));
+                (
+                    quote!(let #init = #expr;),
+                    quote! {
+                        // SAFETY: `slot` is valid, because we are inside of an initializer
+                        // future, we return when an error/panic occurs. When the future is
+                        // dropped, so is the future initializing the field.
+                        unsafe { #call }.await #field_context?;
+                    },
+                )
+            }
+            FieldKind::Init(_) | FieldKind::Nested(_) => {
+                let call = if *pinned {
+                    // We use the `data` to require the correct trait (`Init` or `PinInit`) for
+                    // the field.
+                    quote_spanned!(span=> #data.#name().__init(#place, #init))
+                } else {
+                    quote_spanned!(span=> #krate::Init::__init(#init, #place))
+                };
+                let call = match &field_context {
+                    Some(field_context) => quote!({ #call }#field_context),
+                    None => call,
+                };
+                (
+                    quote!(let #init = #expr;),
+                    quote! {
+                        // SAFETY: `slot` is valid, because we are inside of an initializer
+                        // closure, we return when an error/panic occurs.
+                        unsafe { #call? };
+                    },
+                )
+            }
+            _ => {
+                let value = Ident::new("value", value.span().located_at(span));
+                let write = quote_spanned!(span=> ::core::ptr::write(#place, #value));
+                (
+                    quote!(let #value = #expr;),
+                    quote! {
+                        // SAFETY: The memory at the field is uninitialized.
+                        unsafe { #write };
+                    },
+                )
+            }
+        };
+        // Reports the steps of the field with the `trace` feature, does nothing otherwise.
+        let field_name = Literal::string(&unraw(name));
+        let index = Literal::usize_unsuffixed(index);
+        let trace_field = quote! {
+            let #field_info =
+                #krate::__internal::TraceField::start(#type_name, #field_name, #index);
+        };
+        if matches!(update, Update::Base(_)) {
+            return quote! {
+                #(#attrs)*
+                {
+                    #trace_field
+                    #compute
+                    // Drop the value from the base initializer.
+                    //
+                    // SAFETY: We pass the matching field of `slot`.
+                    unsafe {
+                        #base_guard.uninit_field(|#pin_data| #pin_data.#name().index(), #place)
+                    };
+                    #write
+                    // SAFETY: The field has been initialized above.
+                    unsafe { #base_guard.init_field() };
+                    #field_info.finish();
+                }
+            };
+        }
+        let guard = format_ident!("__{}_guard", unraw(name), span = Span::mixed_site());
+        let mark = matches!(update, Update::Default).then(|| {
+            quote! {
+                // Mark the field as initialized.
+                #(#attrs)*
+                let #defaults = #defaults.#name();
+            }
+        });
+        let res = quote! {
+            #(#attrs)*
+            let #guard = {
+                #trace_field
+                #compute
+                #write
+                // Since initialization could fail later, we need to drop this field if there is
+                // an error later.
+                //
+                // SAFETY: We forget the guard later when initialization has succeeded.
+                unsafe { #krate::__internal::DropGuard::traced(#place, #field_info) }
+            };
+            #mark
+        };
+        guards.push((attrs, guard));
+        res
+    });
+    let init_fields = init_fields.collect::<Vec<_>>();
+    let forget = guards.iter().map(|(attrs, guard)| {
+        quote! {
+            #(#attrs)*
+            ::core::mem::forget(#guard);
+        }
+    });
+    let finish = match update {
+        Update::Default => quote! {
+            // Initialize all remaining fields with their default value. This only compiles if
+            // all fields without a default value have been initialized.
+            //
+            // SAFETY: `slot` is valid and the type of `defaults` tracks the initialized fields.
+            unsafe { #defaults.__init_defaults(#ptr) };
+        },
+        Update::Base(_) => quote!(::core::mem::forget(#base_guard);),
+        _ => quote!(),
+    };
+
+    // The pattern mentions every field, so the compiler reports missing fields. If the struct
+    // update syntax is used, the remaining fields have already been initialized by it.
+    let check = matches!(update, Update::None | Update::Markers).then(|| {
+        let span = path.first().map_or_else(Span::call_site, TokenTree::span);
+        // Qualified paths cannot be used in patterns, but they can be passed through a type alias.
+        // The pattern has to start at the call site, otherwise the compiler does not report the
+        // missing fields.
+        let (use_alias, pat_path) = if path.first().is_some_and(|tt| is_punct(tt, '<')) {
+            (
+                quote_spanned!(span=> use #krate::__internal::Struct as __Struct;),
+                quote_spanned!(span=> __Struct::<#(#path)*>),
+            )
+        } else {
+            (quote!(), quote!(#(#path)*))
+        };
+        let pattern = fields
+            .iter()
+            .map(|InitField { attrs, name, .. }| quote!(#(#attrs)* #name: _,));
+        if markers {
+            // The mentions of all fields that have been initialized, `__check` only compiles if
+            // every field except the marker fields is among them.
+            let names = Ident::new("names", names.span().located_at(span));
+            let mentions = fields
+                .iter()
+                .map(|InitField { attrs, name, .. }| {
+                    if attrs.is_empty() {
+                        quote_spanned!(span=> #names.#name())
+                    } else {
+                        // The field might be disabled by `#[cfg(..)]`.
+                        quote_spanned!(span=> {
+                            #[allow(unused_variables)]
+                            let mention = ();
+                            #(#attrs)*
+                            let mention = #names.#name();
+                            mention
+                        })
+                    }
+                })
+                .collect::<Vec<_>>();
+            let mentions = mention_tree(&mentions, span);
+            let check = quote_spanned!(span=> #names.__check(&(#mentions)););
+            let pattern = quote_spanned! {span=>
+                #[allow(clippy::unneeded_wildcard_pattern)]
+                let #pat_path { #(#pattern)* .. } = #value;
+            };
+            return quote! {
+                // Ensure that no field has been initialized twice and that all of them are
+                // accessible from this scope.
+                //
+                // SAFETY: All fields have been initialized.
+                let #value = unsafe { &*#ptr };
+                #use_alias
+                #pattern
+                #check
+            };
+        }
+        let check = quote_spanned!(span=> let #pat_path { #(#pattern)* } = #value;);
+        quote! {
+            // Ensure that every field has been initialized and that all of them are accessible
+            // from this scope. The compiler reports missing fields with a natural error message.
+            //
+            // SAFETY: All fields have been initialized.
+            let #value = unsafe { &*#ptr };
+            #use_alias
+            #check
+        }
+    });
+
+    let body = quote! {
+        // Shadow the structure so it cannot be used to return early.
+        struct __InitOk;
+        // Get the data again instead of capturing it, since it is not `Copy`.
+        //
+        // SAFETY: The data is only used to initialize the fields of `slot`.
+        let #data = unsafe { #krate::__internal::#has_data::#get_data_of(#ptr) };
+        #zeroed
+        #make_defaults
+        #init_markers
+        #this
+        #base
+        #(#init_fields)*
+        #finish
+        // All fields have been initialized, so we can dismiss the guards.
+        #(#forget)*
+        #check
+    };
+    let make_init = if *asynchronous {
+        quote! {
+            let #init = #krate::__internal::PinData::make_async_closure::<_, _, __InitOk, #init_err>(
+                &#data,
+                move |#slot| {
+                    let #slot = #krate::__internal::AsyncSlot::new(#slot);
+                    async move {
+                        { #body }
+                        Ok(__InitOk)
+                    }
+                }
+            );
+            let #init = move |#slot| {
+                let #init = #init(#slot);
+                async move {
+                    #krate::__internal::trace_result(#type_name, #init.await.map(|__InitOk| ()))
+                }
+            };
+            // SAFETY: The future initializes every field and drops the initialized fields on
+            // error or when it is dropped.
+            let #init = unsafe { #krate::async_pin_init_from_closure::<_, #init_err, _, _>(#init) };
+        }
+    } else {
+        quote! {
+            let #init = #krate::__internal::#data_trait::make_closure::<_, __InitOk, #init_err>(
+                &#data,
+                move |#slot| {
+                    { #body }
+                    Ok(__InitOk)
+                }
+            );
+            let #init = move |#slot| -> ::core::result::Result<(), #init_err> {
+                #krate::__internal::trace_result(#type_name, #init(#slot).map(|__InitOk| ()))
+            };
+            // SAFETY: The closure initializes every field and drops the initialized fields on error.
+            let #init = unsafe { #krate::#construct::<_, #init_err>(#init) };
+        }
+    };
+
+    quote! {{
+        // We do not want to allow arbitrary returns, so we declare this type as the `Ok` return
+        // type and shadow it later when we insert the arbitrary user code. That way there will be
+        // no possibility of returning without `unsafe`.
+        struct __InitOk;
+        // Get the data about fields from the supplied type.
+        let #data = unsafe {
+            use #krate::__internal::#has_data;
+            #(#path)*::#get_data()
+        };
+        // Ensure that `data` really is of type `PinData` or `InitData` and help with type
+        // inference:
+        #make_init
+        #init
+    }}
+}
+
+/// Arranges `mentions` in a balanced tree of pairs, which keeps the depth of the trait resolution
+/// in `__check` logarithmic in the number of fields.
+fn mention_tree(mentions: &[TokenStream], span: Span) -> TokenStream {
+    match mentions {
+        [] => quote_spanned!(span=> ()),
+        [mention] => mention.clone(),
+        _ => {
+            let (left, right) = mentions.split_at(mentions.len() / 2);
+            let left = mention_tree(left, span);
+            let right = mention_tree(right, span);
+            quote_spanned!(span=> (#left, #right))
+        }
+    }
+}
+
+/// Generates the initializer for a tuple or an array.
+fn init_literal(ctx: &Ctx, array: bool, elements: &[Element]) -> TokenStream {
+    let Ctx {
+        krate, pinned, err, ..
+    } = ctx;
+    let (init_trait, init_fn, construct) = if *pinned {
+        (
+            quote!(PinInit),
+            quote!(__pinned_init),
+            quote!(pin_init_from_closure),
+        )
+    } else {
+        (quote!(Init), quote!(__init), quote!(init_from_closure))
+    };
+    let slot = Ident::new("slot", Span::mixed_site());
+    let init = Ident::new("init", Span::mixed_site());
+    let value = Ident::new("value", Span::mixed_site());
+    let ptr = Ident::new("ptr", Span::mixed_site());
+    let type_name = Ident::new("type_name", Span::mixed_site());
+    let element = Ident::new("element", Span::mixed_site());
+
+    let ty = if array {
+        let len = Literal::usize_unsuffixed(elements.len());
+        quote!([_; #len])
+    } else {
+        let infer = elements.iter().map(|_| quote!(_,));
+        quote!((#(#infer)*))
+    };
+    let guards = (0..elements.len())
+        .map(|i| format_ident!("__guard_{}", i, span = Span::mixed_site()))
+        .collect::<Vec<_>>();
+    let init_elements = elements
+        .iter()
+        .zip(&guards)
+        .enumerate()
+        .map(|(i, (e, guard))| {
+            let idx = Literal::usize_unsuffixed(i);
+            let place = if array {
+                quote!(::core::ptr::addr_of_mut!((*#slot)[#idx]))
+            } else {
+                quote!(::core::ptr::addr_of_mut!((*#slot).#idx))
+            };
+            let init = match e {
+                Element::Init(expr) => {
+                    let span = Span::mixed_site().located_at(expr[0].span());
+                    let call = quote_spanned!(span=> #krate::#init_trait::#init_fn(#init, #ptr));
+                    quote! {
+                        let #init = #(#expr)*;
+                        // SAFETY: `slot` is valid, because we are inside of an initializer closure,
+                        // we return when an error/panic occurs.
+                        unsafe { #call? };
+                    }
+                }
+                Element::Value(expr) => {
+                    let span = expr.first().map_or_else(Span::call_site, TokenTree::span);
+                    let value = Ident::new("value", value.span().located_at(span));
+                    let write = quote_spanned!(span=> ::core::ptr::write(#ptr, #value));
+                    quote! {
+                        let #value = #(#expr)*;
+                        // SAFETY: The memory at `ptr` is uninitialized.
+                        unsafe { #write };
+                    }
+                }
+            };
+            quote! {
+                let #guard = {
+                    // Reports the steps of the element with the `trace` feature, does nothing
+                    // otherwise.
+                    let #element = #krate::__internal::TraceField::start(#type_name, "", #idx);
+                    // SAFETY: `slot` is valid, because we are inside of an initializer closure.
+                    let #ptr = unsafe { #place };
+                    #init
+                    // SAFETY: We forget the guard when all elements have been initialized.
+                    unsafe { #krate::__internal::DropGuard::traced(#ptr, #element) }
+                };
+            }
+        });
+
+    quote! {{
+        // We do not want to allow arbitrary returns, so we declare this type as the `Ok` return
+        // type and shadow it later when we insert the arbitrary user code. That way there will be
+        // no possibility of returning without `unsafe`.
+        struct __InitOk;
+        let #init = move |#slot: *mut #ty| -> ::core::result::Result<__InitOk, #err> {
+            let #type_name = #krate::__internal::type_name_of(#slot);
+            {
+                // Shadow the structure so it cannot be used to return early.
+                struct __InitOk;
+                #(#init_elements)*
+                // All elements have been initialized, so we can dismiss the guards.
+                #(::core::mem::forget(#guards);)*
+            }
+            Ok(__InitOk)
+        };
+        let #init = move |#slot| -> ::core::result::Result<(), #err> {
+            let #type_name = #krate::__internal::type_name_of(#slot);
+            #krate::__internal::trace_result(#type_name, #init(#slot).map(|__InitOk| ()))
+        };
+        // SAFETY: The closure initializes every element and drops the initialized elements on
+        // error.
+        let #init = unsafe { #krate::#construct::<_, #err>(#init) };
+        #init
+    }}
+}
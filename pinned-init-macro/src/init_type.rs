@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `#[pin_data(init_type)]`.
+
+use crate::defaults::unraw;
+use crate::pin_data::{error, error_ident, with_trailing_comma, Struct};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+/// Generates the struct `{name}Init`, a nameable initializer for the struct `name`.
+///
+/// It has a type parameter and a field for the initializer of every field, so its type only
+/// depends on the types of the initializers. It implements `PinInit` when the structurally pinned
+/// fields get a `PinInit` and all other fields an `Init`, and `Init` when all fields get an `Init`.
+/// Marker fields are initialized with their default value and do not have a field.
+pub(crate) fn impl_init_type(item: &Struct<'_>) -> TokenStream {
+    let Struct {
+        krate,
+        vis,
+        name,
+        impl_generics,
+        ty_generics,
+        whr,
+        fields,
+    } = *item;
+    // The type parameters of the fields cannot be removed by `#[cfg(..)]` in all the places where
+    // they are used.
+    if let Some(f) = fields.iter().find(|f| !f.cfgs.is_empty()) {
+        return error(
+            f.name.span(),
+            "`#[cfg(..)]` on fields is not supported by `#[pin_data(init_type)]`",
+        );
+    }
+    let impl_generics = with_trailing_comma(impl_generics);
+    let whr = with_trailing_comma(whr);
+    let err = error_ident(ty_generics);
+    let init_type = format_ident!("{}Init", unraw(name));
+    // The marker fields are initialized by the `..` tail.
+    let markers = fields.iter().any(|f| f.marker).then(|| quote!(..));
+    let fields = fields.iter().filter(|f| !f.marker).collect::<Vec<_>>();
+    let states = (0..fields.len())
+        .map(|i| format_ident!("__I{i}"))
+        .collect::<Vec<_>>();
+    let params = quote!(#(#states,)*);
+    // `Foo::<> { .. }` is not accepted by `try_pin_init!`, so the arguments are only added when
+    // there are any.
+    let path = if ty_generics.is_empty() {
+        quote!(#name)
+    } else {
+        quote!(#name::<#(#ty_generics)*>)
+    };
+    let decls = fields.iter().zip(&states).map(|(f, state)| {
+        let field = &f.name;
+        let fvis = &f.vis;
+        let doc = format!(" The initializer of the `{}` field.", unraw(field));
+        quote! {
+            #[doc = #doc]
+            #(#fvis)* #field: #state,
+        }
+    });
+    let field_inits = fields.iter().map(|f| {
+        let field = &f.name;
+        quote!(#field <- self.#field,)
+    });
+    let field_inits = quote!(#(#field_inits)*);
+    let bounds = |pin: bool| {
+        let bounds = fields.iter().zip(&states).map(|(f, state)| {
+            let ty = &f.ty;
+            if pin && f.pinned {
+                quote!(#state: #krate::PinInit<#(#ty)*, #err>,)
+            } else {
+                quote!(#state: #krate::Init<#(#ty)*, #err>,)
+            }
+        });
+        quote!(#(#bounds)*)
+    };
+    let pin_bounds = bounds(true);
+    let init_bounds = bounds(false);
+    let doc = format!(
+        " An initializer for [`{name}`] with a nameable type.\n\n \
+          It holds an initializer for every field in the field of the same name and has a type \
+          parameter for each of them, in the order of the fields."
+    );
+    let name_ty = quote!(#name<#(#ty_generics)*>);
+    quote! {
+        #[doc = #doc]
+        #(#vis)* struct #init_type<#params> {
+            #(#decls)*
+        }
+
+        // SAFETY: `__pinned_init` forwards to the initializer created by `try_pin_init!`.
+        unsafe impl<#(#impl_generics)* #err, #params> #krate::PinInit<#name_ty, #err>
+            for #init_type<#params>
+        where
+            #(#whr)*
+            #pin_bounds
+        {
+            #[inline]
+            unsafe fn __pinned_init(
+                self,
+                slot: *mut #name_ty,
+            ) -> ::core::result::Result<(), #err> {
+                let init = #krate::try_pin_init!(#path {
+                    #field_inits
+                    #markers
+                }? #err);
+                // SAFETY: The caller upholds the requirements of `__pinned_init`.
+                unsafe { #krate::PinInit::__pinned_init(init, slot) }
+            }
+        }
+
+        // SAFETY: `__init` forwards to the initializer created by `try_init!`.
+        unsafe impl<#(#impl_generics)* #err, #params> #krate::Init<#name_ty, #err>
+            for #init_type<#params>
+        where
+            #(#whr)*
+            #init_bounds
+        {
+            #[inline]
+            unsafe fn __init(self, slot: *mut #name_ty) -> ::core::result::Result<(), #err> {
+                let init = #krate::try_init!(#path {
+                    #field_inits
+                    #markers
+                }? #err);
+                // SAFETY: The caller upholds the requirements of `__init`.
+                unsafe { #krate::Init::__init(init, slot) }
+            }
+        }
+    }
+}
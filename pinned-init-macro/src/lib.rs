@@ -1,5 +1,12 @@
+mod builder;
+mod constructor;
+mod defaults;
+mod fields;
+mod init;
+mod init_type;
 mod pin_data;
 mod pinned_drop;
+mod repr;
 mod zeroable;
 
 use proc_macro::TokenStream;
@@ -18,6 +25,50 @@ use proc_macro::TokenStream;
 /// macro, and change your `Drop` implementation to `PinnedDrop` annotated with
 /// `#[`[`macro@pinned_drop`]`]`, since dropping pinned values requires extra care.
 ///
+/// Fields can be given a default value with `#[default(value)]` or an initializer that cannot fail
+/// with `#[default_init(initializer)]`. These fields can then be omitted from [`pin_init!`] when
+/// it ends with `..Default`.
+///
+/// Marker fields, i.e. fields of type `PhantomPinned` or `PhantomData<T>` and fields annotated
+/// with `#[pin_data(marker)]`, can be omitted from [`init!`] and [`pin_init!`] when they end with
+/// `..` and take no parameter in `constructor`. They are initialized with [`Default::default`] and
+/// have to be zero-sized and must not need to be dropped.
+///
+/// Fields can be annotated with `#[cfg(..)]`. The initializers have to put the same attribute on
+/// the field, so that it is only initialized when it exists. `constructor` only takes parameters
+/// for the enabled fields, while `builder` and `init_type` do not support `#[cfg(..)]` on fields.
+///
+/// With `constructor` as an argument, this macro also generates a `new` function that takes an
+/// initializer for every field (`PinInit` for fields marked with `#[pin]`, `Init` for the rest) and
+/// returns an initializer for the whole struct.
+///
+/// With `builder` as an argument, this macro also generates a `builder` function returning a
+/// builder with a setter for every field. Each setter can only be called once and `build` is only
+/// available once all fields without a default value have been set, so forgetting a field is a
+/// compile error. Arguments are separated by commas, e.g. `#[pin_data(PinnedDrop, builder)]`.
+///
+/// With `init_type` as an argument, this macro also generates the struct `{Name}Init` with a type
+/// parameter and a field for the initializer of every field except the marker fields. Unlike the
+/// initializers returned by [`pin_init!`], its type can be named, e.g. in a `static`, a `const` or
+/// an associated type. It implements `PinInit` and, if every field gets an `Init`, also `Init`.
+///
+/// The struct is `Unpin` if all structurally pinned fields are `Unpin`. With `!Unpin` as an
+/// argument it is never `Unpin`, even without a `PhantomPinned` field. With `UnsafeUnpin` it is
+/// `Unpin` exactly when it implements `UnsafeUnpin`, which allows writing the conditions manually.
+/// A manual `Unpin` implementation is rejected in all cases.
+///
+/// Structs with `#[repr(packed)]` cannot have `#[pin]` fields, since they might be unaligned. For a
+/// `#[repr(transparent)]` struct with a single field besides its marker fields, this macro
+/// generates a `transparent` function with the visibility of the field. It turns an initializer of
+/// the field into one of the struct, a `PinInit` if the field is marked with `#[pin]` and an `Init`
+/// otherwise. The marker fields are initialized with their default value. Other
+/// `#[repr(transparent)]` structs are rejected, zero-sized fields that are not recognized as marker
+/// fields need `#[pin_data(marker)]`.
+///
+/// The generated code refers to `::pinned_init` by default. When the crate is only reachable
+/// through a re-export, pass its path with `crate = path`, e.g.
+/// `#[pin_data(PinnedDrop, crate = mycore::init)]`.
+///
 /// # Examples
 ///
 /// ```rust,ignore
@@ -46,6 +97,73 @@ use proc_macro::TokenStream;
 /// }
 /// ```
 ///
+/// ```rust,ignore
+/// #[pin_data]
+/// struct DriverData {
+///     #[pin]
+///     #[default_init(new_mutex!(Vec::new()))]
+///     queue: Mutex<Vec<Command>>,
+///     #[default(0)]
+///     retries: usize,
+///     buf: Box<[u8; 1024 * 1024]>,
+/// }
+///
+/// let init = pin_init!(DriverData {
+///     buf: Box::new([0; 1024 * 1024]),
+///     ..Default
+/// });
+/// ```
+///
+/// ```rust,ignore
+/// #[pin_data(constructor)]
+/// struct DriverData {
+///     #[pin]
+///     queue: Mutex<Vec<Command>>,
+///     buf: Box<[u8; 1024 * 1024]>,
+/// }
+///
+/// // fn new<E>(
+/// //     queue: impl PinInit<Mutex<Vec<Command>>, E>,
+/// //     buf: impl Init<Box<[u8; 1024 * 1024]>, E>,
+/// // ) -> impl PinInit<DriverData, E>
+/// let init = DriverData::new(new_mutex!(Vec::new()), Box::new([0; 1024 * 1024]));
+/// ```
+///
+/// ```rust,ignore
+/// #[pin_data(builder)]
+/// struct DriverData {
+///     #[pin]
+///     queue: Mutex<Vec<Command>>,
+///     #[default(0)]
+///     retries: usize,
+///     buf: Box<[u8; 1024 * 1024]>,
+/// }
+///
+/// let init = DriverData::builder::<Infallible>()
+///     .queue(new_mutex!(Vec::new()))
+///     .buf(Box::new([0; 1024 * 1024]))
+///     .build();
+/// ```
+///
+/// ```rust,ignore
+/// #[pin_data(init_type)]
+/// struct Stats {
+///     hits: AtomicUsize,
+///     misses: AtomicUsize,
+/// }
+///
+/// // struct StatsInit<__I0, __I1> {
+/// //     hits: __I0,
+/// //     misses: __I1,
+/// // }
+/// static STATS: StaticInit<Stats, StatsInit<AtomicUsize, AtomicUsize>> =
+///     StaticInit::new(StatsInit {
+///         hits: AtomicUsize::new(0),
+///         misses: AtomicUsize::new(0),
+///     });
+/// ```
+///
+/// [`init!`]: ../pinned_init/macro.init.html
 /// [`pin_init!`]: ../pinned_init/macro.pin_init.html
 //  ^ cannot use direct link, since `kernel` is not a dependency of `macros`
 #[proc_macro_attribute]
@@ -55,7 +173,9 @@ pub fn pin_data(inner: TokenStream, item: TokenStream) -> TokenStream {
 
 /// Used to implement `PinnedDrop` safely.
 ///
-/// Only works on structs that are annotated via `#[`[`macro@pin_data`]`]`.
+/// Only works on structs that are annotated via `#[`[`macro@pin_data`]`]`. Like that macro, it
+/// accepts `crate = path` when `pinned_init` is only reachable through a re-export, e.g.
+/// `#[pinned_drop(crate = mycore::init)]`.
 ///
 /// # Examples
 ///
@@ -84,6 +204,9 @@ pub fn pinned_drop(args: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// This can only be used for structs where every field implements the [`Zeroable`] trait.
 ///
+/// When `pinned_init` is only reachable through a re-export, add `#[zeroable(crate = path)]` to
+/// the struct, e.g. `#[zeroable(crate = mycore::init)]`.
+///
 /// # Examples
 ///
 /// ```rust,ignore
@@ -94,7 +217,15 @@ pub fn pinned_drop(args: TokenStream, input: TokenStream) -> TokenStream {
 ///     len: usize,
 /// }
 /// ```
-#[proc_macro_derive(Zeroable)]
+#[proc_macro_derive(Zeroable, attributes(zeroable))]
 pub fn derive_zeroable(input: TokenStream) -> TokenStream {
     zeroable::derive(input.into()).into()
 }
+
+/// Implementation of the `[try_][pin_]init!` and `[try_]async_pin_init!` macros, use those
+/// instead.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __init(input: TokenStream) -> TokenStream {
+    init::init(input.into()).into()
+}
@@ -1,8 +1,11 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use proc_macro2::{Group, Punct, Spacing, TokenStream, TokenTree};
-use quote::quote;
+use crate::{builder, constructor, defaults, fields, init_type, repr};
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+use std::ops::Range;
 
+#[derive(Default)]
 pub(crate) struct Generics {
     pub(crate) decl_generics: Vec<TokenTree>,
     pub(crate) impl_generics: Vec<TokenTree>,
@@ -13,101 +16,43 @@ pub(crate) struct Generics {
 ///
 /// The generics are not present in the rest, but a where clause might remain.
 pub(crate) fn parse_generics(input: TokenStream) -> (Generics, Vec<TokenTree>) {
+    let mut rest = input.into_iter().collect::<Vec<_>>();
+    // The generics start at the first `<`, unless we find the where clause before it.
+    let open = rest.iter().position(|tt| {
+        matches!(tt, TokenTree::Punct(p) if p.as_char() == '<')
+            || matches!(tt, TokenTree::Ident(i) if i == "where")
+    });
+    let Some((open, close)) = open
+        .filter(|&open| matches!(rest[open], TokenTree::Punct(_)))
+        .and_then(|open| Some((open, closing_angle(&rest, open)?)))
+    else {
+        // Either there are no generics or they are not closed, in the latter case the compiler
+        // reports the error.
+        return (Generics::default(), rest);
+    };
     // The generics with bounds and default values.
-    let mut decl_generics = vec![];
+    let decl_generics = rest.drain(open..=close).collect::<Vec<_>>();
+    let decl_generics = decl_generics[1..decl_generics.len() - 1].to_vec();
     // `impl_generics`, the declared generics with their bounds.
     let mut impl_generics = vec![];
     // Only the names of the generics, without any bounds.
     let mut ty_generics = vec![];
-    // Tokens not related to the generics e.g. the `where` token and definition.
-    let mut rest = vec![];
-    // The current level of `<`.
-    let mut nesting = 0;
-    let mut toks = input.into_iter();
-    // If we are at the beginning of a generic parameter.
-    let mut at_start = true;
-    let mut skip_until_comma = false;
-    while let Some(tt) = toks.next() {
-        if nesting == 1 && matches!(&tt, TokenTree::Punct(p) if p.as_char() == '>') {
-            // Found the end of the generics.
-            break;
-        } else if nesting >= 1 {
-            decl_generics.push(tt.clone());
-        }
-        match tt.clone() {
-            TokenTree::Punct(p) if p.as_char() == '<' => {
-                if nesting >= 1 && !skip_until_comma {
-                    // This is inside of the generics and part of some bound.
-                    impl_generics.push(tt);
-                }
-                nesting += 1;
-            }
-            TokenTree::Punct(p) if p.as_char() == '>' => {
-                // This is a parsing error, so we just end it here.
-                if nesting == 0 {
-                    break;
-                } else {
-                    nesting -= 1;
-                    if nesting >= 1 && !skip_until_comma {
-                        // We are still inside of the generics and part of some bound.
-                        impl_generics.push(tt);
-                    }
-                }
-            }
-            TokenTree::Punct(p) if skip_until_comma && p.as_char() == ',' => {
-                if nesting == 1 {
-                    impl_generics.push(tt.clone());
-                    impl_generics.push(tt);
-                    skip_until_comma = false;
-                }
-            }
-            _ if !skip_until_comma => {
-                match nesting {
-                    // If we haven't entered the generics yet, we still want to keep these tokens.
-                    0 => rest.push(tt),
-                    1 => {
-                        // Here depending on the token, it might be a generic variable name.
-                        match tt.clone() {
-                            TokenTree::Ident(i) if at_start && i.to_string() == "const" => {
-                                let Some(name) = toks.next() else {
-                                    // Parsing error.
-                                    break;
-                                };
-                                impl_generics.push(tt);
-                                impl_generics.push(name.clone());
-                                ty_generics.push(name.clone());
-                                decl_generics.push(name);
-                                at_start = false;
-                            }
-                            TokenTree::Ident(_) if at_start => {
-                                impl_generics.push(tt.clone());
-                                ty_generics.push(tt);
-                                at_start = false;
-                            }
-                            TokenTree::Punct(p) if p.as_char() == ',' => {
-                                impl_generics.push(tt.clone());
-                                ty_generics.push(tt);
-                                at_start = true;
-                            }
-                            // Lifetimes begin with `'`.
-                            TokenTree::Punct(p) if p.as_char() == '\'' && at_start => {
-                                impl_generics.push(tt.clone());
-                                ty_generics.push(tt);
-                            }
-                            // Generics can have default values, we skip these.
-                            TokenTree::Punct(p) if p.as_char() == '=' => {
-                                skip_until_comma = true;
-                            }
-                            _ => impl_generics.push(tt),
-                        }
-                    }
-                    _ => impl_generics.push(tt),
-                }
-            }
-            _ => {}
+    for (i, param) in split_generics(&decl_generics).into_iter().enumerate() {
+        if i != 0 {
+            impl_generics.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+            ty_generics.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+        }
+        // Generics can have default values, these are only allowed on the declaration.
+        let end = outside_angles(param)
+            .find(|(_, tt)| matches!(tt, TokenTree::Punct(p) if p.as_char() == '='))
+            .map_or(param.len(), |(i, _)| i);
+        impl_generics.extend_from_slice(&param[..end]);
+        let (kind, mut name) = generic_param(param);
+        if kind == GenericKind::Const {
+            name.start += 1;
         }
+        ty_generics.extend_from_slice(&param[name]);
     }
-    rest.extend(toks);
     (
         Generics {
             impl_generics,
@@ -118,11 +63,100 @@ pub(crate) fn parse_generics(input: TokenStream) -> (Generics, Vec<TokenTree>) {
     )
 }
 
+/// The kind of a generic parameter.
+#[derive(PartialEq, Eq)]
+pub(crate) enum GenericKind {
+    Lifetime,
+    Type,
+    Const,
+}
+
+/// Returns the kind of the generic parameter `param` and the range of the tokens of its name,
+/// e.g. `'a`, `T` or `const N`, which come after any attributes.
+pub(crate) fn generic_param(param: &[TokenTree]) -> (GenericKind, Range<usize>) {
+    let mut start = 0;
+    while matches!(param.get(start), Some(TokenTree::Punct(p)) if p.as_char() == '#') {
+        start += 2;
+    }
+    let (kind, len) = match param.get(start) {
+        Some(TokenTree::Punct(p)) if p.as_char() == '\'' => (GenericKind::Lifetime, 2),
+        Some(TokenTree::Ident(i)) if i == "const" => (GenericKind::Const, 2),
+        _ => (GenericKind::Type, 1),
+    };
+    let end = (start + len).min(param.len());
+    (kind, start.min(end)..end)
+}
+
+/// Splits `generics` into the individual parameters at the commas that are not nested inside of
+/// `<>`.
+pub(crate) fn split_generics(generics: &[TokenTree]) -> Vec<&[TokenTree]> {
+    let mut params = vec![];
+    let mut start = 0;
+    for (i, _) in outside_angles(generics)
+        .filter(|(_, tt)| matches!(tt, TokenTree::Punct(p) if p.as_char() == ','))
+    {
+        params.push(&generics[start..i]);
+        start = i + 1;
+    }
+    if start < generics.len() {
+        params.push(&generics[start..]);
+    }
+    params
+}
+
+/// Returns the tokens of `toks` that are not nested inside of `<>`, together with their index.
+///
+/// The `<` and `>` tokens themselves are skipped, except for the `>` of `->` (e.g. in a
+/// `F: Fn() -> T` bound), which does not close anything.
+pub(crate) fn outside_angles(toks: &[TokenTree]) -> impl Iterator<Item = (usize, &TokenTree)> {
+    let mut nesting = 0usize;
+    toks.iter().enumerate().filter(move |&(i, tt)| match tt {
+        TokenTree::Punct(p) if p.as_char() == '<' => {
+            nesting += 1;
+            false
+        }
+        TokenTree::Punct(p) if p.as_char() == '>' && !is_arrow(toks, i) => {
+            nesting = nesting.saturating_sub(1);
+            false
+        }
+        _ => nesting == 0,
+    })
+}
+
+/// Returns the index of the `>` that closes the `<` at `toks[open]`.
+pub(crate) fn closing_angle(toks: &[TokenTree], open: usize) -> Option<usize> {
+    let mut nesting = 0usize;
+    for (i, tt) in toks.iter().enumerate().skip(open) {
+        match tt {
+            TokenTree::Punct(p) if p.as_char() == '<' => nesting += 1,
+            TokenTree::Punct(p) if p.as_char() == '>' && !is_arrow(toks, i) => {
+                nesting -= 1;
+                if nesting == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns `true` if `toks[i]` is the `>` of `->`.
+fn is_arrow(toks: &[TokenTree], i: usize) -> bool {
+    i > 0
+        && matches!(
+            &toks[i - 1],
+            TokenTree::Punct(p) if p.as_char() == '-' && p.spacing() == Spacing::Joint
+        )
+}
+
 pub(crate) fn pin_data(
     args: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    let args: TokenStream = args.into();
+    // Errors that occur when parsing the arguments or replacing `Self` with `struct_name`.
+    let mut errs = TokenStream::new();
+    let (args, opts) = parse_args(args.into(), &mut errs);
     // This proc-macro only does some pre-parsing and then delegates the actual parsing to
     // `pinned_init::__pin_data!`.
 
@@ -139,8 +173,6 @@ pub(crate) fn pin_data(
     // new type as opposed to this struct definition. Therefore we have to replace `Self` with the
     // concrete name.
 
-    // Errors that occur when replacing `Self` with `struct_name`.
-    let mut errs = TokenStream::new();
     // The name of the struct with ty_generics.
     let struct_name = rest
         .iter()
@@ -190,19 +222,217 @@ pub(crate) fn pin_data(
         .collect::<Vec<_>>();
     // This should be the body of the struct `{...}`.
     let last = rest.pop();
-    let mut quoted = quote!(::pinned_init::__pin_data! {
+    let body = match &last {
+        Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Brace => body,
+        _ => {
+            errs.extend(error(
+                last.map_or_else(Span::call_site, |tt| tt.span()),
+                "`#[pin_data]` can only be used on structs with named fields",
+            ));
+            return errs.into();
+        }
+    };
+    let (body, fields) = parse_fields(body, &mut errs);
+    let Some(fields) = fields else {
+        // The compiler reports the syntax error in the body.
+        errs.extend(quote!(#(#rest)* #body));
+        return errs.into();
+    };
+    // `#[default(..)]` and `#[default_init(..)]` are not real attributes, so we remove them here and
+    // generate the `HasDefaults` implementation ourselves.
+    let mut extra = TokenStream::new();
+    let struct_pos = rest
+        .iter()
+        .position(|tt| matches!(tt, TokenTree::Ident(i) if i == "struct"));
+    if let Some(struct_pos) = struct_pos {
+        // The visibility is right in front of `struct`, after the attributes.
+        let mut vis_pos = 0;
+        while matches!(rest.get(vis_pos), Some(TokenTree::Punct(p)) if p.as_char() == '#') {
+            vis_pos += 2;
+        }
+        let whr = rest
+            .iter()
+            .skip_while(|tt| !matches!(tt, TokenTree::Ident(i) if i == "where"))
+            .skip(1)
+            .cloned()
+            .collect::<Vec<_>>();
+        let hints = repr::repr_hints(rest.get(..vis_pos).unwrap_or_default());
+        errs.extend(repr::deny_packed(&hints, &fields));
+        if let Some(TokenTree::Ident(name)) = rest.get(struct_pos + 1) {
+            let item = Struct {
+                krate: &opts.krate,
+                vis: rest.get(vis_pos..struct_pos).unwrap_or_default(),
+                name,
+                impl_generics: &impl_generics,
+                ty_generics: &ty_generics,
+                whr: &whr,
+                fields: &fields,
+            };
+            // Only structs with marker fields support the `..` tail.
+            if fields.iter().any(|f| f.marker) {
+                extra.extend(fields::impl_has_fields(&item));
+            }
+            // Marker fields alone do not need `..Default`, they can be omitted anyway.
+            if fields.iter().any(|f| f.default.is_some() && !f.marker) {
+                extra.extend(defaults::impl_has_defaults(&item));
+            }
+            if opts.constructor {
+                extra.extend(constructor::impl_constructor(&item));
+            }
+            if opts.builder {
+                extra.extend(builder::impl_builder(&item));
+            }
+            if opts.init_type {
+                extra.extend(init_type::impl_init_type(&item));
+            }
+            extra.extend(repr::impl_transparent(&hints, &item));
+        }
+    }
+    // We sort the fields into pinned and not pinned fields here, so `__pin_data!` does not need to
+    // recurse once per field.
+    let decl = |f: &Field| {
+        let Field {
+            attrs,
+            vis,
+            name,
+            ty,
+            ..
+        } = f;
+        quote!(#(#attrs)* #(#vis)* #name: #(#ty)*,)
+    };
+    let all = fields.iter().map(decl);
+    // The projections also need the position of their field for `__drop_fields_except`.
+    let indexed = |(index, f): (usize, &Field)| {
+        let Field {
+            attrs,
+            vis,
+            name,
+            ty,
+            ..
+        } = f;
+        let index = Literal::usize_unsuffixed(index);
+        quote!(#(#attrs)* #(#vis)* #name: #(#ty)* = #index,)
+    };
+    let pinned = fields.iter().enumerate().filter(|(_, f)| f.pinned);
+    let pinned = pinned.map(indexed);
+    let not_pinned = fields.iter().enumerate().filter(|(_, f)| !f.pinned);
+    let not_pinned = not_pinned.map(indexed);
+    let indices = fields.iter().enumerate().map(|(index, f)| {
+        let cfgs = f.cfg_attrs();
+        let name = &f.name;
+        let index = Literal::usize_unsuffixed(index);
+        quote!(#cfgs #name = #index,)
+    });
+    // The `Unpin` impl puts the types of the pinned fields into a tuple, unless some of them have
+    // `#[cfg(..)]` attributes.
+    let pinned_types = fields
+        .iter()
+        .filter(|f| f.pinned)
+        .all(|f| f.cfgs.is_empty())
+        .then(|| {
+            let types = fields.iter().filter(|f| f.pinned).map(|f| &f.ty);
+            quote!([#(#(#types)*),*])
+        });
+    let krate = &opts.krate;
+    let unpin = &opts.unpin;
+    let mut quoted = quote!(#krate::__pin_data! {
         parse_input:
         @args(#args),
+        @unpin(#unpin),
         @sig(#(#rest)*),
         @impl_generics(#(#impl_generics)*),
         @ty_generics(#(#ty_generics)*),
         @decl_generics(#(#decl_generics)*),
-        @body(#last),
+        @fields(#(#all)*),
+        @indices(#(#indices)*),
+        @pinned(#(#pinned)*),
+        @not_pinned(#(#not_pinned)*),
+        @pinned_types(#pinned_types),
     });
+    quoted.extend(extra);
     quoted.extend(errs);
     quoted.into()
 }
 
+/// The arguments to `#[pin_data]` that are handled by this proc-macro.
+struct Options {
+    /// `constructor`: generate `fn new`.
+    constructor: bool,
+    /// `builder`: generate `fn builder`.
+    builder: bool,
+    /// `init_type`: generate the initializer struct `{name}Init`.
+    init_type: bool,
+    /// `crate = path`: the path to the `pinned_init` crate, used by all generated code.
+    krate: TokenStream,
+    /// `!Unpin` or `UnsafeUnpin`: how to implement `Unpin`.
+    unpin: TokenStream,
+}
+
+/// Removes the arguments handled by this proc-macro from `args`.
+///
+/// Returns the remaining arguments and the handled ones. Errors are appended to `errs`.
+fn parse_args(args: TokenStream, errs: &mut TokenStream) -> (TokenStream, Options) {
+    let mut opts = Options {
+        constructor: false,
+        builder: false,
+        init_type: false,
+        krate: quote!(::pinned_init),
+        unpin: TokenStream::new(),
+    };
+    let mut rest = vec![];
+    let mut toks = args.into_iter().peekable();
+    while toks.peek().is_some() {
+        let arg = toks
+            .by_ref()
+            .take_while(|tt| !matches!(tt, TokenTree::Punct(p) if p.as_char() == ','))
+            .collect::<Vec<_>>();
+        if let Some(krate) = crate_path(&arg) {
+            opts.krate = krate;
+            continue;
+        }
+        match &arg[..] {
+            [TokenTree::Ident(i)] if i == "constructor" => opts.constructor = true,
+            [TokenTree::Ident(i)] if i == "builder" => opts.builder = true,
+            [TokenTree::Ident(i)] if i == "init_type" => opts.init_type = true,
+            [TokenTree::Punct(p), TokenTree::Ident(i)] if p.as_char() == '!' && i == "Unpin" => {
+                set_unpin(&mut opts.unpin, arg, errs)
+            }
+            [TokenTree::Ident(i)] if i == "UnsafeUnpin" => set_unpin(&mut opts.unpin, arg, errs),
+            _ => rest.push(arg.into_iter().collect::<TokenStream>()),
+        }
+    }
+    let rest = rest.into_iter().enumerate().flat_map(|(i, arg)| {
+        let comma = (i != 0).then(|| TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+        comma.into_iter().chain(arg)
+    });
+    (rest.collect(), opts)
+}
+
+/// Records the `!Unpin` or `UnsafeUnpin` argument `arg`, only one of them may be given.
+fn set_unpin(unpin: &mut TokenStream, arg: Vec<TokenTree>, errs: &mut TokenStream) {
+    if unpin.is_empty() {
+        unpin.extend(arg);
+    } else {
+        errs.extend(error(
+            arg[0].span(),
+            "`#[pin_data]` accepts only one of `!Unpin` and `UnsafeUnpin`",
+        ));
+    }
+}
+
+/// Returns the path of a `crate = path` argument, which names the `pinned_init` crate in the
+/// generated code. This is needed when it is only reachable through a re-export.
+pub(crate) fn crate_path(arg: &[TokenTree]) -> Option<TokenStream> {
+    match arg {
+        [TokenTree::Ident(i), TokenTree::Punct(p), path @ ..]
+            if i == "crate" && p.as_char() == '=' && !path.is_empty() =>
+        {
+            Some(path.iter().cloned().collect())
+        }
+        _ => None,
+    }
+}
+
 /// Replaces `Self` with `struct_name` and errors on `enum`, `trait`, `struct` `union` and `impl`
 /// keywords.
 ///
@@ -237,12 +467,300 @@ fn replace_self_and_deny_type_defs(
         }
         TokenTree::Ident(i) if i.to_string() == "Self" => struct_name.clone(),
         TokenTree::Literal(_) | TokenTree::Punct(_) | TokenTree::Ident(_) => vec![tt],
-        TokenTree::Group(g) => vec![TokenTree::Group(Group::new(
-            g.delimiter(),
-            g.stream()
-                .into_iter()
-                .flat_map(|tt| replace_self_and_deny_type_defs(struct_name, tt, errs))
-                .collect(),
-        ))],
+        TokenTree::Group(g) => {
+            let mut group = Group::new(
+                g.delimiter(),
+                g.stream()
+                    .into_iter()
+                    .flat_map(|tt| replace_self_and_deny_type_defs(struct_name, tt, errs))
+                    .collect(),
+            );
+            group.set_span(g.span());
+            vec![TokenTree::Group(group)]
+        }
+    }
+}
+
+/// The default value of a field, declared via `#[default(..)]` or `#[default_init(..)]`.
+pub(crate) enum FieldDefault {
+    /// `#[default(value)]`
+    Value(TokenStream),
+    /// `#[default_init(initializer)]`
+    Init(TokenStream),
+}
+
+/// The parts of a struct annotated with `#[pin_data]` that are needed to generate additional
+/// items.
+pub(crate) struct Struct<'a> {
+    /// The path to the `pinned_init` crate.
+    pub(crate) krate: &'a TokenStream,
+    pub(crate) vis: &'a [TokenTree],
+    pub(crate) name: &'a Ident,
+    pub(crate) impl_generics: &'a [TokenTree],
+    pub(crate) ty_generics: &'a [TokenTree],
+    pub(crate) whr: &'a [TokenTree],
+    pub(crate) fields: &'a [Field],
+}
+
+/// A named field of a struct annotated with `#[pin_data]`.
+pub(crate) struct Field {
+    /// The attributes of the field, without `#[pin]`, `#[pin_data(marker)]`, `#[default(..)]` and
+    /// `#[default_init(..)]`.
+    pub(crate) attrs: Vec<TokenTree>,
+    pub(crate) vis: Vec<TokenTree>,
+    pub(crate) name: Ident,
+    pub(crate) ty: Vec<TokenTree>,
+    pub(crate) pinned: bool,
+    /// Whether the field is a zero-sized marker (`PhantomPinned`, `PhantomData<T>` or annotated
+    /// with `#[pin_data(marker)]`), which initializers may omit.
+    pub(crate) marker: bool,
+    pub(crate) default: Option<FieldDefault>,
+    /// The predicates of the `#[cfg(..)]` attributes of the field. They have to be applied to all
+    /// generated code that mentions the field.
+    pub(crate) cfgs: Vec<TokenStream>,
+}
+
+/// Parses the fields of the struct body `body`.
+///
+/// Returns the body with `#[pin]`, `#[pin_data(marker)]`, `#[default(..)]` and
+/// `#[default_init(..)]` removed, since they are not real attributes. The fields are `None` if the
+/// body could not be parsed, in that case the compiler reports the error when it sees the returned
+/// body. Errors are appended to `errs`.
+fn parse_fields(body: &Group, errs: &mut TokenStream) -> (Group, Option<Vec<Field>>) {
+    let mut out = vec![];
+    let mut fields = Some(vec![]);
+    let mut toks = body.stream().into_iter().peekable();
+    while toks.peek().is_some() {
+        let mut attrs = vec![];
+        let mut pinned = false;
+        let mut marker = false;
+        let mut default = None;
+        let mut cfgs = vec![];
+        // Attributes.
+        while matches!(toks.peek(), Some(TokenTree::Punct(p)) if p.as_char() == '#') {
+            let hash = toks.next().unwrap();
+            let Some(TokenTree::Group(attr)) = toks.next() else {
+                // Not a valid attribute, let the compiler report the error.
+                out.push(hash);
+                fields = None;
+                break;
+            };
+            let mut attr_toks = attr.stream().into_iter();
+            let name = match attr_toks.next() {
+                Some(TokenTree::Ident(i)) => i.to_string(),
+                _ => String::new(),
+            };
+            let value = attr_toks.next();
+            let kind = match name.as_str() {
+                "default" => FieldDefault::Value,
+                "default_init" => FieldDefault::Init,
+                "pin" if value.is_none() => {
+                    pinned = true;
+                    continue;
+                }
+                "pin_data" => {
+                    match value {
+                        Some(TokenTree::Group(g))
+                            if g.delimiter() == Delimiter::Parenthesis
+                                && g.stream().to_string() == "marker"
+                                && attr_toks.next().is_none() =>
+                        {
+                            marker = true;
+                        }
+                        _ => errs.extend(error(attr.span(), "expected `#[pin_data(marker)]`")),
+                    }
+                    continue;
+                }
+                _ => {
+                    if let ("cfg", Some(TokenTree::Group(g))) = (name.as_str(), &value) {
+                        cfgs.push(g.stream());
+                    }
+                    attrs.push(hash);
+                    attrs.push(TokenTree::Group(attr));
+                    continue;
+                }
+            };
+            match value {
+                Some(TokenTree::Group(g))
+                    if g.delimiter() == Delimiter::Parenthesis && attr_toks.next().is_none() =>
+                {
+                    if default.is_some() {
+                        errs.extend(error(
+                            attr.span(),
+                            "a field can only have one `#[default(..)]` or `#[default_init(..)]` \
+                                attribute",
+                        ));
+                    }
+                    default = Some(kind(g.stream()));
+                }
+                _ => errs.extend(error(attr.span(), &format!("expected `#[{name}(..)]`"))),
+            }
+        }
+        out.extend(attrs.iter().cloned());
+        // Visibility.
+        let mut vis = vec![];
+        if matches!(toks.peek(), Some(TokenTree::Ident(i)) if i == "pub") {
+            vis.push(toks.next().unwrap());
+            if matches!(toks.peek(), Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis)
+            {
+                vis.push(toks.next().unwrap());
+            }
+        }
+        out.extend(vis.iter().cloned());
+        let name = match toks.next() {
+            Some(TokenTree::Ident(name)) => name,
+            Some(tt) => {
+                // Not a named field, the compiler will report the error.
+                out.push(tt);
+                out.extend(toks);
+                fields = None;
+                break;
+            }
+            None => break,
+        };
+        out.push(TokenTree::Ident(name.clone()));
+        // The `:`.
+        match toks.next() {
+            Some(TokenTree::Punct(p)) if p.as_char() == ':' => out.push(TokenTree::Punct(p)),
+            tt => {
+                out.extend(tt);
+                out.extend(toks);
+                fields = None;
+                break;
+            }
+        }
+        // The type extends until the next `,` that is not inside of generics.
+        let mut ty = vec![];
+        let mut nesting = 0;
+        let mut prev_dash = false;
+        while let Some(tt) = toks.next() {
+            match &tt {
+                TokenTree::Punct(p) if p.as_char() == ',' && nesting == 0 => {
+                    out.push(tt);
+                    break;
+                }
+                TokenTree::Punct(p) if p.as_char() == '<' => nesting += 1,
+                // Ignore the `>` of `->`.
+                TokenTree::Punct(p) if p.as_char() == '>' && !prev_dash => nesting -= 1,
+                // A field after a missing `,`, the compiler will report the error.
+                TokenTree::Ident(_)
+                    if nesting == 0
+                        && !ty.is_empty()
+                        && matches!(toks.peek(), Some(TokenTree::Punct(p)) if p.as_char() == ':' && p.spacing() == Spacing::Alone) =>
+                {
+                    fields = None;
+                }
+                _ => {}
+            }
+            prev_dash = matches!(&tt, TokenTree::Punct(p) if p.as_char() == '-');
+            ty.push(tt.clone());
+            out.push(tt);
+        }
+        if !pinned && is_phantom_pinned(&ty) {
+            errs.extend(error(
+                name.span(),
+                &format!(
+                    "The field `{name}` of type `PhantomPinned` only has an effect, if it has the \
+                        `#[pin]` attribute."
+                ),
+            ));
+        }
+        // Marker fields can be omitted by initializers, they are initialized with their default
+        // value.
+        let marker = marker || is_phantom_pinned(&ty) || is_phantom_data(&ty);
+        if marker && default.is_none() {
+            let span = ty.first().map_or_else(|| name.span(), TokenTree::span);
+            default = Some(FieldDefault::Value(
+                quote_spanned!(span=> ::core::default::Default::default()),
+            ));
+        }
+        if let Some(fields) = &mut fields {
+            fields.push(Field {
+                attrs,
+                vis,
+                name,
+                ty,
+                pinned,
+                marker,
+                default,
+                cfgs,
+            });
+        }
+    }
+    let mut res = Group::new(body.delimiter(), out.into_iter().collect());
+    res.set_span(body.span());
+    (res, fields)
+}
+
+/// Whether `ty` is `PhantomPinned`, optionally prefixed by `marker::`, `core::marker::` or
+/// `::core::marker::`.
+fn is_phantom_pinned(ty: &[TokenTree]) -> bool {
+    let path = ty
+        .iter()
+        .filter(|tt| !matches!(tt, TokenTree::Punct(p) if p.as_char() == ':'))
+        .map(|tt| tt.to_string())
+        .collect::<Vec<_>>();
+    let path = path.iter().map(String::as_str).collect::<Vec<_>>();
+    matches!(
+        &path[..],
+        ["PhantomPinned"] | ["marker", "PhantomPinned"] | ["core", "marker", "PhantomPinned"]
+    )
+}
+
+/// Whether `ty` is `PhantomData<..>`, optionally prefixed by `marker::`, `core::marker::`,
+/// `std::marker::` or `::core::marker::`.
+fn is_phantom_data(ty: &[TokenTree]) -> bool {
+    let Some(open) = ty
+        .iter()
+        .position(|tt| matches!(tt, TokenTree::Punct(p) if p.as_char() == '<'))
+    else {
+        return false;
+    };
+    let path = ty[..open]
+        .iter()
+        .filter(|tt| !matches!(tt, TokenTree::Punct(p) if p.as_char() == ':'))
+        .map(|tt| tt.to_string())
+        .collect::<Vec<_>>();
+    let path = path.iter().map(String::as_str).collect::<Vec<_>>();
+    matches!(
+        &path[..],
+        ["PhantomData"] | ["marker", "PhantomData"] | ["core" | "std", "marker", "PhantomData"]
+    ) && closing_angle(ty, open) == Some(ty.len() - 1)
+}
+
+impl Field {
+    /// Returns the `#[cfg(..)]` attributes of the field.
+    pub(crate) fn cfg_attrs(&self) -> TokenStream {
+        let cfgs = &self.cfgs;
+        quote!(#(#[cfg(#cfgs)])*)
+    }
+}
+
+/// Creates a `compile_error!` invocation with the given message at `span`.
+pub(crate) fn error(span: Span, msg: &str) -> TokenStream {
+    quote_spanned!(span=> ::core::compile_error!(#msg);)
+}
+
+/// Appends a `,` to the given generics, if they are not empty and do not already end in one.
+pub(crate) fn with_trailing_comma(generics: &[TokenTree]) -> Vec<TokenTree> {
+    let mut generics = generics.to_vec();
+    match generics.last() {
+        None => {}
+        Some(TokenTree::Punct(p)) if p.as_char() == ',' => {}
+        Some(_) => generics.push(TokenTree::Punct(Punct::new(',', Spacing::Alone))),
+    }
+    generics
+}
+
+/// Returns the name of the generic error parameter of generated functions, which must not collide
+/// with the generics of the struct.
+pub(crate) fn error_ident(ty_generics: &[TokenTree]) -> Ident {
+    if ty_generics
+        .iter()
+        .any(|tt| matches!(tt, TokenTree::Ident(i) if i == "E"))
+    {
+        Ident::new("__E", Span::call_site())
+    } else {
+        Ident::new("E", Span::call_site())
     }
 }
@@ -1,54 +1,54 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use crate::pin_data::{closing_angle, crate_path, error};
 use proc_macro2::{TokenStream, TokenTree};
 
 pub(crate) fn pinned_drop(
-    _args: proc_macro::TokenStream,
+    args: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
+    let args = TokenStream::from(args).into_iter().collect::<Vec<_>>();
+    let mut errs = TokenStream::new();
+    let krate = match crate_path(&args) {
+        Some(krate) => krate,
+        None => {
+            if let Some(arg) = args.first() {
+                errs.extend(error(arg.span(), "expected `crate = path`"));
+            }
+            quote::quote!(::pinned_init)
+        }
+    };
     let input: TokenStream = input.into();
     let mut toks = input.into_iter().collect::<Vec<_>>();
     assert!(!toks.is_empty());
     // Ensure that we have an `impl` item.
     assert!(matches!(&toks[0], TokenTree::Ident(i) if *i == "impl"));
-    // Ensure that we are implementing `PinnedDrop`.
-    let mut nesting: usize = 0;
-    let mut pinned_drop_idx = None;
-    for (i, tt) in toks.iter().enumerate() {
-        match tt {
-            TokenTree::Punct(p) if p.as_char() == '<' => {
-                nesting += 1;
-            }
-            TokenTree::Punct(p) if p.as_char() == '>' => {
-                nesting = nesting.checked_sub(1).unwrap();
-                continue;
-            }
-            _ => {}
-        }
-        if i >= 1 && nesting == 0 {
-            // Found the end of the generics, this should be `PinnedDrop`.
-            assert!(
-                matches!(tt, TokenTree::Ident(i) if *i == "PinnedDrop"),
-                "expected 'PinnedDrop', found: '{:?}'",
-                tt
-            );
-            pinned_drop_idx = Some(i);
-            break;
-        }
+    // Ensure that we are implementing `PinnedDrop`, it comes right after the generics.
+    let idx = match toks.get(1) {
+        Some(TokenTree::Punct(p)) if p.as_char() == '<' => closing_angle(&toks, 1).map(|i| i + 1),
+        _ => Some(1),
     }
-    let idx = pinned_drop_idx
-        .unwrap_or_else(|| panic!("Expected an `impl` block implementing `PinnedDrop`."));
+    .filter(|&idx| idx < toks.len())
+    .unwrap_or_else(|| panic!("Expected an `impl` block implementing `PinnedDrop`."));
+    assert!(
+        matches!(&toks[idx], TokenTree::Ident(i) if *i == "PinnedDrop"),
+        "expected 'PinnedDrop', found: '{:?}'",
+        toks[idx]
+    );
     // Fully qualify the `PinnedDrop`, as to avoid any tampering.
-    toks.splice(idx..idx, quote::quote!(::pinned_init::));
+    toks.splice(idx..idx, quote::quote!(#krate::));
     // Take the `{}` body and call the declarative macro.
     if let Some(TokenTree::Group(last)) = toks.pop() {
         let last = last.stream();
-        quote::quote!(::pinned_init::__pinned_drop! {
-            @impl_sig(#(#toks)*),
-            @impl_body(#last),
-        })
+        quote::quote!(
+            #errs
+            #krate::__pinned_drop! {
+                @impl_sig(#(#toks)*),
+                @impl_body(#last),
+            }
+        )
         .into()
     } else {
-        TokenStream::from_iter(toks).into()
+        errs.into_iter().chain(toks).collect::<TokenStream>().into()
     }
 }
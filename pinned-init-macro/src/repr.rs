@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `#[repr(packed)]` and `#[repr(transparent)]` structs in `#[pin_data]`.
+
+use crate::pin_data::{error, error_ident, with_trailing_comma, Field, Struct};
+use proc_macro2::{Delimiter, Ident, TokenStream, TokenTree};
+use quote::quote;
+
+/// Returns the representation hints of all `#[repr(..)]` attributes in `attrs`, e.g. `C` and
+/// `packed` for `#[repr(C, packed(2))]`.
+pub(crate) fn repr_hints(attrs: &[TokenTree]) -> Vec<Ident> {
+    let mut hints = vec![];
+    for attr in attrs {
+        let TokenTree::Group(attr) = attr else {
+            continue;
+        };
+        if attr.delimiter() != Delimiter::Bracket {
+            continue;
+        }
+        let mut toks = attr.stream().into_iter();
+        match (toks.next(), toks.next(), toks.next()) {
+            (Some(TokenTree::Ident(repr)), Some(TokenTree::Group(args)), None)
+                if repr == "repr" && args.delimiter() == Delimiter::Parenthesis =>
+            {
+                hints.extend(args.stream().into_iter().filter_map(|tt| match tt {
+                    TokenTree::Ident(hint) => Some(hint),
+                    _ => None,
+                }));
+            }
+            _ => {}
+        }
+    }
+    hints
+}
+
+/// Structurally pinned fields of a packed struct could be unaligned, so they cannot be pinned in
+/// place. Returns an error for each of them.
+pub(crate) fn deny_packed(hints: &[Ident], fields: &[Field]) -> TokenStream {
+    if !hints.iter().any(|hint| hint == "packed") {
+        return TokenStream::new();
+    }
+    fields
+        .iter()
+        .filter(|f| f.pinned)
+        .map(|f| {
+            error(
+                f.name.span(),
+                "`#[pin]` fields are not supported in `#[repr(packed)]` structs",
+            )
+        })
+        .collect()
+}
+
+/// Generates `fn transparent` for a `#[repr(transparent)]` struct with a single field besides its
+/// marker fields, which turns an initializer of the field into one of the struct. The marker
+/// fields are initialized with their default value.
+///
+/// The function has the visibility of the field, since it allows creating the struct from any
+/// value of the field. A structurally pinned field takes a `PinInit`, otherwise an `Init`.
+pub(crate) fn impl_transparent(hints: &[Ident], item: &Struct<'_>) -> TokenStream {
+    let Struct {
+        krate,
+        name,
+        impl_generics,
+        ty_generics,
+        whr,
+        fields,
+        ..
+    } = *item;
+    let Some(hint) = hints.iter().find(|hint| *hint == "transparent") else {
+        return TokenStream::new();
+    };
+    let (markers, rest) = fields.iter().partition::<Vec<_>, _>(|f| f.marker);
+    let field = match rest[..] {
+        [field] if field.cfgs.is_empty() => field,
+        [field] => {
+            return error(
+                field.name.span(),
+                "`#[pin_data]` cannot generate `transparent` if the field of a \
+                 `#[repr(transparent)]` struct has a `#[cfg(..)]` attribute",
+            )
+        }
+        _ => {
+            return error(
+                hint.span(),
+                "`#[pin_data]` can only generate `transparent` for a `#[repr(transparent)]` \
+                 struct with one field besides its marker fields, annotate the other zero-sized \
+                 fields with `#[pin_data(marker)]`",
+            )
+        }
+    };
+    let Field { vis, ty, name: field_name, .. } = field;
+    let impl_generics = with_trailing_comma(impl_generics);
+    let err = error_ident(ty_generics);
+    let doc = format!(
+        " Turns an initializer of the only field of [`{name}`] into an initializer of [`{name}`]."
+    );
+    let markers = markers.iter().map(|f| {
+        let marker = &f.name;
+        let cfgs = f.cfg_attrs();
+        quote! {
+            #cfgs
+            // SAFETY: `slot` is valid for writes.
+            unsafe {
+                #krate::__internal::init_marker(::core::ptr::addr_of_mut!((*slot).#marker))
+            };
+        }
+    });
+    let markers = quote!(#(#markers)*);
+    let func = if field.pinned {
+        quote! {
+            #(#vis)* fn transparent<#err>(
+                init: impl #krate::PinInit<#(#ty)*, #err>,
+            ) -> impl #krate::PinInit<Self, #err> {
+                // SAFETY: The field is initialized by `init` and the marker fields are zero-sized
+                // and initialized afterwards, so all of `slot` is initialized on success. On error,
+                // nothing needs to be dropped. The field is structurally pinned, so it stays pinned
+                // as long as `slot` does.
+                unsafe {
+                    #krate::pin_init_from_closure(move |slot: *mut Self| {
+                        #krate::PinInit::__pinned_init(
+                            init,
+                            ::core::ptr::addr_of_mut!((*slot).#field_name),
+                        )?;
+                        #markers
+                        ::core::result::Result::Ok(())
+                    })
+                }
+            }
+        }
+    } else {
+        quote! {
+            #(#vis)* fn transparent<#err>(
+                init: impl #krate::Init<#(#ty)*, #err>,
+            ) -> impl #krate::Init<Self, #err> {
+                // SAFETY: The field is initialized by `init` and the marker fields are zero-sized
+                // and initialized afterwards, so all of `slot` is initialized on success. On error,
+                // nothing needs to be dropped.
+                unsafe {
+                    #krate::init_from_closure(move |slot: *mut Self| {
+                        #krate::Init::__init(init, ::core::ptr::addr_of_mut!((*slot).#field_name))?;
+                        #markers
+                        ::core::result::Result::Ok(())
+                    })
+                }
+            }
+        }
+    };
+    quote! {
+        impl<#(#impl_generics)*> #name<#(#ty_generics)*>
+        where #(#whr)*
+        {
+            #[doc = #doc]
+            #[inline]
+            #func
+        }
+    }
+}
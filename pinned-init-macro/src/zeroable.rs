@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: GPL-2.0
 
-use crate::pin_data::{parse_generics, Generics};
-use proc_macro2::{TokenStream, TokenTree};
+use crate::pin_data::{
+    crate_path, error, generic_param, parse_generics, split_generics, GenericKind, Generics,
+};
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
 use quote::quote;
 
 pub(crate) fn derive(input: TokenStream) -> TokenStream {
@@ -15,55 +17,55 @@ pub(crate) fn derive(input: TokenStream) -> TokenStream {
     ) = parse_generics(input);
     // This should be the body of the struct `{...}`.
     let last = rest.pop();
-    // Now we insert `Zeroable` as a bound for every generic parameter in `impl_generics`.
-    let mut new_impl_generics = Vec::with_capacity(impl_generics.len());
-    // Are we inside of a generic where we want to add `Zeroable`?
-    let mut in_generic = !impl_generics.is_empty();
-    // Have we already inserted `Zeroable`?
-    let mut inserted = false;
-    // Level of `<>` nestings.
-    let mut nested = 0;
-    for tt in impl_generics {
-        match &tt {
-            // If we find a `,`, then we have finished a generic/constant/lifetime parameter.
-            TokenTree::Punct(p) if nested == 0 && p.as_char() == ',' => {
-                if in_generic && !inserted {
-                    new_impl_generics.extend(quote! { : ::pinned_init::Zeroable });
-                }
-                in_generic = true;
-                inserted = false;
-                new_impl_generics.push(tt);
-            }
-            // If we find `'`, then we are entering a lifetime.
-            TokenTree::Punct(p) if nested == 0 && p.as_char() == '\'' => {
-                in_generic = false;
-                new_impl_generics.push(tt);
-            }
-            TokenTree::Punct(p) if nested == 0 && p.as_char() == ':' => {
-                new_impl_generics.push(tt);
-                if in_generic {
-                    new_impl_generics.extend(quote! { ::pinned_init::Zeroable + });
-                    inserted = true;
-                }
-            }
-            TokenTree::Punct(p) if p.as_char() == '<' => {
-                nested += 1;
-                new_impl_generics.push(tt);
-            }
-            TokenTree::Punct(p) if p.as_char() == '>' => {
-                assert!(nested > 0);
-                nested -= 1;
-                new_impl_generics.push(tt);
+    let mut errs = TokenStream::new();
+    let mut krate = quote!(::pinned_init);
+    // Look for a `#[zeroable(crate = path)]` attribute on the struct.
+    for attr in rest.iter() {
+        let TokenTree::Group(attr) = attr else {
+            continue;
+        };
+        if attr.delimiter() != Delimiter::Bracket {
+            continue;
+        }
+        let attr = attr.stream().into_iter().collect::<Vec<_>>();
+        let path = match &attr[..] {
+            [TokenTree::Ident(i), TokenTree::Group(args)]
+                if i == "zeroable" && args.delimiter() == Delimiter::Parenthesis =>
+            {
+                crate_path(&args.stream().into_iter().collect::<Vec<_>>())
             }
-            _ => new_impl_generics.push(tt),
+            [TokenTree::Ident(i), ..] if i == "zeroable" => None,
+            _ => continue,
+        };
+        match path {
+            Some(path) => krate = path,
+            None => errs.extend(error(attr[0].span(), "expected `zeroable(crate = path)`")),
         }
     }
-    assert_eq!(nested, 0);
-    if in_generic && !inserted {
-        new_impl_generics.extend(quote! { : ::pinned_init::Zeroable });
+    // Now we insert `Zeroable` as a bound for every generic type parameter in `impl_generics`.
+    let mut new_impl_generics = Vec::with_capacity(impl_generics.len());
+    for (i, param) in split_generics(&impl_generics).into_iter().enumerate() {
+        if i != 0 {
+            new_impl_generics.extend(quote!(,));
+        }
+        let (kind, name) = generic_param(param);
+        if kind != GenericKind::Type || name.is_empty() {
+            new_impl_generics.extend_from_slice(param);
+            continue;
+        }
+        // The bounds of a type parameter follow after a `:`.
+        new_impl_generics.extend_from_slice(&param[..name.end]);
+        match param.get(name.end) {
+            Some(TokenTree::Punct(p)) if p.as_char() == ':' => {
+                new_impl_generics.extend(quote! { : #krate::Zeroable + });
+                new_impl_generics.extend_from_slice(&param[name.end + 1..]);
+            }
+            _ => new_impl_generics.extend(quote! { : #krate::Zeroable }),
+        }
     }
     quote! {
-        ::pinned_init::__derive_zeroable!(
+        #errs
+        #krate::__derive_zeroable!(
             parse_input:
                 @sig(#(#rest)*),
                 @impl_generics(#(#new_impl_generics)*),
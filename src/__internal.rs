@@ -52,35 +52,660 @@ where
     }
 }
 
+/// The module-internal type implementing `AsyncPinInit`. It is unsafe to create this type, since
+/// the closure needs to fulfill the same safety requirement as `__pinned_init_async`.
+pub(crate) struct AsyncInitClosure<F, T: ?Sized, E>(pub(crate) F, pub(crate) Invariant<(E, T)>);
+
+// SAFETY: While constructing the `AsyncInitClosure`, the user promised that it upholds the
+// `__pinned_init_async` invariants.
+unsafe impl<T: ?Sized, F, Fut, E> AsyncPinInit<T, E> for AsyncInitClosure<F, T, E>
+where
+    F: FnOnce(*mut T) -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+{
+    type Future = Fut;
+
+    #[inline]
+    unsafe fn __pinned_init_async(self, slot: *mut T) -> Fut {
+        (self.0)(slot)
+    }
+}
+
+/// Object safe version of [`PinInit`], used by [`DynPinInit`] and [`SendDynPinInit`].
+///
+/// [`PinInit::__pinned_init`] takes `self` by value, so it cannot be called on a trait object.
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub(crate) trait BoxedPinInit<T: ?Sized, E> {
+    /// # Safety
+    ///
+    /// Same as [`PinInit::__pinned_init`].
+    unsafe fn __pinned_init_boxed(self: Box<Self>, slot: *mut T) -> Result<(), E>;
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<T: ?Sized, E, I: PinInit<T, E>> BoxedPinInit<T, E> for I {
+    #[inline]
+    unsafe fn __pinned_init_boxed(self: Box<Self>, slot: *mut T) -> Result<(), E> {
+        // SAFETY: The caller upholds the requirements of `__pinned_init`.
+        unsafe { (*self).__pinned_init(slot) }
+    }
+}
+
+/// The slot of an asynchronous initializer generated by `[try_]async_pin_init!`.
+///
+/// The slot is used across `.await` points, a raw pointer would make the future `!Send`.
+pub struct AsyncSlot<T: ?Sized>(*mut T);
+
+impl<T: ?Sized> AsyncSlot<T> {
+    #[inline]
+    pub fn new(slot: *mut T) -> Self {
+        Self(slot)
+    }
+
+    #[inline]
+    pub fn ptr(&self) -> *mut T {
+        self.0
+    }
+}
+
+// SAFETY: The initializer owns the slot until it is fully initialized, so sending it to another
+// thread is like sending a `T`.
+unsafe impl<T: ?Sized + Send> Send for AsyncSlot<T> {}
+
 /// This trait is only implemented via the `#[pin_data]` proc-macro. It is used to facilitate
 /// the pin projections within the initializers.
 ///
 /// # Safety
 ///
 /// Only the `init` module is allowed to use this trait.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not annotated with `#[pin_data]`",
+    label = "this requires `#[pin_data]`"
+)]
 pub unsafe trait HasPinData {
     type PinData: PinData;
 
     unsafe fn __pin_data() -> Self::PinData;
+
+    /// Same as [`HasPinData::__pin_data`], `slot` is only used for type inference.
+    ///
+    /// The initializer closures use this instead of capturing the pin-data.
+    #[inline(always)]
+    unsafe fn __pin_data_of(slot: *mut Self) -> Self::PinData {
+        let _ = slot;
+        // SAFETY: The caller upholds the safety requirements.
+        unsafe { Self::__pin_data() }
+    }
+
+    /// Drops every field of `slot`, except the field declared at position `except`.
+    ///
+    /// # Safety
+    ///
+    /// All fields of `slot` except `except` must be initialized and will be dropped.
+    unsafe fn __drop_fields_except(slot: *mut Self, except: usize);
 }
 
+/// Names the struct `T` in a struct pattern.
+///
+/// Qualified paths like `<T as Trait>::Assoc` cannot be used in patterns, but a type alias of
+/// them can, so the initializers can still check that every field has been initialized.
+pub type Struct<T> = T;
+
 /// Marker trait for pinning data of structs.
 ///
 /// # Safety
 ///
 /// Only the `init` module is allowed to use this trait.
-pub unsafe trait PinData: Copy {
+pub unsafe trait PinData {
     type Datee: ?Sized + HasPinData;
 
     /// Type inference helper function.
-    fn make_closure<F, O, E>(self, f: F) -> F
+    fn make_closure<F, O, E>(&self, f: F) -> F
     where
         F: FnOnce(*mut Self::Datee) -> Result<O, E>,
     {
         f
     }
+
+    /// Type inference helper function for `[try_]async_pin_init!`.
+    fn make_async_closure<F, Fut, O, E>(&self, f: F) -> F
+    where
+        F: FnOnce(*mut Self::Datee) -> Fut,
+        Fut: Future<Output = Result<O, E>>,
+    {
+        f
+    }
+
+    /// Returns the name of the struct for [`InitError`].
+    fn __type_name(&self) -> &'static str {
+        core::any::type_name::<Self::Datee>()
+    }
+
+    /// Initializes `slot` with the base initializer of the `..base` struct update syntax.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`PinInit::__pinned_init`].
+    unsafe fn __init_base<E>(
+        &self,
+        slot: *mut Self::Datee,
+        init: impl PinInit<Self::Datee, E>,
+    ) -> Result<(), E> {
+        // SAFETY: The caller upholds the safety requirements.
+        unsafe { init.__pinned_init(slot) }
+    }
+
+    /// Returns the tracker for the fields mentioned by an initializer.
+    fn __fields(&self) -> <Self::Datee as HasFields>::Fields
+    where
+        Self::Datee: HasFields,
+    {
+        <Self::Datee as HasFields>::__fields()
+    }
+
+    /// Returns the tracker for the `..Default` struct update syntax.
+    fn __defaults(&self) -> <Self::Datee as HasDefaults>::Defaults
+    where
+        Self::Datee: HasDefaults,
+    {
+        <Self::Datee as HasDefaults>::__defaults()
+    }
+}
+
+/// Returned by the projection function of a structurally pinned field of a `#[pin_data]` struct.
+pub struct PinnedField<T: ?Sized> {
+    index: usize,
+    _phantom: Invariant<T>,
+}
+
+impl<T: ?Sized> PinnedField<T> {
+    /// Creates the projection of the field of type `T` that is declared at position `index`.
+    #[inline(always)]
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the position of the field in the declaration of the struct.
+    #[inline(always)]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Initializes the field at `slot`, it has to be initialized via [`PinInit`].
+    ///
+    /// # Safety
+    ///
+    /// Same as [`PinInit::__pinned_init`].
+    #[inline]
+    pub unsafe fn __init<E>(self, slot: *mut T, init: impl PinInit<T, E>) -> Result<(), E> {
+        // SAFETY: The caller upholds the safety requirements.
+        unsafe { init.__pinned_init(slot) }
+    }
+
+    /// Initializes the field at `slot` in `[try_]async_pin_init!`, it has to be initialized via
+    /// [`PinInit`] or [`AsyncPinInit`].
+    ///
+    /// # Safety
+    ///
+    /// Same as [`AsyncPinInit::__pinned_init_async`].
+    #[inline]
+    pub unsafe fn __init_async<E, M, I>(self, slot: *mut T, init: I) -> I::Future
+    where
+        I: FieldInitAsync<T, E, M>,
+    {
+        // SAFETY: The caller upholds the safety requirements.
+        unsafe { init.__init_async(slot) }
+    }
+}
+
+/// Returned by the projection function of a field of a `#[pin_data]` struct that is not
+/// structurally pinned.
+pub struct UnpinnedField<T: ?Sized> {
+    index: usize,
+    _phantom: Invariant<T>,
+}
+
+impl<T: ?Sized> UnpinnedField<T> {
+    /// Creates the projection of the field of type `T` that is declared at position `index`.
+    #[inline(always)]
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the position of the field in the declaration of the struct.
+    #[inline(always)]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Initializes the field at `slot`, it can be initialized via [`Init`].
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Init::__init`].
+    #[inline]
+    pub unsafe fn __init<E>(self, slot: *mut T, init: impl Init<T, E>) -> Result<(), E> {
+        // SAFETY: The caller upholds the safety requirements.
+        unsafe { init.__init(slot) }
+    }
+
+    /// Initializes the field at `slot` in `[try_]async_pin_init!`, it can be initialized via
+    /// [`Init`].
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Init::__init`].
+    #[inline]
+    pub unsafe fn __init_async<E>(
+        self,
+        slot: *mut T,
+        init: impl Init<T, E>,
+    ) -> core::future::Ready<Result<(), E>> {
+        // SAFETY: The caller upholds the safety requirements.
+        core::future::ready(unsafe { init.__init(slot) })
+    }
+}
+
+/// Marks a [`PinInit`] in [`FieldInitAsync`].
+pub struct IsPinInit;
+
+/// Marks an [`AsyncPinInit`] in [`FieldInitAsync`].
+pub struct IsAsyncPinInit;
+
+/// Allows initializing a structurally pinned field in `[try_]async_pin_init!` with either a
+/// [`PinInit`] or an [`AsyncPinInit`].
+///
+/// A blanket implementation of [`AsyncPinInit`] for every [`PinInit`] would conflict with the
+/// implementation for [`async_pin_init_from_closure`], so the marker `M` distinguishes the two
+/// implementations instead. It is inferred from the initializer.
+///
+/// # Safety
+///
+/// Same as [`AsyncPinInit`].
+pub unsafe trait FieldInitAsync<T: ?Sized, E, M> {
+    type Future: Future<Output = Result<(), E>>;
+
+    /// # Safety
+    ///
+    /// Same as [`AsyncPinInit::__pinned_init_async`].
+    unsafe fn __init_async(self, slot: *mut T) -> Self::Future;
+}
+
+// SAFETY: The initializer runs before the future is created, the future only returns the result.
+unsafe impl<T: ?Sized, E, I: PinInit<T, E>> FieldInitAsync<T, E, IsPinInit> for I {
+    type Future = core::future::Ready<Result<(), E>>;
+
+    #[inline]
+    unsafe fn __init_async(self, slot: *mut T) -> Self::Future {
+        // SAFETY: The caller upholds the safety requirements.
+        core::future::ready(unsafe { self.__pinned_init(slot) })
+    }
+}
+
+// SAFETY: We delegate to the `AsyncPinInit`.
+unsafe impl<T: ?Sized, E, I: AsyncPinInit<T, E>> FieldInitAsync<T, E, IsAsyncPinInit> for I {
+    type Future = I::Future;
+
+    #[inline]
+    unsafe fn __init_async(self, slot: *mut T) -> Self::Future {
+        // SAFETY: The caller upholds the safety requirements.
+        unsafe { self.__pinned_init_async(slot) }
+    }
+}
+
+/// Marks an error that is wrapped in [`FieldError`].
+pub struct IsError;
+
+/// Marks an [`InitError`] that is forwarded by [`FieldError`].
+pub struct IsInitError;
+
+/// Adds the context to the error of a field in an initializer with `? Error with context`.
+///
+/// Errors that already have a context come from a nested initializer and are forwarded, they
+/// point at the innermost field. The marker `M` distinguishes the two implementations.
+pub trait FieldError<E, M> {
+    fn __with_context(self, type_name: &'static str, field: &'static str) -> InitError<E>;
+}
+
+impl<E: From<F>, F> FieldError<E, IsError> for F {
+    #[inline]
+    fn __with_context(self, type_name: &'static str, field: &'static str) -> InitError<E> {
+        InitError {
+            type_name,
+            field,
+            source: E::from(self),
+        }
+    }
+}
+
+impl<E: From<F>, F> FieldError<E, IsInitError> for InitError<F> {
+    #[inline]
+    fn __with_context(self, _: &'static str, _: &'static str) -> InitError<E> {
+        InitError {
+            type_name: self.type_name,
+            field: self.field,
+            source: E::from(self.source),
+        }
+    }
+}
+
+/// Evaluates the expression of a field in an initializer with `? Error with context`, errors
+/// returned via `?` get the context of the field.
+#[inline]
+pub fn with_context<T, E>(
+    type_name: &'static str,
+    field: &'static str,
+    f: impl FnOnce() -> Result<T, E>,
+) -> Result<T, InitError<E>> {
+    f().map_err(|source| InitError {
+        type_name,
+        field,
+        source,
+    })
+}
+
+/// Same as [`with_context`] for `[try_]async_pin_init!`, where the expression may `.await`.
+#[inline]
+pub async fn with_context_async<T, E>(
+    type_name: &'static str,
+    field: &'static str,
+    f: impl Future<Output = Result<T, E>>,
+) -> Result<T, InitError<E>> {
+    f.await.map_err(|source| InitError {
+        type_name,
+        field,
+        source,
+    })
+}
+
+/// Implemented by `#[pin_data]` for structs without `PinnedDrop`. Conflicts with the blanket
+/// implementation if the struct also implements `Drop`.
+pub trait MustNotImplDrop {}
+
+#[allow(drop_bounds)]
+impl<T: Drop + ?Sized> MustNotImplDrop for T {}
+
+/// Implemented by `#[pin_data]` for structs without `PinnedDrop`. Conflicts with the blanket
+/// implementation if the user implemented `PinnedDrop`, but forgot to give `PinnedDrop` as the
+/// parameter to `#[pin_data]`.
+#[allow(non_camel_case_types)]
+pub trait UselessPinnedDropImpl_you_need_to_specify_PinnedDrop {}
+
+impl<T: PinnedDrop + ?Sized> UselessPinnedDropImpl_you_need_to_specify_PinnedDrop for T {}
+
+/// Used by `#[pin_data]`, this type is `Unpin` if and only if all types in the tuple `T` are
+/// `Unpin`. `T` contains the types of the structurally pinned fields. The lifetime prevents the
+/// compiler from rejecting an `Unpin` bound on it as trivially false.
+pub struct PinnedFields<'a, T: ?Sized>(PhantomData<&'a ()>, T);
+
+/// Used by `#[pin_data(!Unpin)]`, this type is never `Unpin`. The lifetime prevents the compiler
+/// from rejecting an `Unpin` bound on it as trivially false.
+pub struct NotUnpin<'a>(PhantomData<&'a ()>, core::marker::PhantomPinned);
+
+/// Used by `#[pin_data(UnsafeUnpin)]`, this type is `Unpin` if and only if `T` implements
+/// [`UnsafeUnpin`]. The lifetime serves the same purpose as in [`NotUnpin`].
+pub struct UnsafeUnpinned<'a, T: ?Sized>(
+    PhantomData<&'a ()>,
+    PhantomData<T>,
+    core::marker::PhantomPinned,
+);
+
+impl<T: UnsafeUnpin + ?Sized> Unpin for UnsafeUnpinned<'_, T> {}
+
+/// This trait is implemented via the `#[pin_data]` proc-macro for structs that have at least one
+/// field annotated with `#[default(..)]` or `#[default_init(..)]`.
+///
+/// The `Defaults` type tracks which fields have been initialized explicitly. It has a method for
+/// every field that marks it as initialized and an `unsafe fn __init_defaults(self, slot)` that
+/// initializes every field with a default value that has not been marked. It is only callable if
+/// every field without a default value has been marked.
+///
+/// # Safety
+///
+/// Only the `init` module is allowed to use this trait.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not declare any default values",
+    label = "`..Default` requires default values",
+    note = "annotate fields of `{Self}` with `#[default(value)]` or `#[default_init(initializer)]`"
+)]
+pub unsafe trait HasDefaults {
+    type Defaults;
+
+    fn __defaults() -> Self::Defaults;
+}
+
+/// Marks a field as initialized in the `Defaults` tracker of [`HasDefaults`].
+pub struct Set;
+
+/// Marks a field as not yet initialized in the `Defaults` tracker of [`HasDefaults`].
+pub struct Unset;
+
+/// Runtime view of [`Set`] and [`Unset`].
+pub trait FieldState {
+    const IS_SET: bool;
+}
+
+impl FieldState for Set {
+    const IS_SET: bool = true;
+}
+
+impl FieldState for Unset {
+    const IS_SET: bool = false;
+}
+
+/// Implemented by [`Set`] only. Used to require the initialization of fields that do not have a
+/// default value, `Field` is a marker type named after the field.
+#[diagnostic::on_unimplemented(
+    message = "field `{Field}` does not have a default value and has not been initialized",
+    label = "missing `{Field}`",
+    note = "only fields annotated with `#[default(..)]` or `#[default_init(..)]` can be omitted"
+)]
+pub trait Initialized<Field> {}
+
+impl<Field> Initialized<Field> for Set {}
+
+impl<I, Field> Initialized<Field> for Provided<I> {}
+
+/// A field of a builder generated by `#[pin_data(builder)]` that has been set to the initializer
+/// `I`. Fields that have not been set are [`Unset`].
+pub struct Provided<I>(pub I);
+
+/// A structurally pinned field of a builder generated by `#[pin_data(builder)]`.
+///
+/// # Safety
+///
+/// `__pinned_init_or` must uphold the same requirements as [`PinInit::__pinned_init`].
+pub unsafe trait PinBuilderField<T, E> {
+    /// Initializes `slot` with the provided initializer or with the one returned by `default`.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`PinInit::__pinned_init`].
+    unsafe fn __pinned_init_or<D>(self, slot: *mut T, default: impl FnOnce() -> D) -> Result<(), E>
+    where
+        D: PinInit<T, Infallible>;
+}
+
+// SAFETY: `__pinned_init_or` delegates to `__pinned_init`.
+unsafe impl<T, E> PinBuilderField<T, E> for Unset {
+    #[inline]
+    unsafe fn __pinned_init_or<D>(self, slot: *mut T, default: impl FnOnce() -> D) -> Result<(), E>
+    where
+        D: PinInit<T, Infallible>,
+    {
+        // SAFETY: The caller upholds the safety requirements.
+        match unsafe { default().__pinned_init(slot) } {
+            Ok(()) => Ok(()),
+            Err(e) => match e {},
+        }
+    }
+}
+
+// SAFETY: `__pinned_init_or` delegates to `__pinned_init`.
+unsafe impl<T, E, I: PinInit<T, E>> PinBuilderField<T, E> for Provided<I> {
+    #[inline]
+    unsafe fn __pinned_init_or<D>(self, slot: *mut T, _: impl FnOnce() -> D) -> Result<(), E>
+    where
+        D: PinInit<T, Infallible>,
+    {
+        // SAFETY: The caller upholds the safety requirements.
+        unsafe { self.0.__pinned_init(slot) }
+    }
+}
+
+/// A field of a builder generated by `#[pin_data(builder)]` that is not structurally pinned.
+///
+/// # Safety
+///
+/// `__init_or` must uphold the same requirements as [`Init::__init`].
+pub unsafe trait BuilderField<T, E> {
+    /// Initializes `slot` with the provided initializer or with the one returned by `default`.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Init::__init`].
+    unsafe fn __init_or<D>(self, slot: *mut T, default: impl FnOnce() -> D) -> Result<(), E>
+    where
+        D: Init<T, Infallible>;
+}
+
+// SAFETY: `__init_or` delegates to `__init`.
+unsafe impl<T, E> BuilderField<T, E> for Unset {
+    #[inline]
+    unsafe fn __init_or<D>(self, slot: *mut T, default: impl FnOnce() -> D) -> Result<(), E>
+    where
+        D: Init<T, Infallible>,
+    {
+        // SAFETY: The caller upholds the safety requirements.
+        match unsafe { default().__init(slot) } {
+            Ok(()) => Ok(()),
+            Err(e) => match e {},
+        }
+    }
 }
 
+// SAFETY: `__init_or` delegates to `__init`.
+unsafe impl<T, E, I: Init<T, E>> BuilderField<T, E> for Provided<I> {
+    #[inline]
+    unsafe fn __init_or<D>(self, slot: *mut T, _: impl FnOnce() -> D) -> Result<(), E>
+    where
+        D: Init<T, Infallible>,
+    {
+        // SAFETY: The caller upholds the safety requirements.
+        unsafe { self.0.__init(slot) }
+    }
+}
+
+/// This trait is implemented via the `#[pin_data]` proc-macro for every struct with marker fields
+/// (`PhantomPinned`, `PhantomData<T>` and fields annotated with `#[pin_data(marker)]`). It allows
+/// `[try_][pin_]init!` to omit them when the initializer ends with `..`.
+///
+/// The `Fields` type has a method for every field returning a [`Mention`] of it. The initializer
+/// collects the mentions of the fields it initializes in a tree of pairs and passes it to
+/// `__check(&mentions)`, which only compiles if every field that is not a marker field has been
+/// mentioned.
+///
+/// # Safety
+///
+/// Only the `init` module is allowed to use this trait. `__init_markers` must initialize exactly
+/// the marker fields and `Fields::__check` must require a [`Mention`] of every other field.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not have any marker fields",
+    label = "`..` requires marker fields",
+    note = "marker fields are fields of type `PhantomPinned` or `PhantomData<T>` and fields \
+        annotated with `#[pin_data(marker)]`"
+)]
+pub unsafe trait HasFields {
+    type Fields;
+
+    fn __fields() -> Self::Fields;
+
+    /// Initializes every marker field of `slot`.
+    ///
+    /// # Safety
+    ///
+    /// `slot` must be valid for writes. The marker fields are zero-sized and do not need to be
+    /// dropped, so they may already be initialized.
+    unsafe fn __init_markers(slot: *mut Self);
+}
+
+/// Initializes the marker field at `slot` with its default value.
+///
+/// # Safety
+///
+/// `slot` must be valid for writes.
+#[inline(always)]
+pub unsafe fn init_marker<T: Default>(slot: *mut T) {
+    const {
+        assert!(
+            mem::size_of::<T>() == 0 && !mem::needs_drop::<T>(),
+            "marker fields must be zero-sized and must not need to be dropped",
+        )
+    };
+    // SAFETY: `slot` is valid and the field does not need to be dropped.
+    unsafe { ptr::write(slot, T::default()) };
+}
+
+/// A field that has been initialized by an initializer, `F` is a marker type named after the
+/// field.
+pub struct Mention<F>(PhantomData<F>);
+
+impl<F> Mention<F> {
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F> Default for Mention<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Index of [`Mentions`], the mention is the leaf itself.
+pub struct Here;
+
+/// Index of [`Mentions`], the mention is in the left half of the pair.
+pub struct Left<I>(PhantomData<I>);
+
+/// Index of [`Mentions`], the mention is in the right half of the pair.
+pub struct Right<I>(PhantomData<I>);
+
+/// Implemented by the marker types named after the fields of a `#[pin_data]` struct.
+pub trait FieldName {}
+
+/// Stands in for the marker type of a field that is disabled by `#[cfg(..)]`, it is mentioned by
+/// every tree.
+pub struct Disabled;
+
+/// Implemented by trees of [`Mention`]s that contain a mention of the field `F`, the index `I` is
+/// inferred and describes where the mention is located.
+///
+/// The mentions are arranged in a balanced tree, so the depth stays logarithmic in the number of
+/// fields.
+#[diagnostic::on_unimplemented(
+    message = "missing field `{F}` in initializer",
+    label = "missing `{F}`",
+    note = "only marker fields (`PhantomPinned`, `PhantomData<T>` and fields annotated with \
+        `#[pin_data(marker)]`) can be omitted"
+)]
+pub trait Mentions<F, I> {}
+
+impl<F> Mentions<F, Here> for Mention<F> {}
+
+impl<F: FieldName, I, L: Mentions<F, I>, R> Mentions<F, Left<I>> for (L, R) {}
+
+impl<F: FieldName, I, L, R: Mentions<F, I>> Mentions<F, Right<I>> for (L, R) {}
+
+impl<L> Mentions<Disabled, Disabled> for L {}
+
 /// This trait is automatically implemented for every type. It aims to provide the same type
 /// inference help as `HasPinData`.
 ///
@@ -91,6 +716,14 @@ pub unsafe trait HasInitData {
     type InitData: InitData;
 
     unsafe fn __init_data() -> Self::InitData;
+
+    /// Same as [`HasInitData::__init_data`], `slot` is only used for type inference.
+    #[inline(always)]
+    unsafe fn __init_data_of(slot: *mut Self) -> Self::InitData {
+        let _ = slot;
+        // SAFETY: The caller upholds the safety requirements.
+        unsafe { Self::__init_data() }
+    }
 }
 
 /// Same function as `PinData`, but for arbitrary data.
@@ -98,27 +731,46 @@ pub unsafe trait HasInitData {
 /// # Safety
 ///
 /// Only the `init` module is allowed to use this trait.
-pub unsafe trait InitData: Copy {
+pub unsafe trait InitData {
     type Datee: ?Sized + HasInitData;
 
     /// Type inference helper function.
-    fn make_closure<F, O, E>(self, f: F) -> F
+    fn make_closure<F, O, E>(&self, f: F) -> F
     where
         F: FnOnce(*mut Self::Datee) -> Result<O, E>,
     {
         f
     }
-}
 
-pub struct AllData<T: ?Sized>(PhantomData<fn(*const T) -> *const T>);
+    /// Initializes `slot` with the base initializer of the `..base` struct update syntax.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Init::__init`].
+    unsafe fn __init_base<E>(
+        &self,
+        slot: *mut Self::Datee,
+        init: impl Init<Self::Datee, E>,
+    ) -> Result<(), E> {
+        // SAFETY: The caller upholds the safety requirements.
+        unsafe { init.__init(slot) }
+    }
 
-impl<T: ?Sized> Clone for AllData<T> {
-    fn clone(&self) -> Self {
-        *self
+    /// Returns the name of the struct for [`InitError`].
+    fn __type_name(&self) -> &'static str {
+        core::any::type_name::<Self::Datee>()
+    }
+
+    /// Returns the tracker for the fields mentioned by an initializer.
+    fn __fields(&self) -> <Self::Datee as HasFields>::Fields
+    where
+        Self::Datee: HasFields,
+    {
+        <Self::Datee as HasFields>::__fields()
     }
 }
 
-impl<T: ?Sized> Copy for AllData<T> {}
+pub struct AllData<T: ?Sized>(PhantomData<fn(*const T) -> *const T>);
 
 unsafe impl<T: ?Sized> InitData for AllData<T> {
     type Datee = T;
@@ -180,8 +832,12 @@ impl<T> StackInit<T> {
             // SAFETY: `this.is_init` was true and therefore `this.value` is initialized.
             unsafe { this.value.assume_init_drop() };
         }
+        let slot = this.value.as_mut_ptr();
         // SAFETY: The memory slot is valid and this type ensures that it will stay pinned.
-        unsafe { init.__pinned_init(this.value.as_mut_ptr())? };
+        unsafe {
+            poison_uninit(slot);
+            poison_on_err(slot, init.__pinned_init(slot))?;
+        }
         // INVARIANT: `this.value` is initialized above.
         this.is_init = true;
         // SAFETY: The slot is now pinned, since we will never give access to `&mut T`.
@@ -214,11 +870,60 @@ fn stack_init_reuse() {
     println!("{value:?}");
 }
 
+/// Fills `slot` with [`POISON_UNINIT`] before an initializer runs on it, if the `debug-poison`
+/// feature is enabled.
+///
+/// # Safety
+///
+/// `slot` must be valid for writes and must not contain a value that needs to be dropped.
+///
+/// [`POISON_UNINIT`]: crate::POISON_UNINIT
+#[inline]
+pub(crate) unsafe fn poison_uninit<T>(slot: *mut T) {
+    #[cfg(feature = "debug-poison")]
+    // SAFETY: The caller guarantees that `slot` is valid for writes.
+    unsafe {
+        ptr::write_bytes(slot.cast::<u8>(), crate::POISON_UNINIT, mem::size_of::<T>())
+    };
+    #[cfg(not(feature = "debug-poison"))]
+    let _ = slot;
+}
+
+/// Fills `slot` with [`POISON_ROLLBACK`] if the initializer that ran on it returned an error and
+/// the `debug-poison` feature is enabled. Returns `res`.
+///
+/// # Safety
+///
+/// `slot` must be valid for writes and `res` must be the result of an initializer of `slot`.
+///
+/// [`POISON_ROLLBACK`]: crate::POISON_ROLLBACK
+#[inline]
+pub(crate) unsafe fn poison_on_err<T, E>(slot: *mut T, res: Result<(), E>) -> Result<(), E> {
+    #[cfg(feature = "debug-poison")]
+    if res.is_err() {
+        // SAFETY: The caller guarantees that `slot` is valid for writes. The initializer failed,
+        // so `slot` is uninitialized.
+        unsafe {
+            ptr::write_bytes(
+                slot.cast::<u8>(),
+                crate::POISON_ROLLBACK,
+                mem::size_of::<T>(),
+            )
+        };
+    }
+    #[cfg(not(feature = "debug-poison"))]
+    let _ = slot;
+    res
+}
+
 /// When a value of this type is dropped, it drops a `T`.
 ///
 /// Can be forgotten to prevent the drop.
 pub struct DropGuard<T: ?Sized> {
     ptr: *mut T,
+    /// The field that is reported as rolled back when the guard drops it.
+    #[cfg(feature = "trace")]
+    field: Option<crate::trace::Field>,
 }
 
 impl<T: ?Sized> DropGuard<T> {
@@ -234,19 +939,191 @@ impl<T: ?Sized> DropGuard<T> {
     /// - will not be dropped by any other means.
     #[inline]
     pub unsafe fn new(ptr: *mut T) -> Self {
-        Self { ptr }
+        Self {
+            ptr,
+            #[cfg(feature = "trace")]
+            field: None,
+        }
+    }
+
+    /// Creates a new [`DropGuard<T>`] for the initialized `field` like [`DropGuard::new`].
+    ///
+    /// With the `trace` feature, it reports that `field` has been initialized and that it is
+    /// rolled back when the guard gets dropped.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`DropGuard::new`].
+    #[inline]
+    pub unsafe fn traced(ptr: *mut T, field: TraceField) -> Self {
+        field.finish();
+        Self {
+            ptr,
+            #[cfg(feature = "trace")]
+            field: Some(field.field),
+        }
     }
 }
 
+// SAFETY: The guard owns the value at `ptr`, it only drops it.
+unsafe impl<T: ?Sized + Send> Send for DropGuard<T> {}
+
 impl<T: ?Sized> Drop for DropGuard<T> {
     #[inline]
     fn drop(&mut self) {
+        #[cfg(feature = "trace")]
+        if let Some(field) = self.field {
+            crate::trace::emit(crate::trace::Event::Rollback(field));
+        }
         // SAFETY: A `DropGuard` can only be constructed using the unsafe `new` function
         // ensuring that this operation is safe.
         unsafe { ptr::drop_in_place(self.ptr) }
     }
 }
 
+/// A field of a struct initializer or an element of an array, its steps are reported to
+/// [`crate::trace`] with the `trace` feature and its start is a failure point of
+/// [`crate::testing`] with the `testing` feature. Without them, this does nothing.
+#[derive(Clone, Copy)]
+pub struct TraceField {
+    #[cfg(feature = "trace")]
+    field: crate::trace::Field,
+}
+
+impl TraceField {
+    /// Reports that the initialization of the field `name` of `type_name` starts, `index` is its
+    /// position in the initializer.
+    #[inline]
+    pub fn start(type_name: &'static str, name: &'static str, index: usize) -> Self {
+        #[cfg(feature = "testing")]
+        crate::testing::fail_point();
+        #[cfg(feature = "trace")]
+        {
+            let field = crate::trace::Field {
+                type_name,
+                name,
+                index,
+            };
+            crate::trace::emit(crate::trace::Event::Start(field));
+            Self { field }
+        }
+        #[cfg(not(feature = "trace"))]
+        {
+            let _ = (type_name, name, index);
+            Self {}
+        }
+    }
+
+    /// Reports that the field has been initialized.
+    #[inline]
+    pub fn finish(self) {
+        #[cfg(feature = "trace")]
+        crate::trace::emit(crate::trace::Event::Finish(self.field));
+    }
+}
+
+/// Returns the name of the type of `slot` for [`TraceField::start`].
+#[inline]
+pub fn type_name_of<T: ?Sized>(slot: *mut T) -> &'static str {
+    let _ = slot;
+    core::any::type_name::<T>()
+}
+
+/// Reports an error of the initializer of `type_name` with the `trace` feature.
+#[inline]
+pub fn trace_result<E>(type_name: &'static str, res: Result<(), E>) -> Result<(), E> {
+    #[cfg(feature = "trace")]
+    if res.is_err() {
+        crate::trace::emit(crate::trace::Event::Error { type_name });
+    }
+    #[cfg(not(feature = "trace"))]
+    let _ = type_name;
+    res
+}
+
+/// Drops a value that has been initialized by the base initializer of the `..base` struct update
+/// syntax.
+///
+/// While a field is being re-initialized, it is excluded from the drop.
+///
+/// Can be forgotten to prevent the drop.
+pub struct BaseGuard<T, D> {
+    ptr: *mut T,
+    /// The pin-data of `T`, it is used to look up the position of a field.
+    data: D,
+    except: Option<usize>,
+    drop_fields_except: unsafe fn(*mut T, usize),
+}
+
+impl<T, D> BaseGuard<T, D> {
+    /// Creates a new [`BaseGuard<T>`]. It will drop `ptr` when it gets dropped.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a valid pointer to an initialized value.
+    ///
+    /// It is the callers responsibility that `self` will only get dropped if the pointee of `ptr`:
+    /// - has not been dropped,
+    /// - is not accessible by any other means,
+    /// - will not be dropped by any other means.
+    #[inline]
+    pub unsafe fn new(ptr: *mut T) -> Self
+    where
+        T: HasPinData<PinData = D>,
+    {
+        Self {
+            ptr,
+            // SAFETY: The pin-data is only used to look up the positions of fields.
+            data: unsafe { T::__pin_data() },
+            except: None,
+            drop_fields_except: T::__drop_fields_except,
+        }
+    }
+
+    /// Drops the field at `field` and excludes it from the drop until [`BaseGuard::init_field`] is
+    /// called.
+    ///
+    /// `index` returns the position of the field from its projection in the pin-data.
+    ///
+    /// # Safety
+    ///
+    /// `field` must point to the field of the value pointed to by `self` whose position is returned
+    /// by `index`.
+    #[inline]
+    pub unsafe fn uninit_field<F>(&mut self, index: impl FnOnce(&D) -> usize, field: *mut F) {
+        self.except = Some(index(&self.data));
+        // SAFETY: The caller guarantees that `field` is a field of our value. It is initialized,
+        // since `except` was `None` before.
+        unsafe { ptr::drop_in_place(field) };
+    }
+
+    /// Marks the field that has been passed to [`BaseGuard::uninit_field`] as initialized again.
+    ///
+    /// # Safety
+    ///
+    /// The field must have been re-initialized.
+    #[inline]
+    pub unsafe fn init_field(&mut self) {
+        self.except = None;
+    }
+}
+
+// SAFETY: The guard owns the value at `ptr`, it only drops it.
+unsafe impl<T: Send, D: Send> Send for BaseGuard<T, D> {}
+
+impl<T, D> Drop for BaseGuard<T, D> {
+    #[inline]
+    fn drop(&mut self) {
+        match self.except {
+            // SAFETY: A `BaseGuard` can only be constructed using the unsafe `new` function
+            // ensuring that this operation is safe.
+            None => unsafe { ptr::drop_in_place(self.ptr) },
+            // SAFETY: All fields except `except` are initialized, see `uninit_field`.
+            Some(except) => unsafe { (self.drop_fields_except)(self.ptr, except) },
+        }
+    }
+}
+
 /// Token used by `PinnedDrop` to prevent calling the function without creating this unsafely
 /// created struct. This is needed, because the `drop` function is safe, but should not be called
 /// manually.
@@ -248,10 +248,12 @@ use alloc::{boxed::Box, sync::Arc};
 use std::sync::Arc;
 
 use core::{
+    any::type_name,
     cell::UnsafeCell,
     convert::Infallible,
+    future::Future,
     marker::PhantomData,
-    mem::MaybeUninit,
+    mem::{self, MaybeUninit},
     num::*,
     pin::Pin,
     ptr::{self, NonNull},
@@ -269,9 +271,31 @@ type AllocError = Infallible;
 pub mod __internal;
 #[doc(hidden)]
 pub mod macros;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "trace")]
+pub mod trace;
+
+/// The byte that fills a slot before an initializer runs on it, with the `debug-poison` feature.
+///
+/// [`InPlaceInit`], [`InPlaceWrite`] and [`stack_pin_init!`] write it to the whole slot, so reads
+/// of fields that the initializer did not write yet show up as `0x5a5a...`.
+#[cfg(feature = "debug-poison")]
+pub const POISON_UNINIT: u8 = 0x5a;
+
+/// The byte that fills a slot after its initializer returned an error, with the `debug-poison`
+/// feature.
+///
+/// The fields that were initialized have been dropped at that point, so the slot is filled again
+/// to catch code that still uses them.
+#[cfg(feature = "debug-poison")]
+pub const POISON_ROLLBACK: u8 = 0x6b;
 
 pub use pinned_init_macro::{pin_data, pinned_drop, Zeroable};
 
+#[doc(hidden)]
+pub use pinned_init_macro::__init;
+
 /// Initialize and pin a type directly on the stack.
 ///
 /// # Examples
@@ -563,6 +587,33 @@ macro_rules! stack_try_pin_init {
 /// - Using struct update syntax one can place `..Zeroable::zeroed()` at the very end of the
 ///   struct, this initializes every field with 0 and then runs all initializers specified in the
 ///   body. This can only be done if [`Zeroable`] is implemented for the struct.
+/// - Similarly one can place `..Default` at the very end of the struct, this initializes every
+///   field that has not been mentioned with the default value declared via `#[default(value)]` or
+///   `#[default_init(initializer)]` in `#[pin_data]`. Fields without a default value still have to
+///   be initialized. This is only supported by `[try_]pin_init!`.
+/// - Lastly one can place `..base` at the very end of the struct, where `base` is an initializer
+///   for the whole struct. It runs first, then every mentioned field is dropped and initialized
+///   again in place. Pinned fields are never moved. This requires that the struct has been
+///   annotated with `#[pin_data]`.
+/// - A field can be initialized with a nested struct initializer `inner: Inner { x: 1, m <- m }`.
+///   If one of its fields uses `<-`, the nested struct is initialized in place just like
+///   `inner <- try_pin_init!(Inner { x: 1, m <- m }? E)`, where `E` is the error of the outer
+///   initializer. Otherwise it is a normal struct expression.
+/// - Fields can be annotated with `#[cfg(..)]`, they are then only initialized if the
+///   configuration predicate holds. This should match the `#[cfg(..)]` of the field declaration.
+/// - The struct can be named by any type path that resolves to it, e.g. `Self`, a type alias
+///   `Alias::<T>`, an associated type `D::State` or a qualified path `<D as Driver>::State`.
+/// - Marker fields can be omitted when the initializer ends with `..`, they are then initialized
+///   with their default value. These are fields of type `PhantomPinned` or `PhantomData<T>` and
+///   fields annotated with `#[pin_data(marker)]` of a `#[pin_data]` struct, which have to be
+///   zero-sized, must not need to be dropped and must implement [`Default`]. The `..` is required
+///   because the macro does not know the fields of the struct: without it, every field has to be
+///   mentioned and a missing field is reported by the compiler as for a struct expression. With
+///   it, only the marker fields can be omitted.
+/// - Instead of a struct, a tuple `(a, <- b)` or an array `[<- a, <- b]` can be initialized. Each
+///   element is either a value or an initializer prefixed by `<-`. The elements are initialized
+///   in order and, if one of them fails, the elements that have already been initialized are
+///   dropped.
 ///
 /// For instance:
 ///
@@ -581,20 +632,99 @@ macro_rules! stack_try_pin_init {
 /// let init = pin_init!(&this in Buf {
 ///     buf: [0; 64],
 ///     ptr: unsafe { addr_of_mut!((*this.as_ptr()).buf).cast() },
-///     pin: PhantomPinned,
+///     // `pin` is a marker field and is initialized by `..`.
+///     ..
+/// });
+/// # let _ = Box::pin_init(init);
+/// ```
+///
+/// And with default values:
+///
+/// ```rust
+/// # use pinned_init::*;
+/// #[pin_data]
+/// struct Config {
+///     name: &'static str,
+///     #[default(16)]
+///     depth: usize,
+///     #[default(Vec::new())]
+///     items: Vec<u32>,
+/// }
+///
+/// let init = pin_init!(Config {
+///     name: "small",
+///     depth: 4,
+///     ..Default
+/// });
+/// # let _ = Box::pin_init(init);
+/// ```
+///
+/// And with a base initializer:
+///
+/// ```rust
+/// # use pinned_init::*;
+/// #[pin_data]
+/// struct Config {
+///     name: &'static str,
+///     depth: usize,
+///     items: Vec<u32>,
+/// }
+///
+/// impl Config {
+///     fn low_latency() -> impl PinInit<Self> {
+///         pin_init!(Self {
+///             name: "low latency",
+///             depth: 1,
+///             items: Vec::new(),
+///         })
+///     }
+/// }
+///
+/// let init = pin_init!(Config {
+///     depth: 2,
+///     ..Config::low_latency()
 /// });
 /// # let _ = Box::pin_init(init);
 /// ```
 ///
+/// And with an associated type:
+///
+/// ```rust
+/// # use pinned_init::*;
+/// #[pin_data]
+/// struct State {
+///     count: usize,
+/// }
+///
+/// trait Driver {
+///     type State;
+/// }
+///
+/// fn state<D: Driver<State = State>>() -> impl PinInit<D::State> {
+///     pin_init!(<D as Driver>::State { count: 0 })
+/// }
+/// ```
+///
+/// And without declaring a struct:
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// # use pinned_init::*;
+/// let init = pin_init!((<- CMutex::new(0u32), <- CMutex::new(1u32)));
+/// let pair = Box::pin_init(init).unwrap();
+/// assert_eq!(*pair.1.lock(), 1);
+///
+/// let init = pin_init!([<- CMutex::new(0u32), <- CMutex::new(1u32), <- CMutex::new(2u32)]);
+/// let mutexes = Box::pin_init(init).unwrap();
+/// assert_eq!(*mutexes[2].lock(), 2);
+/// ```
+///
 /// [`NonNull<Self>`]: core::ptr::NonNull
 #[macro_export]
 macro_rules! pin_init {
-    ($(&$this:ident in)? $t:ident $(::<$($generics:ty),* $(,)?>)? {
-        $($fields:tt)*
-    }) => {
-        $crate::try_pin_init!($(&$this in)? $t $(::<$($generics),*>)? {
-            $($fields)*
-        }? ::core::convert::Infallible)
+    ($($init:tt)*) => {
+        $crate::__init!($crate, pin_init, $($init)*)
     };
 }
 
@@ -611,6 +741,9 @@ macro_rules! pin_init {
 /// The syntax is identical to [`pin_init!`] with the following exception: you must append `? $type`
 /// after the `struct` initializer to specify the error type you want to use.
 ///
+/// Append `? $type with context` instead to get an [`InitError`] that records which field
+/// failed to initialize.
+///
 /// # Examples
 ///
 /// ```rust
@@ -637,19 +770,8 @@ macro_rules! pin_init {
 /// ```
 #[macro_export]
 macro_rules! try_pin_init {
-    ($(&$this:ident in)? $t:ident $(::<$($generics:ty),* $(,)?>)? {
-        $($fields:tt)*
-    }? $err:ty) => {
-        $crate::__init_internal!(
-            @this($($this)?),
-            @typ($t $(::<$($generics),*>)? ),
-            @fields($($fields)*),
-            @error($err),
-            @data(PinData, use_data),
-            @has_data(HasPinData, __pin_data),
-            @construct_closure(pin_init_from_closure),
-            @munch_fields($($fields)*),
-        )
+    ($($init:tt)*) => {
+        $crate::__init!($crate, try_pin_init, $($init)*)
     };
 }
 
@@ -690,12 +812,8 @@ macro_rules! try_pin_init {
 /// ```
 #[macro_export]
 macro_rules! init {
-    ($(&$this:ident in)? $t:ident $(::<$($generics:ty),* $(,)?>)? {
-        $($fields:tt)*
-    }) => {
-        $crate::try_init!($(&$this in)? $t $(::<$($generics),*>)? {
-            $($fields)*
-        }? ::core::convert::Infallible)
+    ($($init:tt)*) => {
+        $crate::__init!($crate, init, $($init)*)
     };
 }
 
@@ -735,19 +853,109 @@ macro_rules! init {
 /// ```
 #[macro_export]
 macro_rules! try_init {
-    ($(&$this:ident in)? $t:ident $(::<$($generics:ty),* $(,)?>)? {
-        $($fields:tt)*
-    }? $err:ty) => {
-        $crate::__init_internal!(
-            @this($($this)?),
-            @typ($t $(::<$($generics),*>)?),
-            @fields($($fields)*),
-            @error($err),
-            @data(InitData, /*no use_data*/),
-            @has_data(HasInitData, __init_data),
-            @construct_closure(init_from_closure),
-            @munch_fields($($fields)*),
-        )
+    ($($init:tt)*) => {
+        $crate::__init!($crate, try_init, $($init)*)
+    };
+}
+
+/// Construct an asynchronous in-place initializer for `struct`s.
+///
+/// This macro defaults the error to [`Infallible`]. If you need a different error, then use
+/// [`try_async_pin_init!`].
+///
+/// The syntax is identical to [`pin_init!`], but the expressions may `.await` and structurally
+/// pinned fields can also be initialized with an [`AsyncPinInit`] via `<-`. The fields are still
+/// initialized in place and in order, so the value never has to be moved after an `.await`.
+///
+/// If the returned future is dropped before it completes, the fields that have already been
+/// initialized are dropped again, just like when an error occurs.
+///
+/// Only struct initializers are supported, tuples and arrays are not.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// # use pinned_init::*;
+/// # async fn handshake(peer: u32) -> u32 { peer }
+/// #[pin_data]
+/// struct Connection {
+///     peer: u32,
+///     #[pin]
+///     state: CMutex<u32>,
+/// }
+///
+/// impl Connection {
+///     fn new(peer: u32) -> impl AsyncPinInit<Self> {
+///         async_pin_init!(Self {
+///             peer,
+///             state <- CMutex::new(handshake(peer).await),
+///         })
+///     }
+/// }
+/// # let _ = Box::pin_init_async(Connection::new(1));
+/// ```
+#[macro_export]
+macro_rules! async_pin_init {
+    ($($init:tt)*) => {
+        $crate::__init!($crate, async_pin_init, $($init)*)
+    };
+}
+
+/// Construct an asynchronous fallible in-place initializer for `struct`s.
+///
+/// If the initialization can complete without error (or [`Infallible`]), then use
+/// [`async_pin_init!`].
+///
+/// The syntax is identical to [`try_pin_init!`], but the expressions may `.await` like in
+/// [`async_pin_init!`].
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/error.rs"] mod error; use error::Error;
+/// # use pinned_init::*;
+/// # use core::marker::PhantomPinned;
+/// # async fn handshake(peer: u32) -> Result<u32, Error> { Ok(peer) }
+/// #[pin_data]
+/// struct Session {
+///     key: u32,
+///     #[pin]
+///     _pin: PhantomPinned,
+/// }
+///
+/// #[pin_data]
+/// struct Connection {
+///     peer: u32,
+///     #[pin]
+///     session: Session,
+/// }
+///
+/// impl Session {
+///     fn new(peer: u32) -> impl AsyncPinInit<Self, Error> {
+///         try_async_pin_init!(Self {
+///             key: handshake(peer).await?,
+///             _pin: PhantomPinned,
+///         }? Error)
+///     }
+/// }
+///
+/// impl Connection {
+///     fn new(peer: u32) -> impl AsyncPinInit<Self, Error> {
+///         try_async_pin_init!(Self {
+///             peer,
+///             session <- Session::new(peer),
+///         }? Error)
+///     }
+/// }
+/// # let _ = Box::try_pin_init_async(Connection::new(1));
+/// ```
+#[macro_export]
+macro_rules! try_async_pin_init {
+    ($($init:tt)*) => {
+        $crate::__init!($crate, try_async_pin_init, $($init)*)
     };
 }
 
@@ -809,7 +1017,7 @@ macro_rules! assert_pinned {
             let data = unsafe { <$ty as $crate::__internal::HasPinData>::__pin_data() };
             let init = $crate::__internal::AlwaysFail::<$field_ty>::new();
             // SAFETY: This code is unreachable.
-            unsafe { data.$field(ptr, init) }.ok();
+            unsafe { data.$field().__init(ptr, init) }.ok();
         };
     };
 
@@ -878,6 +1086,43 @@ pub unsafe trait PinInit<T: ?Sized, E = Infallible>: Sized {
     {
         ChainPinInit(self, f, PhantomData)
     }
+
+    /// Catches a panic of the initializer and returns it as an error.
+    ///
+    /// The fields that have been initialized before the panic are dropped while unwinding, just
+    /// like when an error occurs. This is done by the initializers of the macros and the
+    /// combinators of this crate, a hand-written initializer (e.g. from [`pin_init_from_closure`])
+    /// that does not drop its values when it panics leaks them. The payload of the panic is
+    /// returned in [`PanicError::Panic`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #![feature(allocator_api)]
+    /// # use core::alloc::AllocError;
+    /// # use pinned_init::*;
+    /// #[pin_data]
+    /// struct Plugin {
+    ///     name: String,
+    ///     id: u32,
+    /// }
+    ///
+    /// fn load() -> u32 {
+    ///     panic!("plugin is broken");
+    /// }
+    ///
+    /// let init = try_pin_init!(Plugin {
+    ///     name: "broken".to_owned(),
+    ///     id: load(),
+    /// }? AllocError);
+    /// let err = Box::<Plugin>::try_pin_init(init.catch_unwind()).err().unwrap();
+    /// assert!(matches!(err, PanicError::Panic(_)));
+    /// assert_eq!(err.to_string(), "initializer panicked: plugin is broken");
+    /// ```
+    #[cfg(feature = "std")]
+    fn catch_unwind(self) -> CatchUnwind<Self, T, E> {
+        CatchUnwind(self, PhantomData)
+    }
 }
 
 /// An initializer returned by [`PinInit::pin_chain`].
@@ -904,6 +1149,202 @@ where
     }
 }
 
+/// An initializer returned by [`PinInit::catch_unwind`].
+#[cfg(feature = "std")]
+pub struct CatchUnwind<I, T: ?Sized, E>(I, __internal::Invariant<(E, *const T)>);
+
+// SAFETY: The `__pinned_init` function forwards to the inner initializer. If it panics, `slot`
+// can be deallocated without being dropped, `Box::try_pin_init` and the other users of
+// initializers rely on this while unwinding as well. Values that the inner initializer created in
+// `slot` and did not drop before panicking (only the initializers of the macros and the
+// combinators of this crate drop them) are leaked, which is safe. So `slot` can be treated like
+// after an error.
+#[cfg(feature = "std")]
+unsafe impl<T: ?Sized, E, I> PinInit<T, PanicError<E>> for CatchUnwind<I, T, E>
+where
+    I: PinInit<T, E>,
+{
+    unsafe fn __pinned_init(self, slot: *mut T) -> Result<(), PanicError<E>> {
+        // SAFETY: All requirements fulfilled since this function is `__pinned_init`.
+        let init = std::panic::AssertUnwindSafe(|| unsafe { self.0.__pinned_init(slot) });
+        match std::panic::catch_unwind(init) {
+            Ok(res) => res.map_err(PanicError::Error),
+            Err(payload) => Err(PanicError::Panic(payload)),
+        }
+    }
+}
+
+// SAFETY: Same as the `PinInit` implementation, the inner initializer does not rely on `slot`
+// being pinned.
+#[cfg(feature = "std")]
+unsafe impl<T: ?Sized, E, I> Init<T, PanicError<E>> for CatchUnwind<I, T, E>
+where
+    I: Init<T, E>,
+{
+    unsafe fn __init(self, slot: *mut T) -> Result<(), PanicError<E>> {
+        // SAFETY: All requirements fulfilled since this function is `__init`.
+        let init = std::panic::AssertUnwindSafe(|| unsafe { self.0.__init(slot) });
+        match std::panic::catch_unwind(init) {
+            Ok(res) => res.map_err(PanicError::Error),
+            Err(payload) => Err(PanicError::Panic(payload)),
+        }
+    }
+}
+
+/// The error of an initializer returned by [`PinInit::catch_unwind`].
+#[cfg(feature = "std")]
+pub enum PanicError<E> {
+    /// The initializer returned an error.
+    Error(E),
+    /// The initializer panicked, this is the payload of the panic.
+    ///
+    /// Use [`std::panic::resume_unwind`] to continue unwinding.
+    Panic(Box<dyn core::any::Any + Send + 'static>),
+}
+
+#[cfg(feature = "std")]
+impl<E> PanicError<E> {
+    /// Returns the message of the panic, if it has been created by `panic!` with a message.
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Self::Error(_) => None,
+            Self::Panic(payload) => payload
+                .downcast_ref::<&'static str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: From<AllocError>> From<AllocError> for PanicError<E> {
+    fn from(err: AllocError) -> Self {
+        Self::Error(E::from(err))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: core::fmt::Debug> core::fmt::Debug for PanicError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Error(err) => f.debug_tuple("Error").field(err).finish(),
+            Self::Panic(_) => match self.message() {
+                Some(msg) => f.debug_tuple("Panic").field(&msg).finish(),
+                None => f.debug_tuple("Panic").field(&format_args!("..")).finish(),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: core::fmt::Display> core::fmt::Display for PanicError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Error(err) => err.fmt(f),
+            Self::Panic(_) => match self.message() {
+                Some(msg) => write!(f, "initializer panicked: {msg}"),
+                None => f.write_str("initializer panicked"),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for PanicError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            // `Display` already shows the error itself.
+            Self::Error(err) => err.source(),
+            Self::Panic(_) => None,
+        }
+    }
+}
+
+/// The error of an initializer created with `? Error with context`, it records which field failed.
+///
+/// The `[try_][pin_]init!` macros wrap every error of a field with the name of the struct and the
+/// field. A nested initializer `inner: Inner { .. }` also adds context, its fields are reported
+/// as `inner.field` of the outer struct. Errors of initializers that already are an
+/// [`InitError`] are forwarded unchanged, they point at the innermost field that failed.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/error.rs"] mod error; use error::Error;
+/// # use pinned_init::*;
+/// #[pin_data]
+/// struct Ring {
+///     buf: [u8; 64],
+///     head: usize,
+/// }
+///
+/// #[pin_data]
+/// struct Device {
+///     id: u32,
+///     #[pin]
+///     rx: Ring,
+/// }
+///
+/// fn head() -> Result<usize, Error> {
+///     Err(Error)
+/// }
+///
+/// let init = try_pin_init!(Device {
+///     id: 0,
+///     rx: Ring {
+///         buf <- zeroed(),
+///         head: head()?,
+///     },
+/// }? Error with context);
+/// let err = Box::try_pin_init(init).err().unwrap();
+/// assert_eq!(err.field, "rx.head");
+/// assert!(err.type_name.ends_with("Device"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitError<E> {
+    /// The name of the struct as returned by [`core::any::type_name`].
+    ///
+    /// It is empty if the error does not belong to a field, e.g. when the allocation failed.
+    pub type_name: &'static str,
+    /// The name of the field that failed to initialize, fields of nested initializers are
+    /// separated by `.`.
+    pub field: &'static str,
+    /// The error of the field.
+    pub source: E,
+}
+
+impl<E: From<AllocError>> From<AllocError> for InitError<E> {
+    fn from(err: AllocError) -> Self {
+        Self {
+            type_name: "",
+            field: "",
+            source: E::from(err),
+        }
+    }
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for InitError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if !self.field.is_empty() {
+            write!(
+                f,
+                "failed to initialize `{}` of `{}`: ",
+                self.field, self.type_name
+            )?;
+        }
+        self.source.fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for InitError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        // `Display` already shows the error itself.
+        self.source.source()
+    }
+}
+
 /// An initializer for `T`.
 ///
 /// To use this initializer, you will need a suitable memory location that can hold a `T`. This can
@@ -1050,6 +1491,88 @@ pub const unsafe fn init_from_closure<T: ?Sized, E>(
     __internal::InitClosure(f, PhantomData)
 }
 
+/// An asynchronous pin-initializer for the type `T`.
+///
+/// The initialization is a future that writes into a pinned slot. The slot stays reserved while
+/// the future is pending, so it can await other futures, e.g. a handshake, before the value is
+/// fully constructed. Use [`InPlaceInitAsync::try_pin_init_async`] to allocate the slot and
+/// [`async_pin_init!`] to create an initializer for a struct.
+///
+/// # `Send`
+///
+/// The future is an associated type, so `impl AsyncPinInit<T, E>` hides whether it is [`Send`].
+/// Functions returning an initializer that is used on a multi-threaded executor should add the
+/// bound to it:
+///
+/// ```rust
+/// # use pinned_init::*;
+/// # use core::convert::Infallible;
+/// #[pin_data]
+/// struct Counter {
+///     count: usize,
+/// }
+///
+/// fn counter() -> impl AsyncPinInit<Counter, Infallible, Future: Send> {
+///     async_pin_init!(Counter { count: 0 })
+/// }
+/// ```
+///
+/// # Safety
+///
+/// When implementing this trait you will need to take great care. Use
+/// [`async_pin_init_from_closure`] where possible.
+///
+/// The future returned by [`AsyncPinInit::__pinned_init_async`]:
+/// - resolves to `Ok(())` if it initialized every field of `slot`,
+/// - resolves to `Err(err)` if it encountered an error and then cleaned `slot`, this means:
+///     - `slot` can be deallocated without UB occurring,
+///     - `slot` does not need to be dropped,
+///     - `slot` is not partially initialized.
+/// - drops everything it has initialized when it is dropped before it resolved, `slot` is then in
+///   the same state as after an error.
+/// - while constructing the `T` at `slot` it upholds the pinning invariants of `T`.
+#[must_use = "An initializer must be used in order to create its value."]
+pub unsafe trait AsyncPinInit<T: ?Sized, E = Infallible>: Sized {
+    /// The future initializing the slot.
+    type Future: Future<Output = Result<(), E>>;
+
+    /// Returns a future that initializes `slot`.
+    ///
+    /// # Safety
+    ///
+    /// - `slot` is a valid pointer to uninitialized memory until the future resolves or is
+    ///   dropped.
+    /// - the caller does not touch `slot` until then, and when `Err` is returned or the future is
+    ///   dropped before it resolved, they are only permitted to deallocate.
+    /// - `slot` will not move until it is dropped, i.e. it will be pinned.
+    /// - if the future is leaked, `slot` is never deallocated.
+    unsafe fn __pinned_init_async(self, slot: *mut T) -> Self::Future;
+}
+
+/// Creates a new [`AsyncPinInit<T, E>`] from the given closure.
+///
+/// # Safety
+///
+/// The closure returns a future that:
+/// - resolves to `Ok(())` if it initialized every field of `slot`,
+/// - resolves to `Err(err)` if it encountered an error and then cleaned `slot`, this means:
+///     - `slot` can be deallocated without UB occurring,
+///     - `slot` does not need to be dropped,
+///     - `slot` is not partially initialized.
+/// - drops everything it has initialized when it is dropped before it resolved.
+/// - may assume that `slot` will not move until it is dropped, i.e. it will be pinned.
+/// - while constructing the `T` at `slot` it upholds the pinning invariants of `T`.
+#[inline]
+pub const unsafe fn async_pin_init_from_closure<T: ?Sized, E, F, Fut>(
+    f: F,
+) -> impl AsyncPinInit<T, E, Future = Fut>
+where
+    F: FnOnce(*mut T) -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+{
+    __internal::AsyncInitClosure(f, PhantomData)
+}
+
 /// An initializer that leaves the memory uninitialized.
 ///
 /// The initializer is a no-op. The `slot` memory is not changed.
@@ -1059,6 +1582,29 @@ pub fn uninit<T, E>() -> impl Init<MaybeUninit<T>, E> {
     unsafe { init_from_closure(|_| Ok(())) }
 }
 
+/// Drops the initialized elements `slot[0..len]` of an array when an element fails to initialize
+/// or panics.
+struct ArrayGuard<T, const N: usize> {
+    slot: *mut T,
+    len: usize,
+}
+
+impl<T, const N: usize> Drop for ArrayGuard<T, N> {
+    fn drop(&mut self) {
+        #[cfg(feature = "trace")]
+        for index in 0..self.len {
+            trace::emit(trace::Event::Rollback(trace::Field {
+                type_name: type_name::<[T; N]>(),
+                name: "",
+                index,
+            }));
+        }
+        // SAFETY: The elements `slot[0..len]` have been initialized and are not used again,
+        // the array is considered uninitialized memory when the guard is dropped.
+        unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.slot, self.len)) };
+    }
+}
+
 /// Initializes an array by initializing each element via the provided initializer.
 ///
 /// # Examples
@@ -1077,22 +1623,28 @@ where
 {
     let init = move |slot: *mut [T; N]| {
         let slot = slot.cast::<T>();
+        let mut guard = ArrayGuard::<T, N> { slot, len: 0 };
         for i in 0..N {
+            let element = __internal::TraceField::start(type_name::<[T; N]>(), "", i);
             let init = make_init(i);
             // SAFETY: Since 0 <= `i` < N, it is still in bounds of `[T; N]`.
             let ptr = unsafe { slot.add(i) };
             // SAFETY: The pointer is derived from `slot` and thus satisfies the `__init`
             // requirements.
             match unsafe { init.__init(ptr) } {
-                Ok(()) => {}
+                Ok(()) => {
+                    guard.len += 1;
+                    element.finish();
+                }
                 Err(e) => {
-                    // SAFETY: The loop has initialized the elements `slot[0..i]` and since we
-                    // return `Err` below, `slot` will be considered uninitialized memory.
-                    unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(slot, i)) };
-                    return Err(e);
+                    // Drop the elements `slot[0..i]`, since we return `Err` below, `slot` will be
+                    // considered uninitialized memory.
+                    drop(guard);
+                    return __internal::trace_result(type_name::<[T; N]>(), Err(e));
                 }
             }
         }
+        mem::forget(guard);
         Ok(())
     };
     // SAFETY: The initializer above initializes every element of the array. On failure it drops
@@ -1123,22 +1675,28 @@ where
 {
     let init = move |slot: *mut [T; N]| {
         let slot = slot.cast::<T>();
+        let mut guard = ArrayGuard::<T, N> { slot, len: 0 };
         for i in 0..N {
+            let element = __internal::TraceField::start(type_name::<[T; N]>(), "", i);
             let init = make_init(i);
             // SAFETY: Since 0 <= `i` < N, it is still in bounds of `[T; N]`.
             let ptr = unsafe { slot.add(i) };
             // SAFETY: The pointer is derived from `slot` and thus satisfies the `__init`
             // requirements.
             match unsafe { init.__pinned_init(ptr) } {
-                Ok(()) => {}
+                Ok(()) => {
+                    guard.len += 1;
+                    element.finish();
+                }
                 Err(e) => {
-                    // SAFETY: The loop has initialized the elements `slot[0..i]` and since we
-                    // return `Err` below, `slot` will be considered uninitialized memory.
-                    unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(slot, i)) };
-                    return Err(e);
+                    // Drop the elements `slot[0..i]`, since we return `Err` below, `slot` will be
+                    // considered uninitialized memory.
+                    drop(guard);
+                    return __internal::trace_result(type_name::<[T; N]>(), Err(e));
                 }
             }
         }
+        mem::forget(guard);
         Ok(())
     };
     // SAFETY: The initializer above initializes every element of the array. On failure it drops
@@ -1146,6 +1704,188 @@ where
     unsafe { pin_init_from_closure(init) }
 }
 
+/// Allocates a new [`Box`] and pin-initializes its contents with `init`.
+///
+/// This allows initializing a field holding a [`Pin<Box<T>>`] in-place, so that the allocation
+/// and its error are handled by the surrounding initializer.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/error.rs"] mod error; use error::Error;
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// # use pinned_init::*;
+/// # use core::pin::Pin;
+/// #[pin_data]
+/// struct Queue {
+///     #[pin]
+///     items: CMutex<Vec<u32>>,
+/// }
+///
+/// impl Queue {
+///     fn new() -> impl PinInit<Self, Error> {
+///         try_pin_init!(Self {
+///             items <- CMutex::new(Vec::new()),
+///         }? Error)
+///     }
+/// }
+///
+/// #[pin_data]
+/// struct Device {
+///     id: u32,
+///     // Every queue has its own allocation.
+///     rx: Pin<Box<Queue>>,
+///     tx: Pin<Box<Queue>>,
+/// }
+///
+/// impl Device {
+///     fn new(id: u32) -> impl PinInit<Self, Error> {
+///         try_pin_init!(Self {
+///             id,
+///             rx <- boxed(Queue::new()),
+///             tx <- boxed(Queue::new()),
+///         }? Error)
+///     }
+/// }
+/// # let _ = Box::try_pin_init(Device::new(0));
+/// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub fn boxed<T, E>(init: impl PinInit<T, E>) -> impl Init<Pin<Box<T>>, E>
+where
+    E: From<AllocError>,
+{
+    let init = move |slot: *mut Pin<Box<T>>| {
+        let value = Box::try_pin_init(init)?;
+        // SAFETY: `slot` is valid for writes.
+        unsafe { slot.write(value) };
+        Ok(())
+    };
+    // SAFETY: The initializer above either writes the allocated box to `slot` or returns `Err`.
+    unsafe { init_from_closure(init) }
+}
+
+/// A heap-allocated pin-initializer with an erased type.
+///
+/// The initializers created by the `[try_][pin_]init!` macros and most functions have unnameable
+/// types, and [`PinInit`] cannot be used as a trait object. [`DynPinInit`] boxes an initializer
+/// and implements [`PinInit`] itself, so initializers of different types can be returned from the
+/// branches of a `match` or stored in a collection, while the value is still initialized in-place.
+///
+/// The boxed initializer may borrow data for the lifetime `'a`. [`DynPinInit`] is not [`Send`],
+/// use [`SendDynPinInit`] to move the initializers to other threads.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/error.rs"] mod error; use error::Error;
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// # use pinned_init::*;
+/// #[pin_data]
+/// struct Backend {
+///     name: &'static str,
+///     #[pin]
+///     queue: CMutex<Vec<u32>>,
+/// }
+///
+/// fn null() -> impl PinInit<Backend, Error> {
+///     try_pin_init!(Backend {
+///         name: "null",
+///         queue <- CMutex::new(Vec::new()),
+///     }? Error)
+/// }
+///
+/// fn ring(len: usize) -> impl PinInit<Backend, Error> {
+///     try_pin_init!(Backend {
+///         name: "ring",
+///         queue <- CMutex::new(Vec::with_capacity(len)),
+///     }? Error)
+/// }
+///
+/// fn select(config: &str) -> DynPinInit<'static, Backend, Error> {
+///     match config {
+///         "ring" => DynPinInit::new(ring(16)),
+///         _ => DynPinInit::new(null()),
+///     }
+/// }
+///
+/// let backend = Box::<Backend>::try_pin_init(select("ring")).unwrap();
+/// assert_eq!(backend.name, "ring");
+/// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub struct DynPinInit<'a, T: ?Sized, E = Infallible>(Box<dyn __internal::BoxedPinInit<T, E> + 'a>);
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<'a, T: ?Sized, E> DynPinInit<'a, T, E> {
+    /// Moves `init` to the heap and erases its type.
+    ///
+    /// This is not a `From` implementation, because [`DynPinInit`] is a [`PinInit`] itself and
+    /// would conflict with `impl<T> From<T> for T`.
+    pub fn new(init: impl PinInit<T, E> + 'a) -> Self {
+        Self(Box::new(init))
+    }
+}
+
+// SAFETY: `__pinned_init` forwards to the boxed initializer, which upholds the requirements.
+#[cfg(any(feature = "std", feature = "alloc"))]
+unsafe impl<T: ?Sized, E> PinInit<T, E> for DynPinInit<'_, T, E> {
+    unsafe fn __pinned_init(self, slot: *mut T) -> Result<(), E> {
+        // SAFETY: All requirements fulfilled since this function is `__pinned_init`.
+        unsafe { self.0.__pinned_init_boxed(slot) }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<'a, T: ?Sized, E> From<SendDynPinInit<'a, T, E>> for DynPinInit<'a, T, E> {
+    fn from(init: SendDynPinInit<'a, T, E>) -> Self {
+        Self(init.0)
+    }
+}
+
+/// A heap-allocated pin-initializer with an erased type that can be sent to other threads.
+///
+/// The same as [`DynPinInit`], but it only accepts initializers that are [`Send`] and is
+/// [`Send`] itself, e.g. to hand the initializers of a registry to worker threads.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # use pinned_init::*;
+/// let names = ["a", "b"];
+/// let inits: Vec<SendDynPinInit<'_, &str>> =
+///     names.iter().map(|name| SendDynPinInit::new(*name)).collect();
+/// std::thread::scope(|s| {
+///     for init in inits {
+///         s.spawn(move || Box::<&str>::pin_init(init).unwrap());
+///     }
+/// });
+/// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub struct SendDynPinInit<'a, T: ?Sized, E = Infallible>(
+    Box<dyn __internal::BoxedPinInit<T, E> + Send + 'a>,
+);
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<'a, T: ?Sized, E> SendDynPinInit<'a, T, E> {
+    /// Moves `init` to the heap and erases its type.
+    ///
+    /// See [`DynPinInit::new`] for why this is not a `From` implementation.
+    pub fn new(init: impl PinInit<T, E> + Send + 'a) -> Self {
+        Self(Box::new(init))
+    }
+}
+
+// SAFETY: `__pinned_init` forwards to the boxed initializer, which upholds the requirements.
+#[cfg(any(feature = "std", feature = "alloc"))]
+unsafe impl<T: ?Sized, E> PinInit<T, E> for SendDynPinInit<'_, T, E> {
+    unsafe fn __pinned_init(self, slot: *mut T) -> Result<(), E> {
+        // SAFETY: All requirements fulfilled since this function is `__pinned_init`.
+        unsafe { self.0.__pinned_init_boxed(slot) }
+    }
+}
+
 // SAFETY: Every type can be initialized by-value.
 unsafe impl<T, E> Init<T, E> for T {
     unsafe fn __init(self, slot: *mut T) -> Result<(), E> {
@@ -1206,14 +1946,34 @@ pub trait InPlaceInit<T>: Sized {
 
 #[cfg(feature = "alloc")]
 macro_rules! try_new_uninit {
-    ($type:ident) => {
+    ($type:ident) => {{
+        alloc_step!();
+        // The allocation can fail with an error, so it is also an error point of `testing`.
+        #[cfg(feature = "testing")]
+        testing::error_point().map_err(|_| AllocError)?;
         $type::try_new_uninit()?
-    };
+    }};
 }
 #[cfg(all(feature = "std", not(feature = "alloc")))]
 macro_rules! try_new_uninit {
-    ($type:ident) => {
+    ($type:ident) => {{
+        alloc_step!();
         $type::new_uninit()
+    }};
+}
+
+// The allocation of a `T`, the type parameter of the `InPlaceInit` implementation, is reported to
+// `trace` and is a failure point of `testing`.
+#[cfg(any(feature = "std", feature = "alloc"))]
+macro_rules! alloc_step {
+    () => {
+        #[cfg(feature = "testing")]
+        testing::fail_point();
+        #[cfg(feature = "trace")]
+        trace::emit(trace::Event::Alloc {
+            type_name: type_name::<T>(),
+            size: mem::size_of::<T>(),
+        });
     };
 }
 
@@ -1251,7 +2011,10 @@ impl<T> InPlaceInit<T> for Arc<T> {
         let slot = slot.as_mut_ptr();
         // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
         // slot is valid and will not be moved, because we pin it later.
-        unsafe { init.__pinned_init(slot)? };
+        unsafe {
+            __internal::poison_uninit(slot);
+            __internal::poison_on_err(slot, init.__pinned_init(slot))?;
+        }
         // SAFETY: All fields have been initialized and this is the only `Arc` to that data.
         Ok(unsafe { Pin::new_unchecked(this.assume_init()) })
     }
@@ -1269,12 +2032,110 @@ impl<T> InPlaceInit<T> for Arc<T> {
         let slot = slot.as_mut_ptr();
         // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
         // slot is valid.
-        unsafe { init.__init(slot)? };
+        unsafe {
+            __internal::poison_uninit(slot);
+            __internal::poison_on_err(slot, init.__init(slot))?;
+        }
         // SAFETY: All fields have been initialized.
         Ok(unsafe { this.assume_init() })
     }
 }
 
+/// Smart pointer that can initialize memory in-place with an asynchronous initializer.
+pub trait InPlaceInitAsync<T>: Sized {
+    /// Use the given asynchronous pin-initializer to pin-initialize a `T` inside of a new smart
+    /// pointer of this type.
+    ///
+    /// The memory is allocated first and stays reserved until the returned future resolves. If
+    /// the future is dropped before, the initializer drops the fields it has initialized and the
+    /// memory is deallocated.
+    fn try_pin_init_async<E>(
+        init: impl AsyncPinInit<T, E>,
+    ) -> impl Future<Output = Result<Pin<Self>, E>>
+    where
+        E: From<AllocError>;
+
+    /// Use the given asynchronous pin-initializer to pin-initialize a `T` inside of a new smart
+    /// pointer of this type.
+    fn pin_init_async(
+        init: impl AsyncPinInit<T>,
+    ) -> impl Future<Output = Result<Pin<Self>, AllocError>> {
+        // SAFETY: We delegate to `init` and only change the error type.
+        let init = unsafe {
+            async_pin_init_from_closure(|slot| {
+                let init = init.__pinned_init_async(slot);
+                async move {
+                    match init.await {
+                        Ok(()) => Ok(()),
+                        Err(i) => match i {},
+                    }
+                }
+            })
+        };
+        Self::try_pin_init_async(init)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<T> InPlaceInitAsync<T> for Box<T> {
+    #[inline]
+    async fn try_pin_init_async<E>(init: impl AsyncPinInit<T, E>) -> Result<Pin<Self>, E>
+    where
+        E: From<AllocError>,
+    {
+        let mut this = try_new_uninit!(Box);
+        let res = {
+            let slot = this.as_mut_ptr();
+            // SAFETY: `slot` is valid and uninitialized.
+            unsafe { __internal::poison_uninit(slot) };
+            // SAFETY: When init errors/panics or is dropped, `this` will get deallocated but not
+            // dropped. `this` is owned by this future, so it stays valid and will not be moved
+            // until `init` resolves. If this future is leaked, so is `this`.
+            unsafe { init.__pinned_init_async(slot) }
+        }
+        .await;
+        // SAFETY: `this` is valid, it is uninitialized if `res` is an error.
+        unsafe { __internal::poison_on_err(this.as_mut_ptr(), res)? };
+        // SAFETY: All fields have been initialized.
+        Ok(unsafe { this.assume_init() }.into())
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<T> InPlaceInitAsync<T> for Arc<T> {
+    #[inline]
+    async fn try_pin_init_async<E>(init: impl AsyncPinInit<T, E>) -> Result<Pin<Self>, E>
+    where
+        E: From<AllocError>,
+    {
+        let mut this = try_new_uninit!(Arc);
+        let Some(slot) = Arc::get_mut(&mut this) else {
+            // SAFETY: the Arc has just been created and has no external references
+            unsafe { core::hint::unreachable_unchecked() }
+        };
+        let res = {
+            let slot = slot.as_mut_ptr();
+            // SAFETY: `slot` is valid and uninitialized.
+            unsafe { __internal::poison_uninit(slot) };
+            // SAFETY: When init errors/panics or is dropped, `this` will get deallocated but not
+            // dropped. `this` is owned by this future, so it stays valid and will not be moved
+            // until `init` resolves. If this future is leaked, so is `this`.
+            unsafe { init.__pinned_init_async(slot) }
+        }
+        .await;
+        // The pointer is not kept across the `.await`, so that the future stays `Send`.
+        let Some(slot) = Arc::get_mut(&mut this) else {
+            // SAFETY: the Arc has just been created and has no external references
+            unsafe { core::hint::unreachable_unchecked() }
+        };
+        let slot = slot.as_mut_ptr();
+        // SAFETY: `slot` is valid for writes, it is uninitialized if `res` is an error.
+        unsafe { __internal::poison_on_err(slot, res)? };
+        // SAFETY: All fields have been initialized and this is the only `Arc` to that data.
+        Ok(unsafe { Pin::new_unchecked(this.assume_init()) })
+    }
+}
+
 /// Smart pointer containing uninitialized memory and that can write a value.
 pub trait InPlaceWrite<T> {
     /// The type `Self` turns into when the contents are initialized.
@@ -1299,7 +2160,10 @@ impl<T> InPlaceWrite<T> for Box<MaybeUninit<T>> {
         let slot = self.as_mut_ptr();
         // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
         // slot is valid.
-        unsafe { init.__init(slot)? };
+        unsafe {
+            __internal::poison_uninit(slot);
+            __internal::poison_on_err(slot, init.__init(slot))?;
+        }
         // SAFETY: All fields have been initialized.
         Ok(unsafe { self.assume_init() })
     }
@@ -1308,7 +2172,10 @@ impl<T> InPlaceWrite<T> for Box<MaybeUninit<T>> {
         let slot = self.as_mut_ptr();
         // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
         // slot is valid and will not be moved, because we pin it later.
-        unsafe { init.__pinned_init(slot)? };
+        unsafe {
+            __internal::poison_uninit(slot);
+            __internal::poison_on_err(slot, init.__pinned_init(slot))?;
+        }
         // SAFETY: All fields have been initialized.
         Ok(unsafe { self.assume_init() }.into())
     }
@@ -1352,6 +2219,30 @@ pub unsafe trait PinnedDrop: __internal::HasPinData {
     fn drop(self: Pin<&mut Self>, only_call_from_drop: __internal::OnlyCallFromDrop);
 }
 
+/// Trait for manually implementing `Unpin` for a `#[pin_data(UnsafeUnpin)]` struct.
+///
+/// With `UnsafeUnpin` as an argument, `#[pin_data]` does not derive `Unpin` from the structurally
+/// pinned fields. Instead the struct is `Unpin` exactly when it implements this trait:
+///
+/// ```rust
+/// # use pinned_init::*;
+/// #[pin_data(UnsafeUnpin)]
+/// struct Handle<T> {
+///     #[pin]
+///     inner: T,
+/// }
+///
+/// // SAFETY: `inner` is `Unpin`, so it does not rely on staying at its address.
+/// unsafe impl<T: Unpin> UnsafeUnpin for Handle<T> {}
+/// ```
+///
+/// # Safety
+///
+/// Structurally pinned fields are initialized in-place and may rely on never being moved. The
+/// implementation must only apply where moving the struct after it has been pinned is sound, for
+/// example when every structurally pinned field is `Unpin`.
+pub unsafe trait UnsafeUnpin {}
+
 /// Marker trait for types that can be initialized by writing just zeroes.
 ///
 /// # Safety
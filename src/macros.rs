@@ -1,8 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 //! This module provides the macros that actually implement the proc-macros `pin_data`,
-//! `pinned_drop` and the derive macro for zeroable. It also contains `__init_internal` the
-//! implementation of the `{try_}{pin_}init!` macros.
+//! `pinned_drop` and the derive macro for zeroable. The `{try_}{pin_}init!` macros are
+//! implemented by `__init` in `pinned-init-macro`, their expansion is explained below.
 //!
 //! These macros should never be called directly, since they expect their input to be
 //! in a certain format which is internal. If used incorrectly, these macros can lead to UB even in
@@ -97,103 +97,97 @@
 //! // Then an anonymous constant is defined, this is because we do not want any code to access the
 //! // types that we define inside:
 //! const _: () = {
-//!     // We define the pin-data carrying struct, it is a ZST and needs to have the same generics,
-//!     // since we need to implement access functions for each field and thus need to know its
-//!     // type.
-//!     struct __ThePinData<T> {
-//!         __phantom: ::core::marker::PhantomData<fn(Bar<T>) -> Bar<T>>,
-//!     }
-//!     // We implement `Copy` for the pin-data struct, since all functions it defines will take
-//!     // `self` by value.
-//!     impl<T> ::core::clone::Clone for __ThePinData<T> {
-//!         fn clone(&self) -> Self {
-//!             *self
-//!         }
-//!     }
-//!     impl<T> ::core::marker::Copy for __ThePinData<T> {}
+//!     // We define the pin-data carrying struct, it is a ZST. It is generic over the struct, so
+//!     // the impls below do not need to repeat the generics of `Bar`. Since it only has access
+//!     // functions for `Bar`, it is invariant over `__Datee`.
+//!     struct __ThePinData<__Datee: ?::core::marker::Sized>(
+//!         ::core::marker::PhantomData<fn(*const __Datee) -> *const __Datee>,
+//!     );
 //!     // For every field of `Bar`, the pin-data struct will define a function with the same name
-//!     // and accessor (`pub` or `pub(crate)` etc.). This function will take a pointer to the
-//!     // field (`slot`) and a `PinInit` or `Init` depending on the projection kind of the field
-//!     // (if pinning is structural for the field, then `PinInit` otherwise `Init`).
+//!     // and accessor (`pub` or `pub(crate)` etc.). This function returns a `PinnedField` or an
+//!     // `UnpinnedField` depending on the projection kind of the field. Their `__init` function
+//!     // takes a pointer to the field (`slot`) and a `PinInit` or `Init` respectively (if pinning
+//!     // is structural for the field, then `PinInit` otherwise `Init`). Since these types are
+//!     // shared, the functions defined here are tiny and not generic over the initializer. They
+//!     // also store the position of the field, which the `..base` struct update syntax uses to
+//!     // drop all other fields when re-initializing the field fails.
 //!     #[allow(dead_code)]
-//!     impl<T> __ThePinData<T> {
-//!         unsafe fn t<E>(
-//!             self,
-//!             slot: *mut T,
-//!             // Since `t` is `#[pin]`, this is `PinInit`.
-//!             init: impl ::pinned_init::PinInit<T, E>,
-//!         ) -> ::core::result::Result<(), E> {
-//!             unsafe { ::pinned_init::PinInit::__pinned_init(init, slot) }
+//!     impl<T> __ThePinData<Bar<T>> {
+//!         // Since `t` is `#[pin]`, this is `PinnedField`.
+//!         #[inline(always)]
+//!         fn t(&self) -> ::pinned_init::__internal::PinnedField<T> {
+//!             ::pinned_init::__internal::PinnedField::new(0)
 //!         }
-//!         pub unsafe fn x<E>(
-//!             self,
-//!             slot: *mut usize,
-//!             // Since `x` is not `#[pin]`, this is `Init`.
-//!             init: impl ::pinned_init::Init<usize, E>,
-//!         ) -> ::core::result::Result<(), E> {
-//!             unsafe { ::pinned_init::Init::__init(init, slot) }
+//!         // Since `x` is not `#[pin]`, this is `UnpinnedField`.
+//!         #[inline(always)]
+//!         pub fn x(&self) -> ::pinned_init::__internal::UnpinnedField<usize> {
+//!             ::pinned_init::__internal::UnpinnedField::new(1)
 //!         }
 //!     }
 //!     // Implement the internal `HasPinData` trait that associates `Bar` with the pin-data struct
 //!     // that we constructed above.
 //!     unsafe impl<T> ::pinned_init::__internal::HasPinData for Bar<T> {
-//!         type PinData = __ThePinData<T>;
+//!         type PinData = __ThePinData<Self>;
 //!         unsafe fn __pin_data() -> Self::PinData {
-//!             __ThePinData {
-//!                 __phantom: ::core::marker::PhantomData,
+//!             __ThePinData(::core::marker::PhantomData)
+//!         }
+//!         // Used by the `..base` struct update syntax, see above.
+//!         unsafe fn __drop_fields_except(slot: *mut Self, except: usize) {
+//!             if except != 0 {
+//!                 unsafe { ::core::ptr::drop_in_place(::core::ptr::addr_of_mut!((*slot).t)) };
+//!             }
+//!             if except != 1 {
+//!                 unsafe { ::core::ptr::drop_in_place(::core::ptr::addr_of_mut!((*slot).x)) };
 //!             }
 //!         }
 //!     }
 //!     // Implement the internal `PinData` trait that marks the pin-data struct as a pin-data
 //!     // struct. This is important to ensure that no user can implement a rogue `__pin_data`
 //!     // function without using `unsafe`.
-//!     unsafe impl<T> ::pinned_init::__internal::PinData for __ThePinData<T> {
-//!         type Datee = Bar<T>;
+//!     unsafe impl<__Datee: ?::core::marker::Sized + ::pinned_init::__internal::HasPinData>
+//!         ::pinned_init::__internal::PinData for __ThePinData<__Datee>
+//!     {
+//!         type Datee = __Datee;
 //!     }
 //!     // Now we only want to implement `Unpin` for `Bar` when every structurally pinned field is
 //!     // `Unpin`. In other words, whether `Bar` is `Unpin` only depends on structurally pinned
-//!     // fields (those marked with `#[pin]`). These fields will be listed in this struct, in our
-//!     // case no such fields exist, hence this is almost empty. The two phantomdata fields exist
-//!     // for two reasons:
-//!     // - `__phantom`: every generic must be used, since we cannot really know which generics
-//!     //   are used, we declare all and then use everything here once.
-//!     // - `__phantom_pin`: uses the `'__pin` lifetime and ensures that this struct is invariant
-//!     //   over it. The lifetime is needed to work around the limitation that trait bounds must
-//!     //   not be trivial, e.g. the user has a `#[pin] PhantomPinned` field -- this is
-//!     //   unconditionally `!Unpin` and results in an error. The lifetime tricks the compiler
-//!     //   into accepting these bounds regardless.
-//!     #[allow(dead_code)]
-//!     struct __Unpin<'__pin, T> {
-//!         __phantom_pin: ::core::marker::PhantomData<fn(&'__pin ()) -> &'__pin ()>,
-//!         __phantom: ::core::marker::PhantomData<fn(Bar<T>) -> Bar<T>>,
-//!         // Our only `#[pin]` field is `t`.
-//!         t: T,
-//!     }
+//!     // fields (those marked with `#[pin]`). Their types are listed in the tuple, in our case
+//!     // only `t` is pinned. `PinnedFields` lives in `__internal`, it is `Unpin` if the tuple is:
+//!     //
+//!     // ```
+//!     // pub struct PinnedFields<'a, T: ?Sized>(PhantomData<&'a ()>, T);
+//!     // ```
+//!     //
+//!     // The `'__pin` lifetime is needed to work around the limitation that trait bounds must not
+//!     // be trivial, e.g. the user has a `#[pin] PhantomPinned` field -- this is unconditionally
+//!     // `!Unpin` and results in an error. The lifetime tricks the compiler into accepting these
+//!     // bounds regardless. If a pinned field has a `#[cfg(..)]` attribute, a struct with only the
+//!     // pinned fields is declared instead of the tuple.
 //!     #[doc(hidden)]
 //!     impl<'__pin, T> ::core::marker::Unpin for Bar<T>
 //!     where
-//!         __Unpin<'__pin, T>: ::core::marker::Unpin,
+//!         ::pinned_init::__internal::PinnedFields<'__pin, (T,)>: ::core::marker::Unpin,
 //!     {}
 //!     // Now we need to ensure that `Bar` does not implement `Drop`, since that would give users
 //!     // access to `&mut self` inside of `drop` even if the struct was pinned. This could lead to
 //!     // UB with only safe code, so we disallow this by giving a trait implementation error using
-//!     // a direct impl and a blanket implementation.
-//!     trait MustNotImplDrop {}
-//!     // Normally `Drop` bounds do not have the correct semantics, but for this purpose they do
-//!     // (normally people want to know if a type has any kind of drop glue at all, here we want
-//!     // to know if it has any kind of custom drop glue, which is exactly what this bound does).
-//!     #[allow(drop_bounds)]
-//!     impl<T: ::core::ops::Drop> MustNotImplDrop for T {}
-//!     impl<T> MustNotImplDrop for Bar<T> {}
+//!     // a direct impl and a blanket implementation. The trait and its blanket implementation
+//!     // for all types implementing `Drop` live in `__internal`:
+//!     //
+//!     // ```
+//!     // pub trait MustNotImplDrop {}
+//!     // // Normally `Drop` bounds do not have the correct semantics, but for this purpose they do
+//!     // // (normally people want to know if a type has any kind of drop glue at all, here we want
+//!     // // to know if it has any kind of custom drop glue, which is exactly what this bound does).
+//!     // #[allow(drop_bounds)]
+//!     // impl<T: Drop + ?Sized> MustNotImplDrop for T {}
+//!     // ```
+//!     impl<T> ::pinned_init::__internal::MustNotImplDrop for Bar<T> {}
 //!     // Here comes a convenience check, if one implemented `PinnedDrop`, but forgot to add it to
 //!     // `#[pin_data]`, then this will error with the same mechanic as above, this is not needed
 //!     // for safety, but a good sanity check, since no normal code calls `PinnedDrop::drop`.
-//!     #[allow(non_camel_case_types)]
-//!     trait UselessPinnedDropImpl_you_need_to_specify_PinnedDrop {}
-//!     impl<
-//!         T: ::pinned_init::PinnedDrop,
-//!     > UselessPinnedDropImpl_you_need_to_specify_PinnedDrop for T {}
-//!     impl<T> UselessPinnedDropImpl_you_need_to_specify_PinnedDrop for Bar<T> {}
+//!     impl<T> ::pinned_init::__internal::UselessPinnedDropImpl_you_need_to_specify_PinnedDrop
+//!     for Bar<T> {}
 //! };
 //! ```
 //!
@@ -235,13 +229,17 @@
 //!                 _,
 //!                 __InitOk,
 //!                 ::core::convert::Infallible,
-//!             >(data, move |slot| {
+//!             >(&data, move |slot| {
 //!                 {
 //!                     // Shadow the structure so it cannot be used to return early. If a user
 //!                     // tries to write `return Ok(__InitOk)`, then they get a type error,
 //!                     // since that will refer to this struct instead of the one defined
 //!                     // above.
 //!                     struct __InitOk;
+//!                     // The pin-data is not `Copy`, so we get it again instead of capturing it.
+//!                     let data = unsafe {
+//!                         ::pinned_init::__internal::HasPinData::__pin_data_of(slot)
+//!                     };
 //!                     // This is the expansion of `t,`, which is syntactic sugar for `t: t,`.
 //!                     {
 //!                         unsafe { ::core::ptr::write(::core::addr_of_mut!((*slot).t), t) };
@@ -270,26 +268,11 @@
 //!                     ::core::mem::forget(__x_guard);
 //!                     ::core::mem::forget(__t_guard);
 //!                     // Here we use the type checker to ensure that every field has been
-//!                     // initialized exactly once, since this is `if false` it will never get
-//!                     // executed, but still type-checked.
-//!                     // Additionally we abuse `slot` to automatically infer the correct type
-//!                     // for the struct. This is also another check that every field is
-//!                     // accessible from this scope.
-//!                     #[allow(unreachable_code, clippy::diverging_sub_expression)]
-//!                     let _ = || {
-//!                         unsafe {
-//!                             ::core::ptr::write(
-//!                                 slot,
-//!                                 Self {
-//!                                     // We only care about typecheck finding every field
-//!                                     // here, the expression does not matter, just conjure
-//!                                     // one using `panic!()`:
-//!                                     t: ::core::panic!(),
-//!                                     x: ::core::panic!(),
-//!                                 },
-//!                             );
-//!                         };
-//!                     };
+//!                     // initialized: the pattern has to mention every field of the struct.
+//!                     // This is also another check that every field is accessible from this
+//!                     // scope.
+//!                     let value = unsafe { &*slot };
+//!                     let Self { t: _, x: _ } = value;
 //!                 }
 //!                 // We leave the scope above and gain access to the previously shadowed
 //!                 // `__InitOk` that we need to return.
@@ -336,53 +319,43 @@
 //!     b: Bar<u32>,
 //! }
 //! const _: () = {
-//!     struct __ThePinData {
-//!         __phantom: ::core::marker::PhantomData<fn(Foo) -> Foo>,
-//!     }
-//!     impl ::core::clone::Clone for __ThePinData {
-//!         fn clone(&self) -> Self {
-//!             *self
-//!         }
-//!     }
-//!     impl ::core::marker::Copy for __ThePinData {}
+//!     struct __ThePinData<__Datee: ?::core::marker::Sized>(
+//!         ::core::marker::PhantomData<fn(*const __Datee) -> *const __Datee>,
+//!     );
 //!     #[allow(dead_code)]
-//!     impl __ThePinData {
-//!         unsafe fn b<E>(
-//!             self,
-//!             slot: *mut Bar<u32>,
-//!             init: impl ::pinned_init::PinInit<Bar<u32>, E>,
-//!         ) -> ::core::result::Result<(), E> {
-//!             unsafe { ::pinned_init::PinInit::__pinned_init(init, slot) }
+//!     impl __ThePinData<Foo> {
+//!         #[inline(always)]
+//!         fn b(&self) -> ::pinned_init::__internal::PinnedField<Bar<u32>> {
+//!             ::pinned_init::__internal::PinnedField::new(1)
 //!         }
-//!         unsafe fn a<E>(
-//!             self,
-//!             slot: *mut usize,
-//!             init: impl ::pinned_init::Init<usize, E>,
-//!         ) -> ::core::result::Result<(), E> {
-//!             unsafe { ::pinned_init::Init::__init(init, slot) }
+//!         #[inline(always)]
+//!         fn a(&self) -> ::pinned_init::__internal::UnpinnedField<usize> {
+//!             ::pinned_init::__internal::UnpinnedField::new(0)
 //!         }
 //!     }
 //!     unsafe impl ::pinned_init::__internal::HasPinData for Foo {
-//!         type PinData = __ThePinData;
+//!         type PinData = __ThePinData<Self>;
 //!         unsafe fn __pin_data() -> Self::PinData {
-//!             __ThePinData {
-//!                 __phantom: ::core::marker::PhantomData,
+//!             __ThePinData(::core::marker::PhantomData)
+//!         }
+//!         unsafe fn __drop_fields_except(slot: *mut Self, except: usize) {
+//!             if except != 0 {
+//!                 unsafe { ::core::ptr::drop_in_place(::core::ptr::addr_of_mut!((*slot).a)) };
+//!             }
+//!             if except != 1 {
+//!                 unsafe { ::core::ptr::drop_in_place(::core::ptr::addr_of_mut!((*slot).b)) };
 //!             }
 //!         }
 //!     }
-//!     unsafe impl ::pinned_init::__internal::PinData for __ThePinData {
-//!         type Datee = Foo;
-//!     }
-//!     #[allow(dead_code)]
-//!     struct __Unpin<'__pin> {
-//!         __phantom_pin: ::core::marker::PhantomData<fn(&'__pin ()) -> &'__pin ()>,
-//!         __phantom: ::core::marker::PhantomData<fn(Foo) -> Foo>,
-//!         b: Bar<u32>,
+//!     unsafe impl<__Datee: ?::core::marker::Sized + ::pinned_init::__internal::HasPinData>
+//!         ::pinned_init::__internal::PinData for __ThePinData<__Datee>
+//!     {
+//!         type Datee = __Datee;
 //!     }
 //!     #[doc(hidden)]
 //!     impl<'__pin> ::core::marker::Unpin for Foo
 //!     where
-//!         __Unpin<'__pin>: ::core::marker::Unpin,
+//!         ::pinned_init::__internal::PinnedFields<'__pin, (Bar<u32>,)>: ::core::marker::Unpin,
 //!     {}
 //!     // Since we specified `PinnedDrop` as the argument to `#[pin_data]`, we expect `Foo` to
 //!     // implement `PinnedDrop`. Thus we do not need to prevent `Drop` implementations like
@@ -455,9 +428,10 @@
 //!         _,
 //!         __InitOk,
 //!         ::core::convert::Infallible,
-//!     >(data, move |slot| {
+//!     >(&data, move |slot| {
 //!         {
 //!             struct __InitOk;
+//!             let data = unsafe { ::pinned_init::__internal::HasPinData::__pin_data_of(slot) };
 //!             {
 //!                 unsafe { ::core::ptr::write(::core::addr_of_mut!((*slot).a), a) };
 //!             }
@@ -465,24 +439,14 @@
 //!                 ::pinned_init::__internal::DropGuard::new(::core::addr_of_mut!((*slot).a))
 //!             };
 //!             let init = Bar::new(36);
-//!             unsafe { data.b(::core::addr_of_mut!((*slot).b), b)? };
+//!             unsafe { data.b().__init(::core::addr_of_mut!((*slot).b), init)? };
 //!             let __b_guard = unsafe {
 //!                 ::pinned_init::__internal::DropGuard::new(::core::addr_of_mut!((*slot).b))
 //!             };
 //!             ::core::mem::forget(__b_guard);
 //!             ::core::mem::forget(__a_guard);
-//!             #[allow(unreachable_code, clippy::diverging_sub_expression)]
-//!             let _ = || {
-//!                 unsafe {
-//!                     ::core::ptr::write(
-//!                         slot,
-//!                         Foo {
-//!                             a: ::core::panic!(),
-//!                             b: ::core::panic!(),
-//!                         },
-//!                     );
-//!                 };
-//!             };
+//!             let value = unsafe { &*slot };
+//!             let Foo { a: _, b: _ } = value;
 //!         }
 //!         Ok(__InitOk)
 //!     });
@@ -528,299 +492,40 @@ macro_rules! __pinned_drop {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __pin_data {
-    // Proc-macro entry point, this is supplied by the proc-macro pre-parsing.
+    // Proc-macro entry point, this is supplied by the proc-macro pre-parsing. It has already
+    // removed the `#[pin]` attributes and sorted the fields into pinned and not pinned fields.
     (parse_input:
         @args($($pinned_drop:ident)?),
+        // Either nothing, `!Unpin` or `UnsafeUnpin`.
+        @unpin($($unpin:tt)*),
         @sig(
             $(#[$($struct_attr:tt)*])*
             $vis:vis struct $name:ident
             $(where $($whr:tt)*)?
         ),
+        // The generics that will need to be specified on the struct inside of an
+        // `impl<$impl_generics>` block.
         @impl_generics($($impl_generics:tt)*),
+        // The generics that will need to be specified on the impl blocks.
         @ty_generics($($ty_generics:tt)*),
+        // The generics that need to be specified on the struct definition.
         @decl_generics($($decl_generics:tt)*),
-        @body({ $($fields:tt)* }),
-    ) => {
-        // We now use token munching to iterate through all of the fields. While doing this we
-        // identify fields marked with `#[pin]`, these fields are the 'pinned fields'. The user
-        // wants these to be structurally pinned. The rest of the fields are the
-        // 'not pinned fields'. Additionally we collect all fields, since we need them in the right
-        // order to declare the struct.
-        //
-        // In this call we also put some explaining comments for the parameters.
-        $crate::__pin_data!(find_pinned_fields:
-            // Attributes on the struct itself, these will just be propagated to be put onto the
-            // struct definition.
-            @struct_attrs($(#[$($struct_attr)*])*),
-            // The visibility of the struct.
-            @vis($vis),
-            // The name of the struct.
-            @name($name),
-            // The 'impl generics', the generics that will need to be specified on the struct inside
-            // of an `impl<$ty_generics>` block.
-            @impl_generics($($impl_generics)*),
-            // The 'ty generics', the generics that will need to be specified on the impl blocks.
-            @ty_generics($($ty_generics)*),
-            // The 'decl generics', the generics that need to be specified on the struct
-            // definition.
-            @decl_generics($($decl_generics)*),
-            // The where clause of any impl block and the declaration.
-            @where($($($whr)*)?),
-            // The remaining fields tokens that need to be processed.
-            // We add a `,` at the end to ensure correct parsing.
-            @fields_munch($($fields)* ,),
-            // The pinned fields.
-            @pinned(),
-            // The not pinned fields.
-            @not_pinned(),
-            // All fields.
-            @fields(),
-            // The accumulator containing all attributes already parsed.
-            @accum(),
-            // Contains `yes` or `` to indicate if `#[pin]` was found on the current field.
-            @is_pinned(),
-            // The proc-macro argument, this should be `PinnedDrop` or ``.
-            @pinned_drop($($pinned_drop)?),
-        );
-    };
-    (find_pinned_fields:
-        @struct_attrs($($struct_attrs:tt)*),
-        @vis($vis:vis),
-        @name($name:ident),
-        @impl_generics($($impl_generics:tt)*),
-        @ty_generics($($ty_generics:tt)*),
-        @decl_generics($($decl_generics:tt)*),
-        @where($($whr:tt)*),
-        // We found a PhantomPinned field, this should generally be pinned!
-        @fields_munch($field:ident : $($($(::)?core::)?marker::)?PhantomPinned, $($rest:tt)*),
-        @pinned($($pinned:tt)*),
-        @not_pinned($($not_pinned:tt)*),
-        @fields($($fields:tt)*),
-        @accum($($accum:tt)*),
-        // This field is not pinned.
-        @is_pinned(),
-        @pinned_drop($($pinned_drop:ident)?),
-    ) => {
-        ::core::compile_error!(concat!(
-            "The field `",
-            stringify!($field),
-            "` of type `PhantomPinned` only has an effect, if it has the `#[pin]` attribute.",
-        ));
-        $crate::__pin_data!(find_pinned_fields:
-            @struct_attrs($($struct_attrs)*),
-            @vis($vis),
-            @name($name),
-            @impl_generics($($impl_generics)*),
-            @ty_generics($($ty_generics)*),
-            @decl_generics($($decl_generics)*),
-            @where($($whr)*),
-            @fields_munch($($rest)*),
-            @pinned($($pinned)* $($accum)* $field: ::core::marker::PhantomPinned,),
-            @not_pinned($($not_pinned)*),
-            @fields($($fields)* $($accum)* $field: ::core::marker::PhantomPinned,),
-            @accum(),
-            @is_pinned(),
-            @pinned_drop($($pinned_drop)?),
-        );
-    };
-    (find_pinned_fields:
-        @struct_attrs($($struct_attrs:tt)*),
-        @vis($vis:vis),
-        @name($name:ident),
-        @impl_generics($($impl_generics:tt)*),
-        @ty_generics($($ty_generics:tt)*),
-        @decl_generics($($decl_generics:tt)*),
-        @where($($whr:tt)*),
-        // We reached the field declaration.
-        @fields_munch($field:ident : $type:ty, $($rest:tt)*),
-        @pinned($($pinned:tt)*),
-        @not_pinned($($not_pinned:tt)*),
-        @fields($($fields:tt)*),
-        @accum($($accum:tt)*),
-        // This field is pinned.
-        @is_pinned(yes),
-        @pinned_drop($($pinned_drop:ident)?),
-    ) => {
-        $crate::__pin_data!(find_pinned_fields:
-            @struct_attrs($($struct_attrs)*),
-            @vis($vis),
-            @name($name),
-            @impl_generics($($impl_generics)*),
-            @ty_generics($($ty_generics)*),
-            @decl_generics($($decl_generics)*),
-            @where($($whr)*),
-            @fields_munch($($rest)*),
-            @pinned($($pinned)* $($accum)* $field: $type,),
-            @not_pinned($($not_pinned)*),
-            @fields($($fields)* $($accum)* $field: $type,),
-            @accum(),
-            @is_pinned(),
-            @pinned_drop($($pinned_drop)?),
-        );
-    };
-    (find_pinned_fields:
-        @struct_attrs($($struct_attrs:tt)*),
-        @vis($vis:vis),
-        @name($name:ident),
-        @impl_generics($($impl_generics:tt)*),
-        @ty_generics($($ty_generics:tt)*),
-        @decl_generics($($decl_generics:tt)*),
-        @where($($whr:tt)*),
-        // We reached the field declaration.
-        @fields_munch($field:ident : $type:ty, $($rest:tt)*),
-        @pinned($($pinned:tt)*),
-        @not_pinned($($not_pinned:tt)*),
-        @fields($($fields:tt)*),
-        @accum($($accum:tt)*),
-        // This field is not pinned.
-        @is_pinned(),
-        @pinned_drop($($pinned_drop:ident)?),
-    ) => {
-        $crate::__pin_data!(find_pinned_fields:
-            @struct_attrs($($struct_attrs)*),
-            @vis($vis),
-            @name($name),
-            @impl_generics($($impl_generics)*),
-            @ty_generics($($ty_generics)*),
-            @decl_generics($($decl_generics)*),
-            @where($($whr)*),
-            @fields_munch($($rest)*),
-            @pinned($($pinned)*),
-            @not_pinned($($not_pinned)* $($accum)* $field: $type,),
-            @fields($($fields)* $($accum)* $field: $type,),
-            @accum(),
-            @is_pinned(),
-            @pinned_drop($($pinned_drop)?),
-        );
-    };
-    (find_pinned_fields:
-        @struct_attrs($($struct_attrs:tt)*),
-        @vis($vis:vis),
-        @name($name:ident),
-        @impl_generics($($impl_generics:tt)*),
-        @ty_generics($($ty_generics:tt)*),
-        @decl_generics($($decl_generics:tt)*),
-        @where($($whr:tt)*),
-        // We found the `#[pin]` attr.
-        @fields_munch(#[pin] $($rest:tt)*),
-        @pinned($($pinned:tt)*),
-        @not_pinned($($not_pinned:tt)*),
-        @fields($($fields:tt)*),
-        @accum($($accum:tt)*),
-        @is_pinned($($is_pinned:ident)?),
-        @pinned_drop($($pinned_drop:ident)?),
-    ) => {
-        $crate::__pin_data!(find_pinned_fields:
-            @struct_attrs($($struct_attrs)*),
-            @vis($vis),
-            @name($name),
-            @impl_generics($($impl_generics)*),
-            @ty_generics($($ty_generics)*),
-            @decl_generics($($decl_generics)*),
-            @where($($whr)*),
-            @fields_munch($($rest)*),
-            // We do not include `#[pin]` in the list of attributes, since it is not actually an
-            // attribute that is defined somewhere.
-            @pinned($($pinned)*),
-            @not_pinned($($not_pinned)*),
-            @fields($($fields)*),
-            @accum($($accum)*),
-            // Set this to `yes`.
-            @is_pinned(yes),
-            @pinned_drop($($pinned_drop)?),
-        );
-    };
-    (find_pinned_fields:
-        @struct_attrs($($struct_attrs:tt)*),
-        @vis($vis:vis),
-        @name($name:ident),
-        @impl_generics($($impl_generics:tt)*),
-        @ty_generics($($ty_generics:tt)*),
-        @decl_generics($($decl_generics:tt)*),
-        @where($($whr:tt)*),
-        // We reached the field declaration with visibility, for simplicity we only munch the
-        // visibility and put it into `$accum`.
-        @fields_munch($fvis:vis $field:ident $($rest:tt)*),
-        @pinned($($pinned:tt)*),
-        @not_pinned($($not_pinned:tt)*),
+        // All fields, in declaration order.
         @fields($($fields:tt)*),
-        @accum($($accum:tt)*),
-        @is_pinned($($is_pinned:ident)?),
-        @pinned_drop($($pinned_drop:ident)?),
-    ) => {
-        $crate::__pin_data!(find_pinned_fields:
-            @struct_attrs($($struct_attrs)*),
-            @vis($vis),
-            @name($name),
-            @impl_generics($($impl_generics)*),
-            @ty_generics($($ty_generics)*),
-            @decl_generics($($decl_generics)*),
-            @where($($whr)*),
-            @fields_munch($field $($rest)*),
-            @pinned($($pinned)*),
-            @not_pinned($($not_pinned)*),
-            @fields($($fields)*),
-            @accum($($accum)* $fvis),
-            @is_pinned($($is_pinned)?),
-            @pinned_drop($($pinned_drop)?),
-        );
-    };
-    (find_pinned_fields:
-        @struct_attrs($($struct_attrs:tt)*),
-        @vis($vis:vis),
-        @name($name:ident),
-        @impl_generics($($impl_generics:tt)*),
-        @ty_generics($($ty_generics:tt)*),
-        @decl_generics($($decl_generics:tt)*),
-        @where($($whr:tt)*),
-        // Some other attribute, just put it into `$accum`.
-        @fields_munch(#[$($attr:tt)*] $($rest:tt)*),
+        // The name and the position of every field, with the `#[cfg(..)]` attributes of the field.
+        @indices($($indices:tt)*),
+        // The fields that are structurally pinned, followed by their position.
         @pinned($($pinned:tt)*),
+        // The fields that are not structurally pinned, followed by their position.
         @not_pinned($($not_pinned:tt)*),
-        @fields($($fields:tt)*),
-        @accum($($accum:tt)*),
-        @is_pinned($($is_pinned:ident)?),
-        @pinned_drop($($pinned_drop:ident)?),
-    ) => {
-        $crate::__pin_data!(find_pinned_fields:
-            @struct_attrs($($struct_attrs)*),
-            @vis($vis),
-            @name($name),
-            @impl_generics($($impl_generics)*),
-            @ty_generics($($ty_generics)*),
-            @decl_generics($($decl_generics)*),
-            @where($($whr)*),
-            @fields_munch($($rest)*),
-            @pinned($($pinned)*),
-            @not_pinned($($not_pinned)*),
-            @fields($($fields)*),
-            @accum($($accum)* #[$($attr)*]),
-            @is_pinned($($is_pinned)?),
-            @pinned_drop($($pinned_drop)?),
-        );
-    };
-    (find_pinned_fields:
-        @struct_attrs($($struct_attrs:tt)*),
-        @vis($vis:vis),
-        @name($name:ident),
-        @impl_generics($($impl_generics:tt)*),
-        @ty_generics($($ty_generics:tt)*),
-        @decl_generics($($decl_generics:tt)*),
-        @where($($whr:tt)*),
-        // We reached the end of the fields, plus an optional additional comma, since we added one
-        // before and the user is also allowed to put a trailing comma.
-        @fields_munch($(,)?),
-        @pinned($($pinned:tt)*),
-        @not_pinned($($not_pinned:tt)*),
-        @fields($($fields:tt)*),
-        @accum(),
-        @is_pinned(),
-        @pinned_drop($($pinned_drop:ident)?),
+        // The types of the structurally pinned fields in `[..]`, empty if any of them has a
+        // `#[cfg(..)]` attribute.
+        @pinned_types($($pinned_types:tt)*),
     ) => {
         // Declare the struct with all fields in the correct order.
-        $($struct_attrs)*
+        $(#[$($struct_attr)*])*
         $vis struct $name <$($decl_generics)*>
-        where $($whr)*
+        where $($($whr)*)?
         {
             $($fields)*
         }
@@ -828,33 +533,20 @@ macro_rules! __pin_data {
         // We put the rest into this const item, because it then will not be accessible to anything
         // outside.
         const _: () = {
-            // We declare this struct which will host all of the projection function for our type.
-            // it will be invariant over all generic parameters which are inherited from the
-            // struct.
-            $vis struct __ThePinData<$($impl_generics)*>
-            where $($whr)*
-            {
-                __phantom: ::core::marker::PhantomData<
-                    fn($name<$($ty_generics)*>) -> $name<$($ty_generics)*>
-                >,
-            }
-
-            impl<$($impl_generics)*> ::core::clone::Clone for __ThePinData<$($ty_generics)*>
-            where $($whr)*
-            {
-                fn clone(&self) -> Self { *self }
-            }
-
-            impl<$($impl_generics)*> ::core::marker::Copy for __ThePinData<$($ty_generics)*>
-            where $($whr)*
-            {}
+            // We declare this struct which will host all of the projection functions for our type.
+            // It only has projection functions for `$name`, it is generic over the struct so the
+            // impls below do not need to repeat its generics. It is invariant over `__Datee`.
+            $vis struct __ThePinData<__Datee: ?::core::marker::Sized>(
+                ::core::marker::PhantomData<fn(*const __Datee) -> *const __Datee>,
+            );
 
             // Make all projection functions.
             $crate::__pin_data!(make_pin_data:
                 @pin_data(__ThePinData),
+                @name($name),
                 @impl_generics($($impl_generics)*),
                 @ty_generics($($ty_generics)*),
-                @where($($whr)*),
+                @where($($($whr)*)?),
                 @pinned($($pinned)*),
                 @not_pinned($($not_pinned)*),
             );
@@ -863,42 +555,43 @@ macro_rules! __pin_data {
             // we also use the least restrictive generics possible.
             unsafe impl<$($impl_generics)*>
                 $crate::__internal::HasPinData for $name<$($ty_generics)*>
-            where $($whr)*
+            where $($($whr)*)?
             {
-                type PinData = __ThePinData<$($ty_generics)*>;
+                type PinData = __ThePinData<Self>;
 
+                #[inline]
                 unsafe fn __pin_data() -> Self::PinData {
-                    __ThePinData { __phantom: ::core::marker::PhantomData }
+                    __ThePinData(::core::marker::PhantomData)
                 }
-            }
 
-            unsafe impl<$($impl_generics)*>
-                $crate::__internal::PinData for __ThePinData<$($ty_generics)*>
-            where $($whr)*
-            {
-                type Datee = $name<$($ty_generics)*>;
+                #[inline]
+                unsafe fn __drop_fields_except(slot: *mut Self, except: usize) {
+                    $crate::__pin_data!(drop_fields_except:
+                        @slot(slot),
+                        @except(except),
+                        @indices($($indices)*),
+                    );
+                }
             }
 
-            // This struct will be used for the unpin analysis. Since only structurally pinned
-            // fields are relevant whether the struct should implement `Unpin`.
-            #[allow(dead_code)]
-            struct __Unpin <'__pin, $($impl_generics)*>
-            where $($whr)*
+            // SAFETY: `__ThePinData<$name>` is only created by `__pin_data` above, there are no
+            // projection functions for other types.
+            unsafe impl<__Datee: ?::core::marker::Sized + $crate::__internal::HasPinData>
+                $crate::__internal::PinData for __ThePinData<__Datee>
             {
-                __phantom_pin: ::core::marker::PhantomData<fn(&'__pin ()) -> &'__pin ()>,
-                __phantom: ::core::marker::PhantomData<
-                    fn($name<$($ty_generics)*>) -> $name<$($ty_generics)*>
-                >,
-                // Only the pinned fields.
-                $($pinned)*
+                type Datee = __Datee;
             }
 
-            #[doc(hidden)]
-            impl<'__pin, $($impl_generics)*> ::core::marker::Unpin for $name<$($ty_generics)*>
-            where
-                __Unpin<'__pin, $($ty_generics)*>: ::core::marker::Unpin,
-                $($whr)*
-            {}
+            // Implement `Unpin` depending on the structurally pinned fields or the argument.
+            $crate::__pin_data!(unpin:
+                @name($name),
+                @impl_generics($($impl_generics)*),
+                @ty_generics($($ty_generics)*),
+                @where($($($whr)*)?),
+                @pinned($($pinned)*),
+                @pinned_types($($pinned_types)*),
+                @unpin($($unpin)*),
+            );
 
             // We need to disallow normal `Drop` implementation, the exact behavior depends on
             // whether `PinnedDrop` was specified as the parameter.
@@ -906,11 +599,93 @@ macro_rules! __pin_data {
                 @name($name),
                 @impl_generics($($impl_generics)*),
                 @ty_generics($($ty_generics)*),
-                @where($($whr)*),
+                @where($($($whr)*)?),
                 @pinned_drop($($pinned_drop)?),
             );
         };
     };
+    // By default, the struct is `Unpin` if all structurally pinned fields are `Unpin`.
+    (unpin:
+        @name($name:ident),
+        @impl_generics($($impl_generics:tt)*),
+        @ty_generics($($ty_generics:tt)*),
+        @where($($whr:tt)*),
+        @pinned($($pinned:tt)*),
+        @pinned_types([$($p_type:ty),* $(,)?]),
+        @unpin(),
+    ) => {
+        #[doc(hidden)]
+        impl<'__pin, $($impl_generics)*> ::core::marker::Unpin for $name<$($ty_generics)*>
+        where
+            $crate::__internal::PinnedFields<'__pin, ($($p_type,)*)>: ::core::marker::Unpin,
+            $($whr)*
+        {}
+    };
+    // The types of fields with `#[cfg(..)]` attributes cannot be put into a tuple, so we declare a
+    // struct with only the pinned fields for the unpin analysis instead.
+    (unpin:
+        @name($name:ident),
+        @impl_generics($($impl_generics:tt)*),
+        @ty_generics($($ty_generics:tt)*),
+        @where($($whr:tt)*),
+        @pinned($($(#[$($attr:tt)*])* $pvis:vis $field:ident : $type:ty = $index:literal),* $(,)?),
+        @pinned_types(),
+        @unpin(),
+    ) => {
+        #[allow(dead_code)]
+        struct __Unpin <'__pin, $($impl_generics)*>
+        where $($whr)*
+        {
+            __phantom_pin: ::core::marker::PhantomData<fn(&'__pin ()) -> &'__pin ()>,
+            __phantom: ::core::marker::PhantomData<
+                fn($name<$($ty_generics)*>) -> $name<$($ty_generics)*>
+            >,
+            // Only the pinned fields.
+            $($(#[$($attr)*])* $pvis $field: $type,)*
+        }
+
+        #[doc(hidden)]
+        impl<'__pin, $($impl_generics)*> ::core::marker::Unpin for $name<$($ty_generics)*>
+        where
+            __Unpin<'__pin, $($ty_generics)*>: ::core::marker::Unpin,
+            $($whr)*
+        {}
+    };
+    // With `!Unpin` the struct never implements `Unpin`. This impl prevents the compiler from
+    // deriving it and conflicts with a manual implementation.
+    (unpin:
+        @name($name:ident),
+        @impl_generics($($impl_generics:tt)*),
+        @ty_generics($($ty_generics:tt)*),
+        @where($($whr:tt)*),
+        @pinned($($pinned:tt)*),
+        @pinned_types($($pinned_types:tt)*),
+        @unpin(!Unpin),
+    ) => {
+        #[doc(hidden)]
+        impl<'__pin, $($impl_generics)*> ::core::marker::Unpin for $name<$($ty_generics)*>
+        where
+            $crate::__internal::NotUnpin<'__pin>: ::core::marker::Unpin,
+            $($whr)*
+        {}
+    };
+    // With `UnsafeUnpin` the struct is `Unpin` if it implements `UnsafeUnpin`.
+    (unpin:
+        @name($name:ident),
+        @impl_generics($($impl_generics:tt)*),
+        @ty_generics($($ty_generics:tt)*),
+        @where($($whr:tt)*),
+        @pinned($($pinned:tt)*),
+        @pinned_types($($pinned_types:tt)*),
+        @unpin(UnsafeUnpin),
+    ) => {
+        #[doc(hidden)]
+        impl<'__pin, $($impl_generics)*> ::core::marker::Unpin for $name<$($ty_generics)*>
+        where
+            $crate::__internal::UnsafeUnpinned<'__pin, Self>: ::core::marker::Unpin,
+            $($whr)*
+        {}
+    };
     // When no `PinnedDrop` was specified, then we have to prevent implementing drop.
     (drop_prevention:
         @name($name:ident),
@@ -919,23 +694,16 @@ macro_rules! __pin_data {
         @where($($whr:tt)*),
         @pinned_drop(),
     ) => {
-        // We prevent this by creating a trait that will be implemented for all types implementing
-        // `Drop`. Additionally we will implement this trait for the struct leading to a conflict,
-        // if it also implements `Drop`
-        trait MustNotImplDrop {}
-        #[allow(drop_bounds)]
-        impl<T: ::core::ops::Drop> MustNotImplDrop for T {}
-        impl<$($impl_generics)*> MustNotImplDrop for $name<$($ty_generics)*>
+        // `MustNotImplDrop` is implemented for all types implementing `Drop`, so this leads to a
+        // conflict if the struct also implements `Drop`.
+        impl<$($impl_generics)*> $crate::__internal::MustNotImplDrop for $name<$($ty_generics)*>
         where $($whr)* {}
         // We also take care to prevent users from writing a useless `PinnedDrop` implementation.
         // They might implement `PinnedDrop` correctly for the struct, but forget to give
         // `PinnedDrop` as the parameter to `#[pin_data]`.
-        #[allow(non_camel_case_types)]
-        trait UselessPinnedDropImpl_you_need_to_specify_PinnedDrop {}
-        impl<T: $crate::PinnedDrop>
-            UselessPinnedDropImpl_you_need_to_specify_PinnedDrop for T {}
         impl<$($impl_generics)*>
-            UselessPinnedDropImpl_you_need_to_specify_PinnedDrop for $name<$($ty_generics)*>
+            $crate::__internal::UselessPinnedDropImpl_you_need_to_specify_PinnedDrop
+            for $name<$($ty_generics)*>
         where $($whr)* {}
     };
     // When `PinnedDrop` was specified we just implement `Drop` and delegate.
@@ -969,401 +737,105 @@ macro_rules! __pin_data {
         @pinned_drop($($rest:tt)*),
     ) => {
         compile_error!(
-            "Wrong parameters to `#[pin_data]`, expected nothing or `PinnedDrop`, got '{}'.",
+            "Wrong parameters to `#[pin_data]`, expected nothing, `PinnedDrop`, `constructor`, `builder`, `init_type`, `!Unpin` or `UnsafeUnpin`, got '{}'.",
             stringify!($($rest)*),
         );
     };
+    (drop_fields_except:
+        @slot($slot:ident),
+        @except($except:ident),
+        @indices($($(#[$cfg:meta])* $field:ident = $index:literal,)*),
+    ) => {
+        // Drop the fields in declaration order, just like the compiler would.
+        $(
+            $(#[$cfg])*
+            if $except != $index {
+                // SAFETY: The caller guarantees that all fields except `$except` are initialized.
+                unsafe { ::core::ptr::drop_in_place(::core::ptr::addr_of_mut!((*$slot).$field)) };
+            }
+        )*
+    };
+    // Puts only the `#[cfg]` attributes of a field onto the item generated for that field, so that
+    // it is removed together with the field.
+    (with_cfgs:
+        @attrs(),
+        @cfgs($($cfgs:tt)*),
+        @item($($item:tt)*),
+    ) => {
+        $($cfgs)* $($item)*
+    };
+    (with_cfgs:
+        @attrs(#[cfg $($cfg:tt)*] $($attrs:tt)*),
+        @cfgs($($cfgs:tt)*),
+        @item($($item:tt)*),
+    ) => {
+        $crate::__pin_data!(with_cfgs:
+            @attrs($($attrs)*),
+            @cfgs($($cfgs)* #[cfg $($cfg)*]),
+            @item($($item)*),
+        );
+    };
+    (with_cfgs:
+        @attrs(#[$($attr:tt)*] $($attrs:tt)*),
+        @cfgs($($cfgs:tt)*),
+        @item($($item:tt)*),
+    ) => {
+        $crate::__pin_data!(with_cfgs:
+            @attrs($($attrs)*),
+            @cfgs($($cfgs)*),
+            @item($($item)*),
+        );
+    };
     (make_pin_data:
         @pin_data($pin_data:ident),
+        @name($name:ident),
         @impl_generics($($impl_generics:tt)*),
         @ty_generics($($ty_generics:tt)*),
         @where($($whr:tt)*),
-        @pinned($($(#[$($p_attr:tt)*])* $pvis:vis $p_field:ident : $p_type:ty),* $(,)?),
-        @not_pinned($($(#[$($attr:tt)*])* $fvis:vis $field:ident : $type:ty),* $(,)?),
+        @pinned(
+            $($(#[$($p_attr:tt)*])* $pvis:vis $p_field:ident : $p_type:ty = $p_index:literal),* $(,)?
+        ),
+        @not_pinned(
+            $($(#[$($attr:tt)*])* $fvis:vis $field:ident : $type:ty = $index:literal),* $(,)?
+        ),
     ) => {
         // For every field, we create a projection function according to its projection type. If a
         // field is structurally pinned, then it must be initialized via `PinInit`, if it is not
-        // structurally pinned, then it can be initialized via `Init`.
-        //
-        // The functions are `unsafe` to prevent accidentally calling them.
+        // structurally pinned, then it can be initialized via `Init`. The initialization itself is
+        // done by the shared `PinnedField` and `UnpinnedField` types, so these functions are not
+        // generic over the initializer.
         #[allow(dead_code)]
-        impl<$($impl_generics)*> $pin_data<$($ty_generics)*>
+        impl<$($impl_generics)*> $pin_data<$name<$($ty_generics)*>>
         where $($whr)*
         {
             $(
-                $pvis unsafe fn $p_field<E>(
-                    self,
-                    slot: *mut $p_type,
-                    init: impl $crate::PinInit<$p_type, E>,
-                ) -> ::core::result::Result<(), E> {
-                    unsafe { $crate::PinInit::__pinned_init(init, slot) }
-                }
+                $crate::__pin_data!(with_cfgs:
+                    @attrs($(#[$($p_attr)*])*),
+                    @cfgs(),
+                    @item(
+                        #[inline(always)]
+                        $pvis fn $p_field(&self) -> $crate::__internal::PinnedField<$p_type> {
+                            $crate::__internal::PinnedField::new($p_index)
+                        }
+                    ),
+                );
             )*
             $(
-                $fvis unsafe fn $field<E>(
-                    self,
-                    slot: *mut $type,
-                    init: impl $crate::Init<$type, E>,
-                ) -> ::core::result::Result<(), E> {
-                    unsafe { $crate::Init::__init(init, slot) }
-                }
+                $crate::__pin_data!(with_cfgs:
+                    @attrs($(#[$($attr)*])*),
+                    @cfgs(),
+                    @item(
+                        #[inline(always)]
+                        $fvis fn $field(&self) -> $crate::__internal::UnpinnedField<$type> {
+                            $crate::__internal::UnpinnedField::new($index)
+                        }
+                    ),
+                );
             )*
         }
     };
 }
 
-pub use paste::paste;
-
-/// The internal init macro. Do not call manually!
-///
-/// This is called by the `{try_}{pin_}init!` macros with various inputs.
-///
-/// This macro has multiple internal call configurations, these are always the very first ident:
-/// - nothing: this is the base case and called by the `{try_}{pin_}init!` macros.
-/// - `with_update_parsed`: when the `..Zeroable::zeroed()` syntax has been handled.
-/// - `init_slot`: recursively creates the code that initializes all fields in `slot`.
-/// - `make_initializer`: recursively create the struct initializer that guarantees that every
-///   field has been initialized exactly once.
-#[doc(hidden)]
-#[macro_export]
-macro_rules! __init_internal {
-    (
-        @this($($this:ident)?),
-        @typ($t:path),
-        @fields($($fields:tt)*),
-        @error($err:ty),
-        // Either `PinData` or `InitData`, `$use_data` should only be present in the `PinData`
-        // case.
-        @data($data:ident, $($use_data:ident)?),
-        // `HasPinData` or `HasInitData`.
-        @has_data($has_data:ident, $get_data:ident),
-        // `pin_init_from_closure` or `init_from_closure`.
-        @construct_closure($construct_closure:ident),
-        @munch_fields(),
-    ) => {
-        $crate::__init_internal!(with_update_parsed:
-            @this($($this)?),
-            @typ($t),
-            @fields($($fields)*),
-            @error($err),
-            @data($data, $($use_data)?),
-            @has_data($has_data, $get_data),
-            @construct_closure($construct_closure),
-            @zeroed(), // Nothing means default behavior.
-        )
-    };
-    (
-        @this($($this:ident)?),
-        @typ($t:path),
-        @fields($($fields:tt)*),
-        @error($err:ty),
-        // Either `PinData` or `InitData`, `$use_data` should only be present in the `PinData`
-        // case.
-        @data($data:ident, $($use_data:ident)?),
-        // `HasPinData` or `HasInitData`.
-        @has_data($has_data:ident, $get_data:ident),
-        // `pin_init_from_closure` or `init_from_closure`.
-        @construct_closure($construct_closure:ident),
-        @munch_fields(..Zeroable::zeroed()),
-    ) => {
-        $crate::__init_internal!(with_update_parsed:
-            @this($($this)?),
-            @typ($t),
-            @fields($($fields)*),
-            @error($err),
-            @data($data, $($use_data)?),
-            @has_data($has_data, $get_data),
-            @construct_closure($construct_closure),
-            @zeroed(()), // `()` means zero all fields not mentioned.
-        )
-    };
-    (
-        @this($($this:ident)?),
-        @typ($t:path),
-        @fields($($fields:tt)*),
-        @error($err:ty),
-        // Either `PinData` or `InitData`, `$use_data` should only be present in the `PinData`
-        // case.
-        @data($data:ident, $($use_data:ident)?),
-        // `HasPinData` or `HasInitData`.
-        @has_data($has_data:ident, $get_data:ident),
-        // `pin_init_from_closure` or `init_from_closure`.
-        @construct_closure($construct_closure:ident),
-        @munch_fields($ignore:tt $($rest:tt)*),
-    ) => {
-        $crate::__init_internal!(
-            @this($($this)?),
-            @typ($t),
-            @fields($($fields)*),
-            @error($err),
-            @data($data, $($use_data)?),
-            @has_data($has_data, $get_data),
-            @construct_closure($construct_closure),
-            @munch_fields($($rest)*),
-        )
-    };
-    (with_update_parsed:
-        @this($($this:ident)?),
-        @typ($t:path),
-        @fields($($fields:tt)*),
-        @error($err:ty),
-        // Either `PinData` or `InitData`, `$use_data` should only be present in the `PinData`
-        // case.
-        @data($data:ident, $($use_data:ident)?),
-        // `HasPinData` or `HasInitData`.
-        @has_data($has_data:ident, $get_data:ident),
-        // `pin_init_from_closure` or `init_from_closure`.
-        @construct_closure($construct_closure:ident),
-        @zeroed($($init_zeroed:expr)?),
-    ) => {{
-        // We do not want to allow arbitrary returns, so we declare this type as the `Ok` return
-        // type and shadow it later when we insert the arbitrary user code. That way there will be
-        // no possibility of returning without `unsafe`.
-        struct __InitOk;
-        // Get the data about fields from the supplied type.
-        let data = unsafe {
-            use $crate::__internal::$has_data;
-            // Here we abuse `paste!` to retokenize `$t`. Declarative macros have some internal
-            // information that is associated to already parsed fragments, so a path fragment
-            // cannot be used in this position. Doing the retokenization results in valid rust
-            // code.
-            $crate::macros::paste!($t::$get_data())
-        };
-        // Ensure that `data` really is of type `$data` and help with type inference:
-        let init = $crate::__internal::$data::make_closure::<_, __InitOk, $err>(
-            data,
-            move |slot| {
-                {
-                    // Shadow the structure so it cannot be used to return early.
-                    struct __InitOk;
-                    // If `$init_zeroed` is present we should zero the slot now and not emit an
-                    // error when fields are missing (since they will be zeroed). We also have to
-                    // check that the type actually implements `Zeroable`.
-                    $({
-                        fn assert_zeroable<T: $crate::Zeroable>(_: *mut T) {}
-                        // Ensure that the struct is indeed `Zeroable`.
-                        assert_zeroable(slot);
-                        // SAFETY: The type implements `Zeroable` by the check above.
-                        unsafe { ::core::ptr::write_bytes(slot, 0, 1) };
-                        $init_zeroed // This will be `()` if set.
-                    })?
-                    // Create the `this` so it can be referenced by the user inside of the
-                    // expressions creating the individual fields.
-                    $(let $this = unsafe { ::core::ptr::NonNull::new_unchecked(slot) };)?
-                    // Initialize every field.
-                    $crate::__init_internal!(init_slot($($use_data)?):
-                        @data(data),
-                        @slot(slot),
-                        @guards(),
-                        @munch_fields($($fields)*,),
-                    );
-                    // We use unreachable code to ensure that all fields have been mentioned exactly
-                    // once, this struct initializer will still be type-checked and complain with a
-                    // very natural error message if a field is forgotten/mentioned more than once.
-                    #[allow(unreachable_code, clippy::diverging_sub_expression)]
-                    let _ = || {
-                        $crate::__init_internal!(make_initializer:
-                            @slot(slot),
-                            @type_name($t),
-                            @munch_fields($($fields)*,),
-                            @acc(),
-                        );
-                    };
-                }
-                Ok(__InitOk)
-            }
-        );
-        let init = move |slot| -> ::core::result::Result<(), $err> {
-            init(slot).map(|__InitOk| ())
-        };
-        let init = unsafe { $crate::$construct_closure::<_, $err>(init) };
-        init
-    }};
-    (init_slot($($use_data:ident)?):
-        @data($data:ident),
-        @slot($slot:ident),
-        @guards($($guards:ident,)*),
-        @munch_fields($(..Zeroable::zeroed())? $(,)?),
-    ) => {
-        // Endpoint of munching, no fields are left. If execution reaches this point, all fields
-        // have been initialized. Therefore we can now dismiss the guards by forgetting them.
-        $(::core::mem::forget($guards);)*
-    };
-    (init_slot($use_data:ident): // `use_data` is present, so we use the `data` to init fields.
-        @data($data:ident),
-        @slot($slot:ident),
-        @guards($($guards:ident,)*),
-        // In-place initialization syntax.
-        @munch_fields($field:ident <- $val:expr, $($rest:tt)*),
-    ) => {
-        let init = $val;
-        // Call the initializer.
-        //
-        // SAFETY: `slot` is valid, because we are inside of an initializer closure, we
-        // return when an error/panic occurs.
-        // We also use the `data` to require the correct trait (`Init` or `PinInit`) for `$field`.
-        unsafe { $data.$field(::core::ptr::addr_of_mut!((*$slot).$field), init)? };
-        // Create the drop guard:
-        //
-        // We rely on macro hygiene to make it impossible for users to access this local variable.
-        // We use `paste!` to create new hygiene for `$field`.
-        $crate::macros::paste! {
-            // SAFETY: We forget the guard later when initialization has succeeded.
-            let [< __ $field _guard >] = unsafe {
-                $crate::__internal::DropGuard::new(::core::ptr::addr_of_mut!((*$slot).$field))
-            };
-
-            $crate::__init_internal!(init_slot($use_data):
-                @data($data),
-                @slot($slot),
-                @guards([< __ $field _guard >], $($guards,)*),
-                @munch_fields($($rest)*),
-            );
-        }
-    };
-    (init_slot(): // No `use_data`, so we use `Init::__init` directly.
-        @data($data:ident),
-        @slot($slot:ident),
-        @guards($($guards:ident,)*),
-        // In-place initialization syntax.
-        @munch_fields($field:ident <- $val:expr, $($rest:tt)*),
-    ) => {
-        let init = $val;
-        // Call the initializer.
-        //
-        // SAFETY: `slot` is valid, because we are inside of an initializer closure, we
-        // return when an error/panic occurs.
-        unsafe { $crate::Init::__init(init, ::core::ptr::addr_of_mut!((*$slot).$field))? };
-        // Create the drop guard:
-        //
-        // We rely on macro hygiene to make it impossible for users to access this local variable.
-        // We use `paste!` to create new hygiene for `$field`.
-        $crate::macros::paste! {
-            // SAFETY: We forget the guard later when initialization has succeeded.
-            let [< __ $field _guard >] = unsafe {
-                $crate::__internal::DropGuard::new(::core::ptr::addr_of_mut!((*$slot).$field))
-            };
-
-            $crate::__init_internal!(init_slot():
-                @data($data),
-                @slot($slot),
-                @guards([< __ $field _guard >], $($guards,)*),
-                @munch_fields($($rest)*),
-            );
-        }
-    };
-    (init_slot($($use_data:ident)?):
-        @data($data:ident),
-        @slot($slot:ident),
-        @guards($($guards:ident,)*),
-        // Init by-value.
-        @munch_fields($field:ident $(: $val:expr)?, $($rest:tt)*),
-    ) => {
-        {
-            $(let $field = $val;)?
-            // Initialize the field.
-            //
-            // SAFETY: The memory at `slot` is uninitialized.
-            unsafe { ::core::ptr::write(::core::ptr::addr_of_mut!((*$slot).$field), $field) };
-        }
-        // Create the drop guard:
-        //
-        // We rely on macro hygiene to make it impossible for users to access this local variable.
-        // We use `paste!` to create new hygiene for `$field`.
-        $crate::macros::paste! {
-            // SAFETY: We forget the guard later when initialization has succeeded.
-            let [< __ $field _guard >] = unsafe {
-                $crate::__internal::DropGuard::new(::core::ptr::addr_of_mut!((*$slot).$field))
-            };
-
-            $crate::__init_internal!(init_slot($($use_data)?):
-                @data($data),
-                @slot($slot),
-                @guards([< __ $field _guard >], $($guards,)*),
-                @munch_fields($($rest)*),
-            );
-        }
-    };
-    (make_initializer:
-        @slot($slot:ident),
-        @type_name($t:path),
-        @munch_fields(..Zeroable::zeroed() $(,)?),
-        @acc($($acc:tt)*),
-    ) => {
-        // Endpoint, nothing more to munch, create the initializer. Since the users specified
-        // `..Zeroable::zeroed()`, the slot will already have been zeroed and all field that have
-        // not been overwritten are thus zero and initialized. We still check that all fields are
-        // actually accessible by using the struct update syntax ourselves.
-        // We are inside of a closure that is never executed and thus we can abuse `slot` to
-        // get the correct type inference here:
-        #[allow(unused_assignments)]
-        unsafe {
-            let mut zeroed = ::core::mem::zeroed();
-            // We have to use type inference here to make zeroed have the correct type. This does
-            // not get executed, so it has no effect.
-            ::core::ptr::write($slot, zeroed);
-            zeroed = ::core::mem::zeroed();
-            // Here we abuse `paste!` to retokenize `$t`. Declarative macros have some internal
-            // information that is associated to already parsed fragments, so a path fragment
-            // cannot be used in this position. Doing the retokenization results in valid rust
-            // code.
-            $crate::macros::paste!(
-                ::core::ptr::write($slot, $t {
-                    $($acc)*
-                    ..zeroed
-                });
-            );
-        }
-    };
-    (make_initializer:
-        @slot($slot:ident),
-        @type_name($t:path),
-        @munch_fields($(,)?),
-        @acc($($acc:tt)*),
-    ) => {
-        // Endpoint, nothing more to munch, create the initializer.
-        // Since we are in the closure that is never called, this will never get executed.
-        // We abuse `slot` to get the correct type inference here:
-        unsafe {
-            // Here we abuse `paste!` to retokenize `$t`. Declarative macros have some internal
-            // information that is associated to already parsed fragments, so a path fragment
-            // cannot be used in this position. Doing the retokenization results in valid rust
-            // code.
-            $crate::macros::paste!(
-                ::core::ptr::write($slot, $t {
-                    $($acc)*
-                });
-            );
-        }
-    };
-    (make_initializer:
-        @slot($slot:ident),
-        @type_name($t:path),
-        @munch_fields($field:ident <- $val:expr, $($rest:tt)*),
-        @acc($($acc:tt)*),
-    ) => {
-        $crate::__init_internal!(make_initializer:
-            @slot($slot),
-            @type_name($t),
-            @munch_fields($($rest)*),
-            @acc($($acc)* $field: ::core::panic!(),),
-        );
-    };
-    (make_initializer:
-        @slot($slot:ident),
-        @type_name($t:path),
-        @munch_fields($field:ident $(: $val:expr)?, $($rest:tt)*),
-        @acc($($acc:tt)*),
-    ) => {
-        $crate::__init_internal!(make_initializer:
-            @slot($slot),
-            @type_name($t),
-            @munch_fields($($rest)*),
-            @acc($($acc)* $field: ::core::panic!(),),
-        );
-    };
-}
-
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __derive_zeroable {
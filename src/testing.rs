@@ -0,0 +1,604 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Fault injection for the error paths of initializers, enabled by the `testing` feature.
+//!
+//! Every step of an initializer is a *failure point*: the start of a field or of a tuple or array
+//! element in the `[try_][pin_]init!` macros, the start of a field in the `build` function of
+//! `#[pin_data(builder)]`, the start of an element in [`init_array_from_fn`] and
+//! [`pin_init_array_from_fn`] and the allocation of [`InPlaceInit`]. While a [`FailAt`] is alive,
+//! the failure point it selects panics with an [`InjectedFailure`]. The fields and elements that
+//! have been initialized up to that point are dropped by the same guards that roll back an error,
+//! so a test can check that nothing leaks and nothing is dropped twice.
+//!
+//! [`for_each_failure_point`] runs a construction once for every failure point it reaches and
+//! fails at each of them in turn.
+//!
+//! The failure points are counted per thread, so tests can run in parallel.
+//!
+//! # Returning errors
+//!
+//! Injected failures unwind, so they only exercise the guards while unwinding and cannot be used
+//! with `panic = "abort"`. The code paths that handle an `Err` returned by an initializer are
+//! exercised with *error points* instead: a [`FallibleInit`] wraps an initializer and returns an
+//! [`InjectedError`] when its error point is selected by an [`ErrAt`], without calling the wrapped
+//! initializer. With the `alloc` feature, the allocation of [`InPlaceInit`] is an error point as
+//! well and fails with `AllocError`. [`for_each_error_point`] is the counterpart of
+//! [`for_each_failure_point`] and does not unwind.
+//!
+//! The fields of the `[try_][pin_]init!` macros and the elements of the array functions can not
+//! return an error of an arbitrary type, so they are only failure points. Wrap their initializers
+//! in a [`FallibleInit`] to let them fail with an error.
+//!
+//! ```rust
+//! use pinned_init::{testing::*, *};
+//!
+//! #[derive(Debug)]
+//! struct Error;
+//!
+//! impl From<InjectedError> for Error {
+//!     fn from(_: InjectedError) -> Self {
+//!         Self
+//!     }
+//! }
+//!
+//! #[pin_data]
+//! struct Pair {
+//!     #[pin]
+//!     a: Tracked<u32>,
+//!     #[pin]
+//!     b: Tracked<u32>,
+//! }
+//!
+//! fn tracked(value: u32) -> impl PinInit<Tracked<u32>, Error> {
+//!     FallibleInit::new(TrackedInit::new(value))
+//! }
+//!
+//! let points = for_each_error_point(|| {
+//!     try_pin_init!(Pair {
+//!         a <- tracked(1),
+//!         b <- tracked(2),
+//!     }? Error)
+//! });
+//! assert_eq!(points, 2);
+//! assert_each_dropped_once();
+//! ```
+//!
+//! # Examples
+//!
+//! ```rust
+//! use pinned_init::{testing::*, *};
+//! use std::cell::Cell;
+//!
+//! std::thread_local! {
+//!     static ALIVE: Cell<isize> = const { Cell::new(0) };
+//! }
+//!
+//! struct Buf(u8);
+//!
+//! impl Buf {
+//!     fn new(v: u8) -> Self {
+//!         ALIVE.set(ALIVE.get() + 1);
+//!         Self(v)
+//!     }
+//! }
+//!
+//! impl Drop for Buf {
+//!     fn drop(&mut self) {
+//!         ALIVE.set(ALIVE.get() - 1);
+//!     }
+//! }
+//!
+//! #[pin_data]
+//! struct Pair {
+//!     a: Buf,
+//!     b: Buf,
+//! }
+//!
+//! let points = for_each_failure_point(|| {
+//!     pin_init!(Pair {
+//!         a: Buf::new(0),
+//!         b: Buf::new(1),
+//!     })
+//! });
+//! assert_eq!(points, 2);
+//! assert_eq!(ALIVE.get(), 0);
+//! ```
+//!
+//! # Drop accounting
+//!
+//! A [`DropCounter`] records when it is created and dropped in a ledger of the current thread.
+//! [`TrackedInit`] wraps any initializer of `T` into one of [`Tracked<T>`], which carries a
+//! [`DropCounter`] that is only created when the wrapped initializer succeeds. After a test,
+//! [`assert_no_leaks`] and [`assert_each_dropped_once`] check the ledger. Used as the parts of a
+//! custom initializer, they show whether it really drops everything it initialized when it
+//! returns `Err`.
+//!
+//! ```rust
+//! use pinned_init::{testing::*, *};
+//!
+//! #[pin_data]
+//! struct Pair {
+//!     #[pin]
+//!     a: Tracked<u32>,
+//!     #[pin]
+//!     b: Tracked<u32>,
+//! }
+//!
+//! fn fail() -> impl PinInit<u32, ()> {
+//!     // SAFETY: The closure always fails.
+//!     unsafe { pin_init_from_closure(|_| Err(())) }
+//! }
+//!
+//! let init = try_pin_init!(Pair {
+//!     a <- TrackedInit::new(1),
+//!     b <- TrackedInit::new(fail()),
+//! }? ());
+//! stack_try_pin_init!(let pair: Pair = init);
+//! assert!(pair.is_err());
+//! assert_eq!(drop_counts(), DropCounts { constructed: 1, dropped: 1 });
+//! assert_each_dropped_once();
+//! ```
+//!
+//! [`init_array_from_fn`]: crate::init_array_from_fn
+//! [`pin_init_array_from_fn`]: crate::pin_init_array_from_fn
+//! [`InPlaceInit`]: crate::InPlaceInit
+
+use crate::{
+    __internal::{Invariant, StackInit},
+    Init, PinInit,
+};
+use core::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    ops::Deref,
+    pin::{pin, Pin},
+    ptr::addr_of_mut,
+};
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+std::thread_local! {
+    /// The failure point that fails and the number of failure points reached so far.
+    static STATE: Cell<(Option<usize>, usize)> = const { Cell::new((None, 0)) };
+    /// The error point that fails and the number of error points reached so far.
+    static ERR_STATE: Cell<(Option<usize>, usize)> = const { Cell::new((None, 0)) };
+    /// How often each [`DropCounter`] has been dropped, indexed by its id.
+    static DROPS: RefCell<Ledger> = const {
+        RefCell::new(Ledger {
+            generation: 0,
+            drops: Vec::new(),
+        })
+    };
+}
+
+/// The payload of the panic of an injected failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InjectedFailure {
+    /// The failure point that failed, counted from zero.
+    pub point: usize,
+}
+
+/// Makes the `k`-th failure point of the current thread fail while it is alive.
+///
+/// The failure points are counted from zero, starting when the [`FailAt`] is created. Dropping
+/// it restores the previous state, so [`FailAt`]s can be nested.
+#[must_use = "the failure is only injected while the `FailAt` is alive"]
+pub struct FailAt {
+    previous: (Option<usize>, usize),
+}
+
+impl FailAt {
+    /// Makes the `k`-th failure point fail, counted from zero.
+    pub fn nth(k: usize) -> Self {
+        install_panic_hook();
+        Self {
+            previous: STATE.replace((Some(k), 0)),
+        }
+    }
+
+    /// Counts the failure points without failing any of them.
+    pub fn never() -> Self {
+        Self {
+            previous: STATE.replace((None, 0)),
+        }
+    }
+
+    /// Returns the number of failure points that have been reached since `self` was created,
+    /// including the one that failed.
+    pub fn points(&self) -> usize {
+        STATE.get().1
+    }
+}
+
+impl Drop for FailAt {
+    fn drop(&mut self) {
+        STATE.set(self.previous);
+    }
+}
+
+/// Constructs the initializer returned by `make_init` on the stack, first without failing and
+/// then once for every failure point that the first construction reached, failing at each of
+/// them in turn. Returns the number of failure points.
+///
+/// Errors returned by the initializer are ignored, the construction counts the failure points
+/// that it reached until then.
+///
+/// # Panics
+///
+/// Panics if a construction does not reach the failure point that should fail, e.g. because
+/// `make_init` does not always return the same initializer. Other panics are propagated.
+pub fn for_each_failure_point<T, E, I>(mut make_init: impl FnMut() -> I) -> usize
+where
+    I: PinInit<T, E>,
+{
+    let points = {
+        let fail = FailAt::never();
+        let _ = construct(make_init());
+        fail.points()
+    };
+    for k in 0..points {
+        let _fail = FailAt::nth(k);
+        match panic::catch_unwind(AssertUnwindSafe(|| construct(make_init()))) {
+            Ok(_) => panic!("failure point {k} of {points} has not been reached"),
+            Err(payload) if payload.is::<InjectedFailure>() => {}
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+    points
+}
+
+/// Initializes a value with `init` on the stack and drops it again, returns whether the
+/// initialization succeeded.
+fn construct<T, E>(init: impl PinInit<T, E>) -> bool {
+    let slot = pin!(StackInit::<T>::uninit());
+    StackInit::init(slot, init).is_ok()
+}
+
+/// Counts a failure point and fails if it is the one selected by [`FailAt`].
+pub(crate) fn fail_point() {
+    let (fail, point) = STATE.get();
+    STATE.set((fail, point + 1));
+    if fail == Some(point) {
+        panic::panic_any(InjectedFailure { point });
+    }
+}
+
+/// Installs a panic hook that does not report injected failures, all other panics are passed to
+/// the previous hook.
+fn install_panic_hook() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !info.payload().is::<InjectedFailure>() {
+                previous(info);
+            }
+        }));
+    });
+}
+
+/// The error returned by an error point selected by [`ErrAt`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InjectedError {
+    /// The error point that failed, counted from zero.
+    pub point: usize,
+}
+
+/// Makes the `k`-th error point of the current thread return an [`InjectedError`] while it is
+/// alive.
+///
+/// The error points are counted from zero, starting when the [`ErrAt`] is created, independently
+/// of the failure points. Dropping it restores the previous state, so [`ErrAt`]s can be nested.
+#[must_use = "the error is only injected while the `ErrAt` is alive"]
+pub struct ErrAt {
+    previous: (Option<usize>, usize),
+}
+
+impl ErrAt {
+    /// Makes the `k`-th error point fail, counted from zero.
+    pub fn nth(k: usize) -> Self {
+        Self {
+            previous: ERR_STATE.replace((Some(k), 0)),
+        }
+    }
+
+    /// Counts the error points without failing any of them.
+    pub fn never() -> Self {
+        Self {
+            previous: ERR_STATE.replace((None, 0)),
+        }
+    }
+
+    /// Returns the number of error points that have been reached since `self` was created,
+    /// including the one that failed.
+    pub fn points(&self) -> usize {
+        ERR_STATE.get().1
+    }
+}
+
+impl Drop for ErrAt {
+    fn drop(&mut self) {
+        ERR_STATE.set(self.previous);
+    }
+}
+
+/// Constructs the initializer returned by `make_init` on the stack, first without failing and
+/// then once for every error point that the first construction reached, failing at each of them
+/// in turn. Returns the number of error points.
+///
+/// Unlike [`for_each_failure_point`], this does not unwind, the injected errors are returned by
+/// the initializers and handled like any other error.
+///
+/// # Panics
+///
+/// Panics if a construction does not return an error at the error point that should fail, e.g.
+/// because `make_init` does not always return the same initializer.
+pub fn for_each_error_point<T, E, I>(mut make_init: impl FnMut() -> I) -> usize
+where
+    I: PinInit<T, E>,
+{
+    let points = {
+        let fail = ErrAt::never();
+        let _ = construct(make_init());
+        fail.points()
+    };
+    for k in 0..points {
+        let fail = ErrAt::nth(k);
+        if construct(make_init()) || fail.points() <= k {
+            panic!("error point {k} of {points} has not returned an error");
+        }
+    }
+    points
+}
+
+/// Counts an error point and returns an [`InjectedError`] if it is the one selected by
+/// [`ErrAt`].
+pub(crate) fn error_point() -> Result<(), InjectedError> {
+    let (fail, point) = ERR_STATE.get();
+    ERR_STATE.set((fail, point + 1));
+    if fail == Some(point) {
+        return Err(InjectedError { point });
+    }
+    Ok(())
+}
+
+/// Wraps an initializer into one with an error point in front of it.
+///
+/// When the error point is selected by an [`ErrAt`], the initializer returns an
+/// [`InjectedError`] and the wrapped initializer is dropped without being called.
+pub struct FallibleInit<I, T: ?Sized> {
+    init: I,
+    _phantom: Invariant<T>,
+}
+
+impl<I, T: ?Sized> FallibleInit<I, T> {
+    /// Wraps `init`.
+    pub fn new(init: I) -> Self {
+        Self {
+            init,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+// SAFETY: Forwards to `init`, on an injected error nothing has been initialized.
+unsafe impl<T: ?Sized, E, I> PinInit<T, E> for FallibleInit<I, T>
+where
+    I: PinInit<T, E>,
+    E: From<InjectedError>,
+{
+    unsafe fn __pinned_init(self, slot: *mut T) -> Result<(), E> {
+        error_point()?;
+        // SAFETY: The caller upholds the requirements of `__pinned_init`.
+        unsafe { self.init.__pinned_init(slot) }
+    }
+}
+
+// SAFETY: Same as the `PinInit` implementation, `init` does not require `slot` to be pinned.
+unsafe impl<T: ?Sized, E, I> Init<T, E> for FallibleInit<I, T>
+where
+    I: Init<T, E>,
+    E: From<InjectedError>,
+{
+    unsafe fn __init(self, slot: *mut T) -> Result<(), E> {
+        error_point()?;
+        // SAFETY: The caller upholds the requirements of `__init`.
+        unsafe { self.init.__init(slot) }
+    }
+}
+
+/// The drops recorded since the last [`reset_drop_counts`].
+struct Ledger {
+    /// The number of resets so far, counters of an older generation are no longer recorded.
+    generation: usize,
+    drops: Vec<usize>,
+}
+
+/// A value that records its creation and drop in the ledger of the current thread.
+///
+/// It must be dropped on the thread that created it, so it is `!Send`. Dropping it twice is
+/// undefined behavior, but in practice it still records the second drop, which
+/// [`assert_each_dropped_once`] reports.
+#[derive(Debug)]
+pub struct DropCounter {
+    id: usize,
+    generation: usize,
+    _not_send: PhantomData<*mut ()>,
+}
+
+impl DropCounter {
+    /// Creates a new [`DropCounter`] and records it in the ledger.
+    pub fn new() -> Self {
+        let (id, generation) = DROPS.with_borrow_mut(|ledger| {
+            ledger.drops.push(0);
+            (ledger.drops.len() - 1, ledger.generation)
+        });
+        Self {
+            id,
+            generation,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the id of this counter, the counters of a thread are numbered from zero in the
+    /// order of their creation, starting again after [`reset_drop_counts`].
+    pub fn id(&self) -> usize {
+        self.id
+    }
+}
+
+impl Default for DropCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        // The ledger is gone if the counter is dropped while the thread exits.
+        let _ = DROPS.try_with(|ledger| {
+            let mut ledger = ledger.borrow_mut();
+            if ledger.generation != self.generation {
+                return;
+            }
+            if let Some(drops) = ledger.drops.get_mut(self.id) {
+                *drops += 1;
+            }
+        });
+    }
+}
+
+/// A `T` with a [`DropCounter`], created by [`TrackedInit`].
+///
+/// The value is structurally pinned.
+#[derive(Debug)]
+pub struct Tracked<T> {
+    value: T,
+    counter: DropCounter,
+}
+
+impl<T> Tracked<T> {
+    /// Returns the counter that records the drop of this value.
+    pub fn counter(&self) -> &DropCounter {
+        &self.counter
+    }
+
+    /// Returns a pinned mutable reference to the value.
+    pub fn value(self: Pin<&mut Self>) -> Pin<&mut T> {
+        // SAFETY: The value is structurally pinned and never moved out of `self`.
+        unsafe { self.map_unchecked_mut(|this| &mut this.value) }
+    }
+}
+
+impl<T> Deref for Tracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// Wraps an initializer of `T` into an initializer of [`Tracked<T>`].
+///
+/// The [`DropCounter`] of the value is only created when the wrapped initializer succeeds, so
+/// the ledger records exactly the values that have been constructed.
+pub struct TrackedInit<I> {
+    init: I,
+}
+
+impl<I> TrackedInit<I> {
+    /// Wraps `init`.
+    pub fn new(init: I) -> Self {
+        Self { init }
+    }
+}
+
+// SAFETY: The value is initialized by `init`, which keeps it pinned, the counter is only written
+// when that succeeded. On error, nothing has been initialized.
+unsafe impl<T, E, I: PinInit<T, E>> PinInit<Tracked<T>, E> for TrackedInit<I> {
+    unsafe fn __pinned_init(self, slot: *mut Tracked<T>) -> Result<(), E> {
+        // SAFETY: `slot` is valid for writes and the value is structurally pinned.
+        unsafe { self.init.__pinned_init(addr_of_mut!((*slot).value))? };
+        // SAFETY: `slot` is valid for writes.
+        unsafe { addr_of_mut!((*slot).counter).write(DropCounter::new()) };
+        Ok(())
+    }
+}
+
+// SAFETY: Same as the `PinInit` implementation, `init` does not require the value to be pinned.
+unsafe impl<T, E, I: Init<T, E>> Init<Tracked<T>, E> for TrackedInit<I> {
+    unsafe fn __init(self, slot: *mut Tracked<T>) -> Result<(), E> {
+        // SAFETY: `slot` is valid for writes.
+        unsafe { self.init.__init(addr_of_mut!((*slot).value))? };
+        // SAFETY: `slot` is valid for writes.
+        unsafe { addr_of_mut!((*slot).counter).write(DropCounter::new()) };
+        Ok(())
+    }
+}
+
+/// The number of [`DropCounter`]s created and drops recorded on the current thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DropCounts {
+    /// The number of counters that have been created.
+    pub constructed: usize,
+    /// The number of drops of all counters, a counter that is dropped twice counts twice.
+    pub dropped: usize,
+}
+
+/// Returns the [`DropCounts`] of the current thread.
+pub fn drop_counts() -> DropCounts {
+    DROPS.with_borrow(|ledger| DropCounts {
+        constructed: ledger.drops.len(),
+        dropped: ledger.drops.iter().sum(),
+    })
+}
+
+/// Forgets all [`DropCounter`]s of the current thread, counters that are still alive are no
+/// longer recorded when they are dropped.
+pub fn reset_drop_counts() {
+    DROPS.with_borrow_mut(|ledger| {
+        ledger.generation += 1;
+        ledger.drops.clear();
+    });
+}
+
+/// Returns the ids of the counters of the current thread whose number of drops `matches`.
+fn dropped(matches: impl Fn(usize) -> bool) -> Vec<usize> {
+    DROPS.with_borrow(|ledger| {
+        ledger
+            .drops
+            .iter()
+            .enumerate()
+            .filter(|&(_, &n)| matches(n))
+            .map(|(id, _)| id)
+            .collect()
+    })
+}
+
+/// Asserts that every [`DropCounter`] of the current thread has been dropped.
+///
+/// # Panics
+///
+/// Panics with the ids of the counters that are still alive.
+#[track_caller]
+pub fn assert_no_leaks() {
+    let leaked = dropped(|n| n == 0);
+    assert!(leaked.is_empty(), "leaked `DropCounter`s: {leaked:?}");
+}
+
+/// Asserts that every [`DropCounter`] of the current thread has been dropped exactly once.
+///
+/// # Panics
+///
+/// Panics with the ids of the counters that are still alive or have been dropped more than once.
+#[track_caller]
+pub fn assert_each_dropped_once() {
+    assert_no_leaks();
+    let twice = dropped(|n| n > 1);
+    assert!(
+        twice.is_empty(),
+        "`DropCounter`s dropped more than once: {twice:?}"
+    );
+}
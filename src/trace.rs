@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tracing of initialization steps, enabled by the `trace` feature.
+//!
+//! Struct, tuple and array initializers created by the `[try_][pin_]init!` macros, the builders of
+//! `#[pin_data(builder)]`, [`init_array_from_fn`], [`pin_init_array_from_fn`] and the allocations
+//! of [`InPlaceInit`] report what they do to the hook installed with [`set_hook`]. This shows the
+//! order in which a nested object graph is constructed and how it is rolled back on failure.
+//!
+//! The hook is a plain `fn` pointer stored in an atomic, so this module works without `std`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #![feature(allocator_api)]
+//! use pinned_init::{trace::*, *};
+//! use std::cell::RefCell;
+//!
+//! std::thread_local! {
+//!     static EVENTS: RefCell<Vec<Event>> = const { RefCell::new(Vec::new()) };
+//! }
+//!
+//! fn record(event: &Event) {
+//!     EVENTS.with(|events| events.borrow_mut().push(*event));
+//! }
+//!
+//! struct Point {
+//!     x: u32,
+//!     y: u32,
+//! }
+//!
+//! set_hook(Some(record));
+//! let _ = Box::init(init!(Point { x: 1, y: 2 }));
+//! set_hook(None);
+//!
+//! let events = EVENTS.with(|events| events.take());
+//! assert!(matches!(events[0], Event::Alloc { size: 8, .. }));
+//! assert!(matches!(events[1], Event::Start(Field { name: "x", index: 0, .. })));
+//! assert!(matches!(events[2], Event::Finish(Field { name: "x", index: 0, .. })));
+//! assert!(matches!(events[3], Event::Start(Field { name: "y", index: 1, .. })));
+//! ```
+//!
+//! [`init_array_from_fn`]: crate::init_array_from_fn
+//! [`pin_init_array_from_fn`]: crate::pin_init_array_from_fn
+//! [`InPlaceInit`]: crate::InPlaceInit
+
+use core::{
+    mem, ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+/// A field of a struct or an element of a tuple or an array that is being initialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field {
+    /// The name of the struct, tuple or array type.
+    pub type_name: &'static str,
+    /// The name of the field, empty for tuple and array elements.
+    pub name: &'static str,
+    /// The position of the field in the initializer, or the index of the element.
+    pub index: usize,
+}
+
+/// A step of an initializer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Event {
+    /// The initialization of a field starts.
+    Start(Field),
+    /// A field has been initialized.
+    Finish(Field),
+    /// A field is dropped again, because a later field failed to initialize.
+    Rollback(Field),
+    /// The initializer of `type_name` failed, its initialized fields have been dropped.
+    Error {
+        /// The name of the struct, tuple or array type.
+        type_name: &'static str,
+    },
+    /// [`InPlaceInit`](crate::InPlaceInit) allocates memory for a value of `type_name`.
+    Alloc {
+        /// The name of the allocated type.
+        type_name: &'static str,
+        /// The size of the allocation in bytes.
+        size: usize,
+    },
+}
+
+/// A hook that receives the [`Event`]s of all initializers.
+pub type Hook = fn(&Event);
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Installs `hook`, replacing the previous one. `None` disables tracing.
+pub fn set_hook(hook: Option<Hook>) {
+    let hook = hook.map_or(ptr::null_mut(), |hook| hook as *mut ());
+    HOOK.store(hook, Ordering::Release);
+}
+
+/// Reports `event` to the installed hook.
+#[inline]
+pub fn emit(event: Event) {
+    let hook = HOOK.load(Ordering::Acquire);
+    if !hook.is_null() {
+        // SAFETY: Only `set_hook` stores non-null pointers and it only stores `Hook`s.
+        let hook = unsafe { mem::transmute::<*mut (), Hook>(hook) };
+        hook(&event);
+    }
+}
@@ -0,0 +1,206 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+
+use core::{
+    cell::Cell,
+    convert::Infallible,
+    future::Future,
+    marker::PhantomPinned,
+    pin::{pin, Pin},
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+use pinned_init::*;
+use std::{rc::Rc, sync::Arc};
+
+#[cfg(feature = "alloc")]
+use core::alloc::AllocError;
+
+fn waker() -> Waker {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |_| RawWaker::new(core::ptr::null(), &VTABLE),
+        |_| {},
+        |_| {},
+        |_| {},
+    );
+    // SAFETY: The functions of the vtable do nothing.
+    unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) }
+}
+
+fn poll<F: Future>(fut: Pin<&mut F>) -> Poll<F::Output> {
+    fut.poll(&mut Context::from_waker(&waker()))
+}
+
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = pin!(fut);
+    loop {
+        if let Poll::Ready(res) = poll(fut.as_mut()) {
+            return res;
+        }
+    }
+}
+
+/// Returns `Pending` once before it completes.
+async fn yield_now() {
+    let mut yielded = false;
+    core::future::poll_fn(|_| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+async fn handshake(peer: u32) -> Result<u32, Error> {
+    yield_now().await;
+    if peer == 0 {
+        Err(Error)
+    } else {
+        Ok(peer * 2)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Error;
+
+impl From<Infallible> for Error {
+    fn from(e: Infallible) -> Self {
+        match e {}
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<AllocError> for Error {
+    fn from(_: AllocError) -> Self {
+        Self
+    }
+}
+
+/// Counts how often it has been dropped.
+struct Tracked(Rc<Cell<usize>>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[pin_data]
+struct Session {
+    key: u32,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+impl Session {
+    fn new(peer: u32) -> impl AsyncPinInit<Self, Error, Future: Send> {
+        try_async_pin_init!(Self {
+            key: handshake(peer).await?,
+            ..
+        }? Error)
+    }
+}
+
+#[pin_data]
+struct Connection {
+    peer: u32,
+    #[pin]
+    session: Session,
+    #[pin]
+    buf: [u8; 4],
+}
+
+impl Connection {
+    fn new(peer: u32) -> impl AsyncPinInit<Self, Error, Future: Send> {
+        try_async_pin_init!(Self {
+            peer,
+            session <- Session::new(peer),
+            buf <- zeroed(),
+        }? Error)
+    }
+}
+
+#[pin_data]
+struct Guarded {
+    first: Tracked,
+    #[pin]
+    second: u32,
+}
+
+impl Guarded {
+    fn new(drops: Rc<Cell<usize>>, peer: u32) -> impl AsyncPinInit<Self, Error> {
+        try_async_pin_init!(Self {
+            first: Tracked(drops),
+            second: handshake(peer).await?,
+        }? Error)
+    }
+}
+
+#[test]
+fn box_async() {
+    let conn = block_on(Box::try_pin_init_async(Connection::new(21))).unwrap();
+    assert_eq!(conn.peer, 21);
+    assert_eq!(conn.session.key, 42);
+    assert_eq!(conn.buf, [0; 4]);
+}
+
+#[test]
+fn arc_async() {
+    let conn: Pin<Arc<Connection>> = block_on(Arc::try_pin_init_async(Connection::new(1))).unwrap();
+    assert_eq!(conn.session.key, 2);
+}
+
+#[test]
+fn infallible() {
+    let session = async_pin_init!(Session {
+        key: async { 7 }.await,
+        ..
+    });
+    let session = block_on(Box::pin_init_async(session)).unwrap();
+    assert_eq!(session.key, 7);
+}
+
+#[test]
+fn error_drops_fields() {
+    let drops = Rc::new(Cell::new(0));
+    let res = block_on(Box::try_pin_init_async(Guarded::new(drops.clone(), 0)));
+    assert_eq!(res.err(), Some(Error));
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn cancel_drops_fields() {
+    let drops = Rc::new(Cell::new(0));
+    {
+        let mut fut = pin!(Box::try_pin_init_async(Guarded::new(drops.clone(), 1)));
+        // The first field is initialized and the handshake is pending.
+        assert!(poll(fut.as_mut()).is_pending());
+        assert_eq!(drops.get(), 0);
+    }
+    assert_eq!(drops.get(), 1);
+    let guarded = block_on(Box::try_pin_init_async(Guarded::new(drops.clone(), 1))).unwrap();
+    assert_eq!(guarded.second, 2);
+    drop(guarded);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn from_closure() {
+    // SAFETY: The future initializes the value before it resolves.
+    let init = unsafe {
+        async_pin_init_from_closure(|slot: *mut u32| async move {
+            let value = handshake(3).await?;
+            slot.write(value);
+            Ok::<_, Error>(())
+        })
+    };
+    let value = block_on(Box::try_pin_init_async(init)).unwrap();
+    assert_eq!(*value, 6);
+}
+
+#[test]
+fn send() {
+    fn assert_send<T: Send>(_: T) {}
+    assert_send(Box::try_pin_init_async(Connection::new(1)));
+}
@@ -0,0 +1,107 @@
+use core::{
+    cell::Cell,
+    convert::Infallible,
+    marker::PhantomPinned,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use pinned_init::*;
+
+#[pin_data(builder)]
+pub struct Config<T> {
+    #[pin]
+    counter: Counter,
+    value: T,
+    #[default(8)]
+    depth: usize,
+    #[pin]
+    #[default_init(Counter::new(7))]
+    spare: Counter,
+}
+
+#[pin_data]
+pub struct Counter {
+    count: Cell<usize>,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+impl Counter {
+    fn new(count: usize) -> impl PinInit<Self> {
+        pin_init!(Self {
+            count: Cell::new(count),
+            _pin: PhantomPinned,
+        })
+    }
+}
+
+#[test]
+fn builder() {
+    stack_pin_init!(let cfg = Config::builder()
+        .value("hello")
+        .counter(Counter::new(1))
+        .build());
+    assert_eq!(cfg.counter.count.get(), 1);
+    assert_eq!(cfg.value, "hello");
+    assert_eq!(cfg.depth, 8);
+    assert_eq!(cfg.spare.count.get(), 7);
+    stack_pin_init!(let cfg = Config::builder()
+        .depth(2)
+        .counter(Counter::new(3))
+        .value(4u8)
+        .spare(Counter::new(5))
+        .build());
+    assert_eq!(cfg.counter.count.get(), 3);
+    assert_eq!(cfg.value, 4);
+    assert_eq!(cfg.depth, 2);
+    assert_eq!(cfg.spare.count.get(), 5);
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+struct Tracked(usize);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(self.0, Ordering::SeqCst);
+    }
+}
+
+#[pin_data(PinnedDrop, builder)]
+struct WithDrop {
+    a: Tracked,
+    #[pin]
+    b: Tracked,
+    #[default(Tracked(100))]
+    c: Tracked,
+}
+
+#[pinned_drop]
+impl PinnedDrop for WithDrop {
+    fn drop(self: Pin<&mut Self>) {
+        DROPPED.fetch_add(1000, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn builder_error() {
+    {
+        stack_try_pin_init!(let val = WithDrop::builder::<Infallible>()
+            .b(Tracked(10))
+            .a(Tracked(1))
+            .build());
+        assert_eq!(val.unwrap().c.0, 100);
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1111);
+    // On failure, the fields initialized so far are dropped, but `PinnedDrop` is not run.
+    DROPPED.store(0, Ordering::SeqCst);
+    {
+        stack_try_pin_init!(let val: WithDrop = WithDrop::builder()
+            .a(Tracked(1))
+            // SAFETY: The closure always fails.
+            .b(unsafe { pin_init_from_closure(|_| Err(())) })
+            .build());
+        assert!(val.is_err());
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,128 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+
+use core::{cell::Cell, convert::Infallible, marker::PhantomPinned};
+use pinned_init::*;
+use std::rc::Rc;
+
+#[cfg(feature = "alloc")]
+use core::alloc::AllocError;
+
+#[derive(Debug, PartialEq)]
+struct Error;
+
+impl From<Infallible> for Error {
+    fn from(e: Infallible) -> Self {
+        match e {}
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<AllocError> for Error {
+    fn from(_: AllocError) -> Self {
+        Self
+    }
+}
+
+/// Counts how often it has been dropped.
+struct Tracked(Rc<Cell<usize>>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[pin_data]
+struct Plugin {
+    first: Tracked,
+    #[pin]
+    second: Tracked,
+    id: u32,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+fn load(id: u32) -> Result<u32, Error> {
+    match id {
+        0 => panic!("plugin {id} is broken"),
+        1 => Err(Error),
+        _ => Ok(id),
+    }
+}
+
+impl Plugin {
+    fn new(drops: &Rc<Cell<usize>>, id: u32) -> impl PinInit<Self, Error> + '_ {
+        try_pin_init!(Self {
+            first: Tracked(drops.clone()),
+            second: Tracked(drops.clone()),
+            id: load(id)?,
+            ..
+        }? Error)
+    }
+}
+
+#[test]
+fn panic_is_caught() {
+    let drops = Rc::new(Cell::new(0));
+    let err = Box::<Plugin>::try_pin_init(Plugin::new(&drops, 0).catch_unwind())
+        .err()
+        .unwrap();
+    assert_eq!(err.message(), Some("plugin 0 is broken"));
+    // Both fields have been dropped while unwinding.
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn error_is_forwarded() {
+    let drops = Rc::new(Cell::new(0));
+    let err = Box::<Plugin>::try_pin_init(Plugin::new(&drops, 1).catch_unwind())
+        .err()
+        .unwrap();
+    assert!(matches!(err, PanicError::Error(Error)));
+    assert_eq!(err.message(), None);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn success() {
+    let drops = Rc::new(Cell::new(0));
+    stack_try_pin_init!(let plugin: Plugin = Plugin::new(&drops, 2).catch_unwind());
+    let plugin = plugin.unwrap();
+    assert_eq!(plugin.id, 2);
+    assert_eq!(drops.get(), 0);
+}
+
+struct Config {
+    name: String,
+    size: usize,
+}
+
+fn size() -> usize {
+    panic!("no size")
+}
+
+#[test]
+fn init() {
+    let init = try_init!(Config {
+        name: "config".to_owned(),
+        size: size(),
+    }? Error);
+    let err = Box::<Config>::try_init(init.catch_unwind()).err().unwrap();
+    assert_eq!(err.message(), Some("no size"));
+    assert_eq!(format!("{err:?}"), r#"Panic("no size")"#);
+}
+
+fn no_message() -> usize {
+    std::panic::panic_any(42u32)
+}
+
+#[test]
+fn payload_without_message() {
+    let init = try_init!(Config {
+        name: "config".to_owned(),
+        size: no_message(),
+    }? Error);
+    let err = Box::<Config>::try_init(init.catch_unwind()).err().unwrap();
+    assert_eq!(err.message(), None);
+    assert_eq!(format!("{err:?}"), "Panic(..)");
+}
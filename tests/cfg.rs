@@ -0,0 +1,83 @@
+use core::{cell::Cell, marker::PhantomPinned};
+use pinned_init::*;
+
+// `cfg(any())` is never enabled and `cfg(not(any()))` is always enabled.
+
+#[pin_data(constructor)]
+struct Stats {
+    hits: usize,
+    #[cfg(any())]
+    misses: usize,
+    #[cfg(not(any()))]
+    total: usize,
+    #[pin]
+    #[cfg(any())]
+    pinned: Pinned,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[pin_data]
+struct Pinned {
+    value: Cell<usize>,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[pin_data]
+struct Config {
+    name: &'static str,
+    #[cfg(any())]
+    path: &'static str,
+    #[cfg(any())]
+    #[default(8)]
+    depth: usize,
+    #[cfg(not(any()))]
+    #[default(4)]
+    width: usize,
+}
+
+#[test]
+fn cfg_fields() {
+    stack_pin_init!(let stats = pin_init!(Stats {
+        hits: 1,
+        #[cfg(any())]
+        misses: 2,
+        #[cfg(any())]
+        pinned <- pin_init!(Pinned {
+            value: Cell::new(3),
+            _pin: PhantomPinned,
+        }),
+        #[cfg(not(any()))]
+        total: 3,
+        _pin: PhantomPinned,
+    }));
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.total, 3);
+}
+
+#[test]
+fn cfg_defaults() {
+    stack_pin_init!(let config = pin_init!(Config {
+        name: "config",
+        ..Default
+    }));
+    assert_eq!(config.name, "config");
+    assert_eq!(config.width, 4);
+    stack_pin_init!(let config = pin_init!(Config {
+        name: "config",
+        #[cfg(any())]
+        path: "/",
+        #[cfg(not(any()))]
+        width: 2,
+        ..Default
+    }));
+    assert_eq!(config.width, 2);
+}
+
+#[test]
+fn cfg_constructor() {
+    stack_pin_init!(let stats = Stats::new(1, 2));
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.total, 2);
+}
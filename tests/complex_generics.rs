@@ -0,0 +1,58 @@
+use core::{
+    marker::{PhantomData, PhantomPinned},
+    num::Wrapping,
+    pin::Pin,
+};
+use pinned_init::*;
+
+fn first(x: &[u8]) -> &u8 {
+    &x[0]
+}
+
+#[pin_data(PinnedDrop)]
+struct Foo<F: for<'a> Fn(&'a [u8]) -> &'a u8, T: Into<Vec<Vec<u8>>>, const N: usize = { 4 >> 1 }>
+where
+    for<'a> &'a T: IntoIterator<Item = &'a Vec<u8>>,
+{
+    f: F,
+    t: T,
+    array: [u8; N],
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[pinned_drop]
+impl<F: for<'a> Fn(&'a [u8]) -> &'a u8, T: Into<Vec<Vec<u8>>>, const N: usize> PinnedDrop
+    for Foo<F, T, N>
+where
+    for<'a> &'a T: IntoIterator<Item = &'a Vec<u8>>,
+{
+    fn drop(self: Pin<&mut Self>) {}
+}
+
+#[derive(Zeroable)]
+struct Bar<'a, T: 'a + Into<Wrapping<Wrapping<u8>>>, const N: usize = { 4 >> 1 }>
+where
+    fn() -> T: Copy,
+    for<'b> &'b T: Copy,
+{
+    r: PhantomData<&'a T>,
+    t: T,
+    array: [u8; N],
+}
+
+#[test]
+fn complex_generics() {
+    stack_pin_init!(let foo: Foo<_, Vec<Vec<u8>>> = pin_init!(Foo {
+        f: first,
+        t: vec![vec![1, 2], vec![3]],
+        array: [4, 5],
+        _pin: PhantomPinned,
+    }));
+    let firsts = foo.t.iter().map(|v| *(foo.f)(v)).collect::<Vec<_>>();
+    assert_eq!(firsts, [1, 3]);
+    assert_eq!(foo.array, [4, 5]);
+    stack_pin_init!(let bar = zeroed::<Bar<'_, Wrapping<Wrapping<u8>>>>());
+    assert_eq!(bar.t.0 .0, 0);
+    assert_eq!(bar.array, [0, 0]);
+}
@@ -0,0 +1,59 @@
+use core::{cell::Cell, convert::Infallible, marker::PhantomPinned};
+use pinned_init::*;
+
+#[pin_data(constructor)]
+pub struct Pair<T> {
+    #[pin]
+    first: Counter,
+    second: T,
+}
+
+#[pin_data(PinnedDrop, constructor)]
+struct Guarded<T> {
+    value: T,
+}
+
+#[pinned_drop]
+impl<T> PinnedDrop for Guarded<T> {
+    fn drop(self: core::pin::Pin<&mut Self>) {}
+}
+
+#[pin_data]
+pub struct Counter {
+    count: Cell<usize>,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+impl Counter {
+    fn new(count: usize) -> impl PinInit<Self> {
+        pin_init!(Self {
+            count: Cell::new(count),
+            _pin: PhantomPinned,
+        })
+    }
+}
+
+#[test]
+fn constructor() {
+    stack_pin_init!(let pair = Pair::new(Counter::new(1), 2u8));
+    assert_eq!(pair.first.count.get(), 1);
+    assert_eq!(pair.second, 2);
+    stack_pin_init!(let pair = Pair::new(Counter::new(3), init!(Counter {
+        count: Cell::new(4),
+        _pin: PhantomPinned,
+    })));
+    assert_eq!(pair.first.count.get(), 3);
+    assert_eq!(pair.second.count.get(), 4);
+}
+
+#[test]
+fn constructor_error() {
+    stack_try_pin_init!(let guarded = Guarded::new(42));
+    let guarded: Result<_, Infallible> = guarded;
+    assert_eq!(guarded.unwrap().value, 42);
+    // SAFETY: The closure always fails.
+    let failing = unsafe { init_from_closure(|_| Err(())) };
+    stack_try_pin_init!(let guarded: Guarded<u8> = Guarded::new(failing));
+    assert!(guarded.is_err());
+}
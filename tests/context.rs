@@ -0,0 +1,172 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+
+use core::cell::Cell;
+use pinned_init::*;
+use std::rc::Rc;
+
+#[cfg(feature = "alloc")]
+use core::alloc::AllocError;
+
+#[derive(Debug, PartialEq)]
+enum Error {
+    Parse,
+    Alloc,
+}
+
+#[cfg(feature = "alloc")]
+impl From<AllocError> for Error {
+    fn from(_: AllocError) -> Self {
+        Self::Alloc
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Parse => f.write_str("parse error"),
+            Self::Alloc => f.write_str("out of memory"),
+        }
+    }
+}
+
+/// Counts how often it has been dropped.
+struct Tracked(Rc<Cell<usize>>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn parse(ok: bool) -> Result<u32, Error> {
+    if ok {
+        Ok(1)
+    } else {
+        Err(Error::Parse)
+    }
+}
+
+fn value(ok: bool) -> impl PinInit<u32, Error> {
+    // SAFETY: The closure initializes `slot` on success.
+    unsafe {
+        pin_init_from_closure(move |slot: *mut u32| {
+            slot.write(parse(ok)?);
+            Ok(())
+        })
+    }
+}
+
+#[pin_data]
+struct Ring {
+    tracked: Tracked,
+    #[pin]
+    head: u32,
+    tail: u32,
+}
+
+#[pin_data]
+struct Device {
+    id: u32,
+    #[pin]
+    rx: Ring,
+}
+
+fn device(
+    drops: &Rc<Cell<usize>>,
+    id: bool,
+    head: bool,
+    tail: bool,
+) -> impl PinInit<Device, InitError<Error>> + '_ {
+    try_pin_init!(Device {
+        id: parse(id)?,
+        rx: Ring {
+            tracked: Tracked(drops.clone()),
+            head <- value(head),
+            tail: parse(tail)?,
+        },
+    }? Error with context)
+}
+
+#[test]
+fn value_field() {
+    let drops = Rc::new(Cell::new(0));
+    stack_try_pin_init!(let dev: Device = device(&drops, false, true, true));
+    let err = dev.err().unwrap();
+    assert_eq!(err.field, "id");
+    assert!(err.type_name.ends_with("Device"));
+    assert_eq!(err.source, Error::Parse);
+    assert_eq!(drops.get(), 0);
+}
+
+#[test]
+fn nested_fields() {
+    let drops = Rc::new(Cell::new(0));
+    stack_try_pin_init!(let dev: Device = device(&drops, true, false, true));
+    let err = dev.err().unwrap();
+    assert_eq!(err.field, "rx.head");
+    assert!(err.type_name.ends_with("Device"));
+    assert_eq!(drops.get(), 1);
+
+    stack_try_pin_init!(let dev: Device = device(&drops, true, true, false));
+    let err = dev.err().unwrap();
+    assert_eq!(err.field, "rx.tail");
+    assert_eq!(drops.get(), 2);
+
+    stack_try_pin_init!(let dev: Device = device(&drops, true, true, true));
+    assert_eq!(dev.unwrap().rx.head, 1);
+}
+
+#[pin_data]
+struct Driver {
+    #[pin]
+    dev: Device,
+}
+
+#[test]
+fn forwarded() {
+    let drops = Rc::new(Cell::new(0));
+    let init = try_pin_init!(Driver {
+        dev <- device(&drops, true, false, true),
+    }? Error with context);
+    stack_try_pin_init!(let driver: Driver = init);
+    // The error points at the innermost field that failed.
+    let err = driver.err().unwrap();
+    assert_eq!(err.field, "rx.head");
+    assert!(err.type_name.ends_with("Device"));
+}
+
+struct Config {
+    name: &'static str,
+    size: u32,
+}
+
+#[test]
+fn init() {
+    let init = try_init!(Config {
+        name: "config",
+        size: parse(false)?,
+    }? Error with context);
+    stack_try_pin_init!(let config: Config = init);
+    let err = config.err().unwrap();
+    assert_eq!(err.field, "size");
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "failed to initialize `size` of `{}`: parse error",
+            core::any::type_name::<Config>()
+        )
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc() {
+    let err = InitError::<Error>::from(AllocError);
+    assert_eq!(err.source, Error::Alloc);
+    assert_eq!(err.to_string(), "out of memory");
+    let config = Box::<Config>::try_init(try_init!(Config {
+        name: "config",
+        size: parse(true)?,
+    }? Error with context));
+    assert_eq!(config.ok().unwrap().size, 1);
+}
@@ -0,0 +1,71 @@
+use core::{cell::Cell, marker::PhantomPinned, pin::Pin};
+
+// Only use `pinned_init` through the facade, like a downstream crate of `mycore` would.
+mod mycore {
+    pub use pinned_init as init;
+}
+
+use mycore::init::{pin_data, pin_init, pinned_drop, stack_pin_init, zeroed, Zeroable};
+
+#[pin_data(PinnedDrop, crate = mycore::init)]
+struct Tracked<'a> {
+    dropped: &'a Cell<bool>,
+    #[default(7)]
+    value: usize,
+    #[cfg(not(any()))]
+    #[default(3)]
+    extra: usize,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[pinned_drop(crate = mycore::init)]
+impl PinnedDrop for Tracked<'_> {
+    fn drop(self: Pin<&mut Self>) {
+        self.dropped.set(true);
+    }
+}
+
+#[pin_data(constructor, builder, crate = crate::mycore::init)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Zeroable)]
+#[zeroable(crate = mycore::init)]
+struct Raw<T> {
+    len: usize,
+    ptr: *mut T,
+}
+
+#[test]
+fn pin_data_and_pinned_drop() {
+    let flag = Cell::new(false);
+    {
+        let dropped = &flag;
+        stack_pin_init!(let tracked = pin_init!(Tracked {
+            dropped,
+            _pin: PhantomPinned,
+            ..Default
+        }));
+        assert_eq!(tracked.value, 7);
+        assert_eq!(tracked.extra, 3);
+    }
+    assert!(flag.get());
+}
+
+#[test]
+fn constructor_and_builder() {
+    stack_pin_init!(let point = Point::new(1, 2));
+    assert_eq!((point.x, point.y), (1, 2));
+    stack_pin_init!(let point = Point::builder::<core::convert::Infallible>().x(3).y(4).build());
+    assert_eq!((point.x, point.y), (3, 4));
+}
+
+#[test]
+fn derive_zeroable() {
+    stack_pin_init!(let raw = zeroed::<Raw<u8>>());
+    assert_eq!(raw.len, 0);
+    assert!(raw.ptr.is_null());
+}
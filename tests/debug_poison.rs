@@ -0,0 +1,66 @@
+#![cfg(feature = "debug-poison")]
+
+use core::{cell::Cell, mem::MaybeUninit};
+use pinned_init::*;
+
+/// Copies the bytes of the slot into `seen` before writing `value` to it.
+fn peek(seen: &Cell<[u8; 8]>, value: u64) -> impl Init<u64> + '_ {
+    // SAFETY: The slot is fully initialized when `Ok` is returned.
+    unsafe {
+        init_from_closure(move |slot: *mut u64| {
+            seen.set(slot.cast::<[u8; 8]>().read());
+            slot.write(value);
+            Ok(())
+        })
+    }
+}
+
+#[test]
+fn stack_uninit() {
+    let seen = Cell::new([0; 8]);
+    stack_pin_init!(let value = peek(&seen, 42));
+    assert_eq!(*value, 42);
+    assert_eq!(seen.get(), [POISON_UNINIT; 8]);
+}
+
+#[test]
+fn stack_rollback() {
+    let slot = Cell::new(core::ptr::null::<[u8; 8]>());
+    // SAFETY: Always returns an error.
+    let fail = unsafe {
+        pin_init_from_closure(|ptr: *mut u64| {
+            ptr.write(42);
+            slot.set(ptr.cast());
+            Err(())
+        })
+    };
+    stack_try_pin_init!(let value = fail);
+    assert!(value.is_err());
+    // SAFETY: The `StackInit` of `value` is still alive until the end of the scope.
+    assert_eq!(unsafe { slot.get().read() }, [POISON_ROLLBACK; 8]);
+}
+
+#[test]
+#[cfg(any(feature = "std", feature = "alloc"))]
+fn in_place_init() {
+    let seen = Cell::new([0; 8]);
+    let value = Box::pin_init(peek(&seen, 1)).unwrap();
+    assert_eq!(*value, 1);
+    assert_eq!(seen.get(), [POISON_UNINIT; 8]);
+
+    seen.set([0; 8]);
+    let value = std::sync::Arc::init(peek(&seen, 2)).unwrap();
+    assert_eq!(*value, 2);
+    assert_eq!(seen.get(), [POISON_UNINIT; 8]);
+}
+
+#[test]
+#[cfg(any(feature = "std", feature = "alloc"))]
+fn in_place_write() {
+    let seen = Cell::new([0; 8]);
+    let value = Box::new(MaybeUninit::new(7u64))
+        .write_init(peek(&seen, 3))
+        .unwrap();
+    assert_eq!(*value, 3);
+    assert_eq!(seen.get(), [POISON_UNINIT; 8]);
+}
@@ -0,0 +1,123 @@
+use core::{
+    cell::Cell,
+    marker::PhantomPinned,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use pinned_init::*;
+
+#[pin_data]
+struct Config {
+    name: &'static str,
+    #[default(8)]
+    depth: usize,
+    #[default(Vec::new())]
+    items: Vec<u32>,
+    #[pin]
+    #[default_init(Pinned::new(42))]
+    pinned: Pinned,
+    #[default_init(init!(Counter { count: Cell::new(1) }))]
+    counter: Counter,
+}
+
+#[pin_data]
+struct Pinned {
+    value: usize,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+impl Pinned {
+    fn new(value: usize) -> impl PinInit<Self> {
+        pin_init!(Self {
+            value,
+            _pin: PhantomPinned,
+        })
+    }
+}
+
+struct Counter {
+    count: Cell<usize>,
+}
+
+#[test]
+fn all_defaults() {
+    stack_pin_init!(let cfg = pin_init!(Config { name: "all", ..Default }));
+    assert_eq!(cfg.name, "all");
+    assert_eq!(cfg.depth, 8);
+    assert!(cfg.items.is_empty());
+    assert_eq!(cfg.pinned.value, 42);
+    assert_eq!(cfg.counter.count.get(), 1);
+}
+
+#[test]
+fn override_defaults() {
+    stack_pin_init!(let cfg = pin_init!(Config {
+        depth: 2,
+        name: "some",
+        pinned <- Pinned::new(0),
+        ..Default
+    }));
+    assert_eq!(cfg.name, "some");
+    assert_eq!(cfg.depth, 2);
+    assert!(cfg.items.is_empty());
+    assert_eq!(cfg.pinned.value, 0);
+    assert_eq!(cfg.counter.count.get(), 1);
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+struct Tracked;
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[pin_data(PinnedDrop)]
+struct WithDrop<T> {
+    #[default(Tracked)]
+    a: Tracked,
+    b: T,
+    #[default(Tracked)]
+    c: Tracked,
+}
+
+#[pinned_drop]
+impl<T> PinnedDrop for WithDrop<T> {
+    fn drop(self: Pin<&mut Self>) {}
+}
+
+#[test]
+fn defaults_with_drop() {
+    {
+        stack_pin_init!(let val = pin_init!(WithDrop { b: 0u8, ..Default }));
+        assert_eq!(val.b, 0);
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 0);
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 2);
+    DROPPED.store(0, Ordering::SeqCst);
+    {
+        stack_try_pin_init!(let val: WithDrop<u8> = try_pin_init!(WithDrop {
+            a: Tracked,
+            b: 0,
+            c: Tracked,
+            ..Default
+        }? ()));
+        assert!(val.is_ok());
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 2);
+    // The already initialized fields are dropped when initialization fails.
+    DROPPED.store(0, Ordering::SeqCst);
+    {
+        stack_try_pin_init!(let val: WithDrop<u8> = try_pin_init!(WithDrop {
+            a: Tracked,
+            // SAFETY: The closure always fails.
+            b <- unsafe { init_from_closure(|_| Err(())) },
+            ..Default
+        }? ()));
+        assert!(val.is_err());
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,128 @@
+#![cfg(any(feature = "std", feature = "alloc"))]
+
+use core::{cell::Cell, marker::PhantomPinned, pin::Pin};
+use pinned_init::*;
+use std::rc::Rc;
+
+#[derive(Debug, PartialEq)]
+struct Error;
+
+#[pin_data(PinnedDrop)]
+struct Node {
+    id: u32,
+    drops: Rc<Cell<u32>>,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[pinned_drop]
+impl PinnedDrop for Node {
+    fn drop(self: Pin<&mut Self>) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+fn node<E>(id: u32, drops: &Rc<Cell<u32>>) -> impl PinInit<Node, E> {
+    let drops = drops.clone();
+    try_pin_init!(Node { id, drops, _pin: PhantomPinned }? E)
+}
+
+fn failing() -> impl PinInit<Node, Error> {
+    // SAFETY: Always returns an error without touching the slot.
+    unsafe { pin_init_from_closure(|_| Err(Error)) }
+}
+
+#[test]
+fn select_at_runtime() {
+    let drops = Rc::new(Cell::new(0));
+    let inits: Vec<DynPinInit<'_, Node>> = (0..4)
+        .map(|id| match id % 2 {
+            0 => DynPinInit::new(node(id, &drops)),
+            _ => DynPinInit::new(pin_init_from(node(id * 10, &drops))),
+        })
+        .collect();
+    let nodes = inits
+        .into_iter()
+        .map(|init| Box::<Node>::pin_init(init).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        nodes.iter().map(|n| n.id).collect::<Vec<_>>(),
+        [0, 10, 2, 30]
+    );
+    drop(nodes);
+    assert_eq!(drops.get(), 4);
+}
+
+/// Wraps `init` so that it has a different type.
+fn pin_init_from(init: impl PinInit<Node>) -> impl PinInit<Node> {
+    init.pin_chain(|_| Ok(()))
+}
+
+#[test]
+fn error() {
+    stack_try_pin_init!(let node: Node = DynPinInit::new(failing()));
+    assert_eq!(node.err(), Some(Error));
+}
+
+#[test]
+fn nested() {
+    #[pin_data]
+    struct Pair {
+        #[pin]
+        a: Node,
+        #[pin]
+        b: Node,
+    }
+
+    let drops = Rc::new(Cell::new(0));
+    let a = DynPinInit::new(node(1, &drops));
+    let init = try_pin_init!(Pair {
+        a <- a,
+        b <- DynPinInit::new(failing()),
+    }? Error);
+    stack_try_pin_init!(let pair: Pair = init);
+    assert_eq!(pair.err(), Some(Error));
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn stack() {
+    stack_pin_init!(let value: u64 = DynPinInit::new(42));
+    assert_eq!(*value, 42);
+}
+
+/// Initializes `slot` with a copy of `value`, borrowing it until then.
+fn copied(value: &u64) -> impl PinInit<u64> + Send + '_ {
+    // SAFETY: The slot is fully initialized when `Ok` is returned.
+    unsafe {
+        pin_init_from_closure(move |slot: *mut u64| {
+            slot.write(*value);
+            Ok(())
+        })
+    }
+}
+
+#[test]
+fn send_borrowed() {
+    let values = [1, 2, 3];
+    let inits: Vec<SendDynPinInit<'_, u64>> = values
+        .iter()
+        .map(|v| SendDynPinInit::new(copied(v)))
+        .collect();
+    let sum = std::thread::scope(|s| {
+        let handles = inits
+            .into_iter()
+            .map(|init| {
+                s.spawn(move || {
+                    stack_pin_init!(let value: u64 = init);
+                    *value
+                })
+            })
+            .collect::<Vec<_>>();
+        handles.into_iter().map(|h| h.join().unwrap()).sum::<u64>()
+    });
+    assert_eq!(sum, 6);
+    let init: DynPinInit<'_, u64> = SendDynPinInit::new(copied(&values[0])).into();
+    stack_pin_init!(let value: u64 = init);
+    assert_eq!(*value, 1);
+}
@@ -0,0 +1,97 @@
+use core::{
+    cell::Cell,
+    convert::Infallible,
+    marker::PhantomPinned,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use pinned_init::*;
+use std::sync::Mutex;
+
+#[pin_data(init_type)]
+pub struct Stats {
+    pub hits: AtomicUsize,
+    pub misses: AtomicUsize,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[pin_data(init_type)]
+pub struct Pair<T> {
+    #[pin]
+    pub stats: Stats,
+    pub value: T,
+}
+
+/// A nameable initializer stored in a `static`, until it is taken to initialize a `Pair`.
+static PAIR: Mutex<Option<PairInit<StatsInit<AtomicUsize, AtomicUsize>, u32>>> =
+    Mutex::new(Some(PairInit {
+        stats: StatsInit {
+            hits: AtomicUsize::new(1),
+            misses: AtomicUsize::new(2),
+        },
+        value: 3,
+    }));
+
+#[test]
+fn nameable() {
+    let init = PAIR.lock().unwrap().take().unwrap();
+    stack_pin_init!(let pair: Pair<u32> = init);
+    assert_eq!(pair.stats.hits.load(Ordering::Relaxed), 1);
+    assert_eq!(pair.stats.misses.load(Ordering::Relaxed), 2);
+    assert_eq!(pair.value, 3);
+}
+
+#[test]
+fn pin_init_fields() {
+    let init = PairInit {
+        stats: pin_init!(Stats {
+            hits: AtomicUsize::new(4),
+            misses: AtomicUsize::new(5),
+            _pin: PhantomPinned,
+        }),
+        value: Cell::new(6),
+    };
+    stack_pin_init!(let pair: Pair<Cell<u8>> = init);
+    assert_eq!(pair.stats.hits.load(Ordering::Relaxed), 4);
+    assert_eq!(pair.value.get(), 6);
+}
+
+#[test]
+fn init() {
+    let init: StatsInit<_, _> = StatsInit {
+        hits: AtomicUsize::new(7),
+        misses: AtomicUsize::new(8),
+    };
+    let mut slot = core::mem::MaybeUninit::<Stats>::uninit();
+    // SAFETY: `slot` is valid and uninitialized.
+    let res: Result<(), Infallible> = unsafe { Init::__init(init, slot.as_mut_ptr()) };
+    res.unwrap();
+    // SAFETY: `__init` returned `Ok`.
+    let stats = unsafe { slot.assume_init() };
+    assert_eq!(stats.misses.load(Ordering::Relaxed), 8);
+}
+
+#[test]
+fn error() {
+    thread_local! {
+        static DROPPED: Cell<bool> = const { Cell::new(false) };
+    }
+    struct Flag;
+    impl Drop for Flag {
+        fn drop(&mut self) {
+            DROPPED.with(|d| d.set(true));
+        }
+    }
+
+    #[pin_data(init_type)]
+    struct Both {
+        first: Flag,
+        second: u8,
+    }
+
+    // SAFETY: The closure always fails.
+    let failing = unsafe { init_from_closure(|_| Err(())) };
+    stack_try_pin_init!(let both: Both = BothInit { first: Flag, second: failing });
+    assert!(both.is_err());
+    assert!(DROPPED.with(Cell::get));
+}
@@ -0,0 +1,106 @@
+use core::{
+    cell::Cell,
+    convert::Infallible,
+    marker::PhantomPinned,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use pinned_init::*;
+
+#[pin_data]
+struct Counter {
+    count: Cell<usize>,
+    // Points to `count` after initialization.
+    this: *const Cell<usize>,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+impl Counter {
+    fn new(count: usize) -> impl PinInit<Self> {
+        pin_init!(&this in Self {
+            count: Cell::new(count),
+            // SAFETY: `this` is valid.
+            this: unsafe { core::ptr::addr_of!((*this.as_ptr()).count) },
+            _pin: PhantomPinned,
+        })
+    }
+
+    fn is_pinned(&self) -> bool {
+        core::ptr::eq(self.this, &self.count)
+    }
+}
+
+#[test]
+fn tuple() {
+    stack_pin_init!(let pair = pin_init!((<- Counter::new(1), 2u8, <- Counter::new(3))));
+    assert_eq!(pair.0.count.get(), 1);
+    assert!(pair.0.is_pinned());
+    assert_eq!(pair.1, 2);
+    assert_eq!(pair.2.count.get(), 3);
+    assert!(pair.2.is_pinned());
+    stack_pin_init!(let unit = init!(()));
+    assert_eq!(*unit, ());
+}
+
+#[test]
+fn array() {
+    stack_pin_init!(let counters = pin_init!([<- Counter::new(1), <- Counter::new(2)]));
+    assert_eq!(counters[0].count.get(), 1);
+    assert_eq!(counters[1].count.get(), 2);
+    assert!(counters.iter().all(Counter::is_pinned));
+    stack_pin_init!(let array = init!([<- zeroed(), 4u64, 5,]));
+    assert_eq!(*array, [0, 4, 5]);
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+struct Tracked(usize);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(self.0, Ordering::SeqCst);
+    }
+}
+
+fn fail() -> impl PinInit<Tracked, ()> {
+    // SAFETY: The closure always fails.
+    unsafe { pin_init_from_closure(|_| Err(())) }
+}
+
+#[test]
+fn drops() {
+    {
+        stack_try_pin_init!(let val = try_pin_init!((Tracked(1), Tracked(10))? Infallible));
+        assert_eq!(val.unwrap().1 .0, 10);
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 11);
+    // On failure, only the elements initialized so far are dropped.
+    DROPPED.store(0, Ordering::SeqCst);
+    {
+        stack_try_pin_init!(let val = try_pin_init!((Tracked(1), <- fail(), Tracked(100))? ()));
+        assert!(val.is_err());
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+    DROPPED.store(0, Ordering::SeqCst);
+    {
+        stack_try_pin_init!(let val = try_pin_init!([Tracked(1), Tracked(10), <- fail()]? ()));
+        assert!(val.is_err());
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 11);
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn failure_points() {
+    use pinned_init::testing::*;
+
+    fn tracked(value: u8) -> impl PinInit<Tracked<u8>> {
+        TrackedInit::new(value)
+    }
+
+    let points =
+        for_each_failure_point(|| pin_init!((<- tracked(1), <- tracked(2), <- tracked(3))));
+    // Every element is a failure point.
+    assert_eq!(points, 3);
+    assert_each_dropped_once();
+}
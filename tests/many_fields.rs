@@ -0,0 +1,198 @@
+//! A struct with hundreds of fields, `#[pin_data]` and the init macros must not hit the
+//! `recursion_limit` and should expand in linear time.
+
+use pinned_init::*;
+
+#[rustfmt::skip]
+#[pin_data]
+#[derive(Zeroable)]
+struct Registers {
+    f000: u32, f001: u32, f002: u32, f003: u32, f004: u32, f005: u32, f006: u32, f007: u32, f008: u32, f009: u32,
+    f010: u32, f011: u32, f012: u32, f013: u32, f014: u32, f015: u32, f016: u32, f017: u32, f018: u32, f019: u32,
+    f020: u32, f021: u32, f022: u32, f023: u32, f024: u32, f025: u32, f026: u32, f027: u32, f028: u32, f029: u32,
+    f030: u32, f031: u32, f032: u32, f033: u32, f034: u32, f035: u32, f036: u32, f037: u32, f038: u32, f039: u32,
+    f040: u32, f041: u32, f042: u32, f043: u32, f044: u32, f045: u32, f046: u32, f047: u32, f048: u32, f049: u32,
+    f050: u32, f051: u32, f052: u32, f053: u32, f054: u32, f055: u32, f056: u32, f057: u32, f058: u32, f059: u32,
+    f060: u32, f061: u32, f062: u32, f063: u32, f064: u32, f065: u32, f066: u32, f067: u32, f068: u32, f069: u32,
+    f070: u32, f071: u32, f072: u32, f073: u32, f074: u32, f075: u32, f076: u32, f077: u32, f078: u32, f079: u32,
+    f080: u32, f081: u32, f082: u32, f083: u32, f084: u32, f085: u32, f086: u32, f087: u32, f088: u32, f089: u32,
+    f090: u32, f091: u32, f092: u32, f093: u32, f094: u32, f095: u32, f096: u32, f097: u32, f098: u32, f099: u32,
+    f100: u32, f101: u32, f102: u32, f103: u32, f104: u32, f105: u32, f106: u32, f107: u32, f108: u32, f109: u32,
+    f110: u32, f111: u32, f112: u32, f113: u32, f114: u32, f115: u32, f116: u32, f117: u32, f118: u32, f119: u32,
+    f120: u32, f121: u32, f122: u32, f123: u32, f124: u32, f125: u32, f126: u32, f127: u32, f128: u32, f129: u32,
+    f130: u32, f131: u32, f132: u32, f133: u32, f134: u32, f135: u32, f136: u32, f137: u32, f138: u32, f139: u32,
+    f140: u32, f141: u32, f142: u32, f143: u32, f144: u32, f145: u32, f146: u32, f147: u32, f148: u32, f149: u32,
+    f150: u32, f151: u32, f152: u32, f153: u32, f154: u32, f155: u32, f156: u32, f157: u32, f158: u32, f159: u32,
+    f160: u32, f161: u32, f162: u32, f163: u32, f164: u32, f165: u32, f166: u32, f167: u32, f168: u32, f169: u32,
+    f170: u32, f171: u32, f172: u32, f173: u32, f174: u32, f175: u32, f176: u32, f177: u32, f178: u32, f179: u32,
+    f180: u32, f181: u32, f182: u32, f183: u32, f184: u32, f185: u32, f186: u32, f187: u32, f188: u32, f189: u32,
+    f190: u32, f191: u32, f192: u32, f193: u32, f194: u32, f195: u32, f196: u32, f197: u32, f198: u32, f199: u32,
+    f200: u32, f201: u32, f202: u32, f203: u32, f204: u32, f205: u32, f206: u32, f207: u32, f208: u32, f209: u32,
+    f210: u32, f211: u32, f212: u32, f213: u32, f214: u32, f215: u32, f216: u32, f217: u32, f218: u32, f219: u32,
+    f220: u32, f221: u32, f222: u32, f223: u32, f224: u32, f225: u32, f226: u32, f227: u32, f228: u32, f229: u32,
+    f230: u32, f231: u32, f232: u32, f233: u32, f234: u32, f235: u32, f236: u32, f237: u32, f238: u32, f239: u32,
+    f240: u32, f241: u32, f242: u32, f243: u32, f244: u32, f245: u32, f246: u32, f247: u32, f248: u32, f249: u32,
+    f250: u32, f251: u32, f252: u32, f253: u32, f254: u32, f255: u32, f256: u32, f257: u32, f258: u32, f259: u32,
+    f260: u32, f261: u32, f262: u32, f263: u32, f264: u32, f265: u32, f266: u32, f267: u32, f268: u32, f269: u32,
+    f270: u32, f271: u32, f272: u32, f273: u32, f274: u32, f275: u32, f276: u32, f277: u32, f278: u32, f279: u32,
+    f280: u32, f281: u32, f282: u32, f283: u32, f284: u32, f285: u32, f286: u32, f287: u32, f288: u32, f289: u32,
+    f290: u32, f291: u32, f292: u32, f293: u32, f294: u32, f295: u32, f296: u32, f297: u32, f298: u32, f299: u32,
+    f300: u32, f301: u32, f302: u32, f303: u32, f304: u32, f305: u32, f306: u32, f307: u32, f308: u32, f309: u32,
+    f310: u32, f311: u32, f312: u32, f313: u32, f314: u32, f315: u32, f316: u32, f317: u32, f318: u32, f319: u32,
+    f320: u32, f321: u32, f322: u32, f323: u32, f324: u32, f325: u32, f326: u32, f327: u32, f328: u32, f329: u32,
+    f330: u32, f331: u32, f332: u32, f333: u32, f334: u32, f335: u32, f336: u32, f337: u32, f338: u32, f339: u32,
+    f340: u32, f341: u32, f342: u32, f343: u32, f344: u32, f345: u32, f346: u32, f347: u32, f348: u32, f349: u32,
+    f350: u32, f351: u32, f352: u32, f353: u32, f354: u32, f355: u32, f356: u32, f357: u32, f358: u32, f359: u32,
+    f360: u32, f361: u32, f362: u32, f363: u32, f364: u32, f365: u32, f366: u32, f367: u32, f368: u32, f369: u32,
+    f370: u32, f371: u32, f372: u32, f373: u32, f374: u32, f375: u32, f376: u32, f377: u32, f378: u32, f379: u32,
+    f380: u32, f381: u32, f382: u32, f383: u32, f384: u32, f385: u32, f386: u32, f387: u32, f388: u32, f389: u32,
+    f390: u32, f391: u32, f392: u32, f393: u32, f394: u32, f395: u32, f396: u32, f397: u32, f398: u32, f399: u32,
+    f400: u32, f401: u32, f402: u32, f403: u32, f404: u32, f405: u32, f406: u32, f407: u32, f408: u32, f409: u32,
+    f410: u32, f411: u32, f412: u32, f413: u32, f414: u32, f415: u32, f416: u32, f417: u32, f418: u32, f419: u32,
+    f420: u32, f421: u32, f422: u32, f423: u32, f424: u32, f425: u32, f426: u32, f427: u32, f428: u32, f429: u32,
+    f430: u32, f431: u32, f432: u32, f433: u32, f434: u32, f435: u32, f436: u32, f437: u32, f438: u32, f439: u32,
+    f440: u32, f441: u32, f442: u32, f443: u32, f444: u32, f445: u32, f446: u32, f447: u32, f448: u32, f449: u32,
+    f450: u32, f451: u32, f452: u32, f453: u32, f454: u32, f455: u32, f456: u32, f457: u32, f458: u32, f459: u32,
+    f460: u32, f461: u32, f462: u32, f463: u32, f464: u32, f465: u32, f466: u32, f467: u32, f468: u32, f469: u32,
+    f470: u32, f471: u32, f472: u32, f473: u32, f474: u32, f475: u32, f476: u32, f477: u32, f478: u32, f479: u32,
+    f480: u32, f481: u32, f482: u32, f483: u32, f484: u32, f485: u32, f486: u32, f487: u32, f488: u32, f489: u32,
+    f490: u32, f491: u32, f492: u32, f493: u32, f494: u32, f495: u32, f496: u32, f497: u32, f498: u32, f499: u32,
+}
+
+#[rustfmt::skip]
+fn registers() -> impl PinInit<Registers> {
+    pin_init!(Registers {
+        f000: 0, f001: 1, f002: 2, f003: 3, f004: 4, f005: 5, f006: 6, f007: 7, f008: 8, f009: 9,
+        f010: 10, f011: 11, f012: 12, f013: 13, f014: 14, f015: 15, f016: 16, f017: 17, f018: 18, f019: 19,
+        f020: 20, f021: 21, f022: 22, f023: 23, f024: 24, f025: 25, f026: 26, f027: 27, f028: 28, f029: 29,
+        f030: 30, f031: 31, f032: 32, f033: 33, f034: 34, f035: 35, f036: 36, f037: 37, f038: 38, f039: 39,
+        f040: 40, f041: 41, f042: 42, f043: 43, f044: 44, f045: 45, f046: 46, f047: 47, f048: 48, f049: 49,
+        f050: 50, f051: 51, f052: 52, f053: 53, f054: 54, f055: 55, f056: 56, f057: 57, f058: 58, f059: 59,
+        f060: 60, f061: 61, f062: 62, f063: 63, f064: 64, f065: 65, f066: 66, f067: 67, f068: 68, f069: 69,
+        f070: 70, f071: 71, f072: 72, f073: 73, f074: 74, f075: 75, f076: 76, f077: 77, f078: 78, f079: 79,
+        f080: 80, f081: 81, f082: 82, f083: 83, f084: 84, f085: 85, f086: 86, f087: 87, f088: 88, f089: 89,
+        f090: 90, f091: 91, f092: 92, f093: 93, f094: 94, f095: 95, f096: 96, f097: 97, f098: 98, f099: 99,
+        f100: 100, f101: 101, f102: 102, f103: 103, f104: 104, f105: 105, f106: 106, f107: 107, f108: 108, f109: 109,
+        f110: 110, f111: 111, f112: 112, f113: 113, f114: 114, f115: 115, f116: 116, f117: 117, f118: 118, f119: 119,
+        f120: 120, f121: 121, f122: 122, f123: 123, f124: 124, f125: 125, f126: 126, f127: 127, f128: 128, f129: 129,
+        f130: 130, f131: 131, f132: 132, f133: 133, f134: 134, f135: 135, f136: 136, f137: 137, f138: 138, f139: 139,
+        f140: 140, f141: 141, f142: 142, f143: 143, f144: 144, f145: 145, f146: 146, f147: 147, f148: 148, f149: 149,
+        f150: 150, f151: 151, f152: 152, f153: 153, f154: 154, f155: 155, f156: 156, f157: 157, f158: 158, f159: 159,
+        f160: 160, f161: 161, f162: 162, f163: 163, f164: 164, f165: 165, f166: 166, f167: 167, f168: 168, f169: 169,
+        f170: 170, f171: 171, f172: 172, f173: 173, f174: 174, f175: 175, f176: 176, f177: 177, f178: 178, f179: 179,
+        f180: 180, f181: 181, f182: 182, f183: 183, f184: 184, f185: 185, f186: 186, f187: 187, f188: 188, f189: 189,
+        f190: 190, f191: 191, f192: 192, f193: 193, f194: 194, f195: 195, f196: 196, f197: 197, f198: 198, f199: 199,
+        f200: 200, f201: 201, f202: 202, f203: 203, f204: 204, f205: 205, f206: 206, f207: 207, f208: 208, f209: 209,
+        f210: 210, f211: 211, f212: 212, f213: 213, f214: 214, f215: 215, f216: 216, f217: 217, f218: 218, f219: 219,
+        f220: 220, f221: 221, f222: 222, f223: 223, f224: 224, f225: 225, f226: 226, f227: 227, f228: 228, f229: 229,
+        f230: 230, f231: 231, f232: 232, f233: 233, f234: 234, f235: 235, f236: 236, f237: 237, f238: 238, f239: 239,
+        f240: 240, f241: 241, f242: 242, f243: 243, f244: 244, f245: 245, f246: 246, f247: 247, f248: 248, f249: 249,
+        f250: 250, f251: 251, f252: 252, f253: 253, f254: 254, f255: 255, f256: 256, f257: 257, f258: 258, f259: 259,
+        f260: 260, f261: 261, f262: 262, f263: 263, f264: 264, f265: 265, f266: 266, f267: 267, f268: 268, f269: 269,
+        f270: 270, f271: 271, f272: 272, f273: 273, f274: 274, f275: 275, f276: 276, f277: 277, f278: 278, f279: 279,
+        f280: 280, f281: 281, f282: 282, f283: 283, f284: 284, f285: 285, f286: 286, f287: 287, f288: 288, f289: 289,
+        f290: 290, f291: 291, f292: 292, f293: 293, f294: 294, f295: 295, f296: 296, f297: 297, f298: 298, f299: 299,
+        f300: 300, f301: 301, f302: 302, f303: 303, f304: 304, f305: 305, f306: 306, f307: 307, f308: 308, f309: 309,
+        f310: 310, f311: 311, f312: 312, f313: 313, f314: 314, f315: 315, f316: 316, f317: 317, f318: 318, f319: 319,
+        f320: 320, f321: 321, f322: 322, f323: 323, f324: 324, f325: 325, f326: 326, f327: 327, f328: 328, f329: 329,
+        f330: 330, f331: 331, f332: 332, f333: 333, f334: 334, f335: 335, f336: 336, f337: 337, f338: 338, f339: 339,
+        f340: 340, f341: 341, f342: 342, f343: 343, f344: 344, f345: 345, f346: 346, f347: 347, f348: 348, f349: 349,
+        f350: 350, f351: 351, f352: 352, f353: 353, f354: 354, f355: 355, f356: 356, f357: 357, f358: 358, f359: 359,
+        f360: 360, f361: 361, f362: 362, f363: 363, f364: 364, f365: 365, f366: 366, f367: 367, f368: 368, f369: 369,
+        f370: 370, f371: 371, f372: 372, f373: 373, f374: 374, f375: 375, f376: 376, f377: 377, f378: 378, f379: 379,
+        f380: 380, f381: 381, f382: 382, f383: 383, f384: 384, f385: 385, f386: 386, f387: 387, f388: 388, f389: 389,
+        f390: 390, f391: 391, f392: 392, f393: 393, f394: 394, f395: 395, f396: 396, f397: 397, f398: 398, f399: 399,
+        f400: 400, f401: 401, f402: 402, f403: 403, f404: 404, f405: 405, f406: 406, f407: 407, f408: 408, f409: 409,
+        f410: 410, f411: 411, f412: 412, f413: 413, f414: 414, f415: 415, f416: 416, f417: 417, f418: 418, f419: 419,
+        f420: 420, f421: 421, f422: 422, f423: 423, f424: 424, f425: 425, f426: 426, f427: 427, f428: 428, f429: 429,
+        f430: 430, f431: 431, f432: 432, f433: 433, f434: 434, f435: 435, f436: 436, f437: 437, f438: 438, f439: 439,
+        f440: 440, f441: 441, f442: 442, f443: 443, f444: 444, f445: 445, f446: 446, f447: 447, f448: 448, f449: 449,
+        f450: 450, f451: 451, f452: 452, f453: 453, f454: 454, f455: 455, f456: 456, f457: 457, f458: 458, f459: 459,
+        f460: 460, f461: 461, f462: 462, f463: 463, f464: 464, f465: 465, f466: 466, f467: 467, f468: 468, f469: 469,
+        f470: 470, f471: 471, f472: 472, f473: 473, f474: 474, f475: 475, f476: 476, f477: 477, f478: 478, f479: 479,
+        f480: 480, f481: 481, f482: 482, f483: 483, f484: 484, f485: 485, f486: 486, f487: 487, f488: 488, f489: 489,
+        f490: 490, f491: 491, f492: 492, f493: 493, f494: 494, f495: 495, f496: 496, f497: 497, f498: 498, f499: 499,
+    })
+}
+
+#[rustfmt::skip]
+fn registers_moveable() -> impl Init<Registers> {
+    init!(Registers {
+        f000: 0, f001: 1, f002: 2, f003: 3, f004: 4, f005: 5, f006: 6, f007: 7, f008: 8, f009: 9,
+        f010: 10, f011: 11, f012: 12, f013: 13, f014: 14, f015: 15, f016: 16, f017: 17, f018: 18, f019: 19,
+        f020: 20, f021: 21, f022: 22, f023: 23, f024: 24, f025: 25, f026: 26, f027: 27, f028: 28, f029: 29,
+        f030: 30, f031: 31, f032: 32, f033: 33, f034: 34, f035: 35, f036: 36, f037: 37, f038: 38, f039: 39,
+        f040: 40, f041: 41, f042: 42, f043: 43, f044: 44, f045: 45, f046: 46, f047: 47, f048: 48, f049: 49,
+        f050: 50, f051: 51, f052: 52, f053: 53, f054: 54, f055: 55, f056: 56, f057: 57, f058: 58, f059: 59,
+        f060: 60, f061: 61, f062: 62, f063: 63, f064: 64, f065: 65, f066: 66, f067: 67, f068: 68, f069: 69,
+        f070: 70, f071: 71, f072: 72, f073: 73, f074: 74, f075: 75, f076: 76, f077: 77, f078: 78, f079: 79,
+        f080: 80, f081: 81, f082: 82, f083: 83, f084: 84, f085: 85, f086: 86, f087: 87, f088: 88, f089: 89,
+        f090: 90, f091: 91, f092: 92, f093: 93, f094: 94, f095: 95, f096: 96, f097: 97, f098: 98, f099: 99,
+        f100: 100, f101: 101, f102: 102, f103: 103, f104: 104, f105: 105, f106: 106, f107: 107, f108: 108, f109: 109,
+        f110: 110, f111: 111, f112: 112, f113: 113, f114: 114, f115: 115, f116: 116, f117: 117, f118: 118, f119: 119,
+        f120: 120, f121: 121, f122: 122, f123: 123, f124: 124, f125: 125, f126: 126, f127: 127, f128: 128, f129: 129,
+        f130: 130, f131: 131, f132: 132, f133: 133, f134: 134, f135: 135, f136: 136, f137: 137, f138: 138, f139: 139,
+        f140: 140, f141: 141, f142: 142, f143: 143, f144: 144, f145: 145, f146: 146, f147: 147, f148: 148, f149: 149,
+        f150: 150, f151: 151, f152: 152, f153: 153, f154: 154, f155: 155, f156: 156, f157: 157, f158: 158, f159: 159,
+        f160: 160, f161: 161, f162: 162, f163: 163, f164: 164, f165: 165, f166: 166, f167: 167, f168: 168, f169: 169,
+        f170: 170, f171: 171, f172: 172, f173: 173, f174: 174, f175: 175, f176: 176, f177: 177, f178: 178, f179: 179,
+        f180: 180, f181: 181, f182: 182, f183: 183, f184: 184, f185: 185, f186: 186, f187: 187, f188: 188, f189: 189,
+        f190: 190, f191: 191, f192: 192, f193: 193, f194: 194, f195: 195, f196: 196, f197: 197, f198: 198, f199: 199,
+        f200: 200, f201: 201, f202: 202, f203: 203, f204: 204, f205: 205, f206: 206, f207: 207, f208: 208, f209: 209,
+        f210: 210, f211: 211, f212: 212, f213: 213, f214: 214, f215: 215, f216: 216, f217: 217, f218: 218, f219: 219,
+        f220: 220, f221: 221, f222: 222, f223: 223, f224: 224, f225: 225, f226: 226, f227: 227, f228: 228, f229: 229,
+        f230: 230, f231: 231, f232: 232, f233: 233, f234: 234, f235: 235, f236: 236, f237: 237, f238: 238, f239: 239,
+        f240: 240, f241: 241, f242: 242, f243: 243, f244: 244, f245: 245, f246: 246, f247: 247, f248: 248, f249: 249,
+        f250: 250, f251: 251, f252: 252, f253: 253, f254: 254, f255: 255, f256: 256, f257: 257, f258: 258, f259: 259,
+        f260: 260, f261: 261, f262: 262, f263: 263, f264: 264, f265: 265, f266: 266, f267: 267, f268: 268, f269: 269,
+        f270: 270, f271: 271, f272: 272, f273: 273, f274: 274, f275: 275, f276: 276, f277: 277, f278: 278, f279: 279,
+        f280: 280, f281: 281, f282: 282, f283: 283, f284: 284, f285: 285, f286: 286, f287: 287, f288: 288, f289: 289,
+        f290: 290, f291: 291, f292: 292, f293: 293, f294: 294, f295: 295, f296: 296, f297: 297, f298: 298, f299: 299,
+        f300: 300, f301: 301, f302: 302, f303: 303, f304: 304, f305: 305, f306: 306, f307: 307, f308: 308, f309: 309,
+        f310: 310, f311: 311, f312: 312, f313: 313, f314: 314, f315: 315, f316: 316, f317: 317, f318: 318, f319: 319,
+        f320: 320, f321: 321, f322: 322, f323: 323, f324: 324, f325: 325, f326: 326, f327: 327, f328: 328, f329: 329,
+        f330: 330, f331: 331, f332: 332, f333: 333, f334: 334, f335: 335, f336: 336, f337: 337, f338: 338, f339: 339,
+        f340: 340, f341: 341, f342: 342, f343: 343, f344: 344, f345: 345, f346: 346, f347: 347, f348: 348, f349: 349,
+        f350: 350, f351: 351, f352: 352, f353: 353, f354: 354, f355: 355, f356: 356, f357: 357, f358: 358, f359: 359,
+        f360: 360, f361: 361, f362: 362, f363: 363, f364: 364, f365: 365, f366: 366, f367: 367, f368: 368, f369: 369,
+        f370: 370, f371: 371, f372: 372, f373: 373, f374: 374, f375: 375, f376: 376, f377: 377, f378: 378, f379: 379,
+        f380: 380, f381: 381, f382: 382, f383: 383, f384: 384, f385: 385, f386: 386, f387: 387, f388: 388, f389: 389,
+        f390: 390, f391: 391, f392: 392, f393: 393, f394: 394, f395: 395, f396: 396, f397: 397, f398: 398, f399: 399,
+        f400: 400, f401: 401, f402: 402, f403: 403, f404: 404, f405: 405, f406: 406, f407: 407, f408: 408, f409: 409,
+        f410: 410, f411: 411, f412: 412, f413: 413, f414: 414, f415: 415, f416: 416, f417: 417, f418: 418, f419: 419,
+        f420: 420, f421: 421, f422: 422, f423: 423, f424: 424, f425: 425, f426: 426, f427: 427, f428: 428, f429: 429,
+        f430: 430, f431: 431, f432: 432, f433: 433, f434: 434, f435: 435, f436: 436, f437: 437, f438: 438, f439: 439,
+        f440: 440, f441: 441, f442: 442, f443: 443, f444: 444, f445: 445, f446: 446, f447: 447, f448: 448, f449: 449,
+        f450: 450, f451: 451, f452: 452, f453: 453, f454: 454, f455: 455, f456: 456, f457: 457, f458: 458, f459: 459,
+        f460: 460, f461: 461, f462: 462, f463: 463, f464: 464, f465: 465, f466: 466, f467: 467, f468: 468, f469: 469,
+        f470: 470, f471: 471, f472: 472, f473: 473, f474: 474, f475: 475, f476: 476, f477: 477, f478: 478, f479: 479,
+        f480: 480, f481: 481, f482: 482, f483: 483, f484: 484, f485: 485, f486: 486, f487: 487, f488: 488, f489: 489,
+        f490: 490, f491: 491, f492: 492, f493: 493, f494: 494, f495: 495, f496: 496, f497: 497, f498: 498, f499: 499,
+    })
+}
+
+#[test]
+fn pin_init_many_fields() {
+    stack_pin_init!(let regs = registers());
+    assert_eq!(regs.f000, 0);
+    assert_eq!(regs.f250, 250);
+    assert_eq!(regs.f499, 499);
+}
+
+#[test]
+fn init_many_fields() {
+    let regs = Box::init(registers_moveable()).unwrap();
+    assert_eq!(regs.f123, 123);
+    assert_eq!(regs.f499, 499);
+}
+
+#[test]
+fn zeroed_many_fields() {
+    stack_pin_init!(let regs = pin_init!(Registers {
+        f498: 1,
+        ..Zeroable::zeroed()
+    }));
+    assert_eq!(regs.f000, 0);
+    assert_eq!(regs.f498, 1);
+    assert_eq!(regs.f499, 0);
+}
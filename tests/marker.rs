@@ -0,0 +1,85 @@
+use core::{
+    cell::Cell,
+    marker::{PhantomData, PhantomPinned},
+};
+use pinned_init::*;
+
+#[derive(Default)]
+struct NotSend(PhantomData<*mut ()>);
+
+#[pin_data]
+struct Node<'a, T> {
+    value: T,
+    next: Cell<Option<&'a Node<'a, T>>>,
+    #[pin]
+    _pin: PhantomPinned,
+    _marker: PhantomData<fn() -> T>,
+    #[pin_data(marker)]
+    _not_send: NotSend,
+}
+
+#[pin_data(constructor)]
+struct Counter {
+    count: usize,
+    #[pin]
+    _pin: core::marker::PhantomPinned,
+}
+
+#[pin_data(builder)]
+struct Config {
+    name: &'static str,
+    #[default(4)]
+    width: usize,
+    _marker: PhantomData<u8>,
+}
+
+#[test]
+fn omitted_markers() {
+    stack_pin_init!(let node = pin_init!(Node {
+        value: 42,
+        next: Cell::new(None),
+        ..
+    }));
+    assert_eq!(node.value, 42);
+    assert!(node.next.get().is_none());
+}
+
+#[test]
+fn omitted_markers_init() {
+    let node: Box<Node<'_, u8>> = Box::init(init!(Node {
+        value: 7,
+        next: Cell::new(None),
+        ..
+    }))
+    .unwrap();
+    assert_eq!(node.value, 7);
+}
+
+#[test]
+fn explicit_markers() {
+    stack_pin_init!(let node = pin_init!(Node {
+        value: 1,
+        next: Cell::new(None),
+        _pin: PhantomPinned,
+        _marker: PhantomData,
+        _not_send: NotSend(PhantomData),
+    }));
+    assert_eq!(node.value, 1);
+}
+
+#[test]
+fn markers_with_defaults() {
+    stack_pin_init!(let config = pin_init!(Config {
+        name: "config",
+        ..Default
+    }));
+    assert_eq!(config.width, 4);
+    stack_pin_init!(let config = Config::builder().name("built").build());
+    assert_eq!(config.name, "built");
+}
+
+#[test]
+fn markers_in_constructor() {
+    stack_pin_init!(let counter = Counter::new(3));
+    assert_eq!(counter.count, 3);
+}
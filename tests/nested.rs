@@ -0,0 +1,220 @@
+#![feature(allocator_api)]
+
+use core::{
+    alloc::AllocError,
+    cell::Cell,
+    convert::Infallible,
+    marker::PhantomPinned,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use pinned_init::*;
+
+#[pin_data]
+struct Outer {
+    name: &'static str,
+    #[pin]
+    inner: Inner,
+    plain: Plain,
+}
+
+#[pin_data]
+struct Inner {
+    x: usize,
+    #[pin]
+    counter: Counter,
+}
+
+#[derive(Debug, PartialEq)]
+struct Plain {
+    a: usize,
+    b: Vec<u8>,
+}
+
+#[pin_data]
+struct Counter {
+    count: Cell<usize>,
+    // Points to `count` after initialization.
+    this: *const Cell<usize>,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+impl Counter {
+    fn new(count: usize) -> impl PinInit<Self> {
+        pin_init!(&this in Self {
+            count: Cell::new(count),
+            // SAFETY: `this` is valid.
+            this: unsafe { core::ptr::addr_of!((*this.as_ptr()).count) },
+            _pin: PhantomPinned,
+        })
+    }
+
+    fn try_new(count: usize) -> impl PinInit<Self, AllocError> {
+        try_pin_init!(&this in Self {
+            count: Cell::new(count),
+            // SAFETY: `this` is valid.
+            this: unsafe { core::ptr::addr_of!((*this.as_ptr()).count) },
+            _pin: PhantomPinned,
+        }? AllocError)
+    }
+
+    fn is_pinned(&self) -> bool {
+        core::ptr::eq(self.this, &self.count)
+    }
+}
+
+#[test]
+fn nested() {
+    stack_pin_init!(let outer = pin_init!(Outer {
+        name: "outer",
+        inner: Inner {
+            x: 1,
+            counter <- Counter::new(2),
+        },
+        // Struct expressions without `<-` are still moved into place.
+        plain: Plain { a: 3, b: vec![4] },
+    }));
+    assert_eq!(outer.name, "outer");
+    assert_eq!(outer.inner.x, 1);
+    assert_eq!(outer.inner.counter.count.get(), 2);
+    assert!(outer.inner.counter.is_pinned());
+    assert_eq!(outer.plain, Plain { a: 3, b: vec![4] });
+}
+
+mod m {
+    use super::*;
+
+    #[pin_data]
+    pub struct Inner {
+        pub x: usize,
+        #[pin]
+        pub counter: Counter,
+    }
+}
+
+#[pin_data]
+struct PathOuter {
+    #[pin]
+    inner: m::Inner,
+    z: usize,
+}
+
+#[test]
+fn nested_path() {
+    stack_pin_init!(let outer = pin_init!(PathOuter {
+        inner: m::Inner { x: 1, counter <- Counter::new(2) },
+        z: 3,
+    }));
+    assert_eq!(outer.inner.x, 1);
+    assert_eq!(outer.inner.counter.count.get(), 2);
+    assert!(outer.inner.counter.is_pinned());
+    assert_eq!(outer.z, 3);
+}
+
+#[test]
+fn nested_init() {
+    #[derive(Debug, PartialEq)]
+    struct Wrapper {
+        plain: Plain,
+        c: u8,
+    }
+
+    stack_pin_init!(let val = init!(Wrapper {
+        plain: Plain { a <- zeroed(), b: vec![2] },
+        c: 3
+    }));
+    assert_eq!(
+        *val,
+        Wrapper {
+            plain: Plain { a: 0, b: vec![2] },
+            c: 3
+        }
+    );
+}
+
+#[pin_data]
+struct Device {
+    id: usize,
+    counter: Pin<Box<Counter>>,
+}
+
+#[test]
+fn boxed_field() {
+    stack_try_pin_init!(let dev = try_pin_init!(Device {
+        id: 1,
+        counter <- boxed(Counter::try_new(4)),
+    }? AllocError));
+    let dev = dev.unwrap();
+    assert_eq!(dev.id, 1);
+    assert_eq!(dev.counter.count.get(), 4);
+    assert!(dev.counter.is_pinned());
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+struct Tracked(usize);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(self.0, Ordering::SeqCst);
+    }
+}
+
+#[pin_data]
+struct Pair {
+    a: Tracked,
+    #[pin]
+    b: Tracked,
+}
+
+#[pin_data]
+struct WithPair {
+    first: Tracked,
+    #[pin]
+    pair: Pair,
+    last: Tracked,
+}
+
+fn tracked(n: usize) -> impl Init<Tracked> {
+    Tracked(n)
+}
+
+fn fail() -> impl Init<Tracked, ()> {
+    // SAFETY: The closure always fails.
+    unsafe { init_from_closure(|_| Err(())) }
+}
+
+#[test]
+fn drops() {
+    {
+        stack_try_pin_init!(let val = try_pin_init!(WithPair {
+            first: Tracked(1),
+            pair: Pair { a: Tracked(10), b <- tracked(100) },
+            last: Tracked(1000),
+        }? Infallible));
+        assert_eq!(val.unwrap().pair.b.0, 100);
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1111);
+    // On failure, everything initialized so far is dropped, including the nested fields.
+    DROPPED.store(0, Ordering::SeqCst);
+    {
+        stack_try_pin_init!(let val: WithPair = try_pin_init!(WithPair {
+            first: Tracked(1),
+            pair: Pair { a: Tracked(10), b <- fail() },
+            last: Tracked(1000),
+        }? ()));
+        assert!(val.is_err());
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 11);
+    DROPPED.store(0, Ordering::SeqCst);
+    {
+        stack_try_pin_init!(let val: WithPair = try_pin_init!(WithPair {
+            first: Tracked(1),
+            pair: Pair { a: Tracked(10), b: Tracked(100) },
+            last <- fail(),
+        }? ()));
+        assert!(val.is_err());
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 111);
+}
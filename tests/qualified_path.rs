@@ -0,0 +1,82 @@
+use core::marker::PhantomPinned;
+use pinned_init::*;
+
+#[pin_data]
+struct State {
+    count: usize,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[pin_data]
+struct Pair<T> {
+    first: T,
+    second: T,
+}
+
+type Alias<T> = Pair<T>;
+
+trait Driver {
+    type State;
+
+    fn state(count: usize) -> impl PinInit<Self::State>;
+}
+
+struct Counter;
+
+impl Driver for Counter {
+    type State = State;
+
+    fn state(count: usize) -> impl PinInit<Self::State> {
+        pin_init!(Self::State {
+            count,
+            _pin: PhantomPinned,
+        })
+    }
+}
+
+fn generic_state<D: Driver<State = State>>(count: usize) -> impl PinInit<D::State> {
+    pin_init!(D::State {
+        count,
+        _pin: PhantomPinned,
+    })
+}
+
+fn qualified_state<D: Driver<State = State>>(count: usize) -> impl PinInit<<D as Driver>::State> {
+    pin_init!(<D as Driver>::State {
+        count,
+        _pin: PhantomPinned,
+    })
+}
+
+#[test]
+fn assoc_type() {
+    stack_pin_init!(let state = Counter::state(1));
+    assert_eq!(state.count, 1);
+    stack_pin_init!(let state = generic_state::<Counter>(2));
+    assert_eq!(state.count, 2);
+    stack_pin_init!(let state = qualified_state::<Counter>(3));
+    assert_eq!(state.count, 3);
+    stack_pin_init!(let state = pin_init!(<Counter as Driver>::State {
+        count: 4,
+        _pin: PhantomPinned,
+    }));
+    assert_eq!(state.count, 4);
+}
+
+#[test]
+fn type_alias() {
+    stack_pin_init!(let pair = pin_init!(Alias::<u8> { first: 1, second: 2 }));
+    assert_eq!((pair.first, pair.second), (1, 2));
+    stack_pin_init!(let pair = init!(Alias::<u8> { first: 3, second: 4 }));
+    assert_eq!((pair.first, pair.second), (3, 4));
+}
+
+#[test]
+fn qualified_init() {
+    stack_pin_init!(let state = init!(<Counter as Driver>::State {
+        count: 5,
+        _pin: PhantomPinned,
+    }));
+    assert_eq!(state.count, 5);
+}
@@ -0,0 +1,76 @@
+use core::{
+    cell::Cell,
+    marker::{PhantomData, PhantomPinned},
+};
+use pinned_init::*;
+
+#[pin_data]
+struct Inner {
+    value: Cell<usize>,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+impl Inner {
+    fn new(value: usize) -> impl PinInit<Self> {
+        pin_init!(Self {
+            value: Cell::new(value),
+            ..
+        })
+    }
+}
+
+#[pin_data]
+#[repr(transparent)]
+struct Pinned {
+    #[pin]
+    inner: Inner,
+}
+
+#[pin_data]
+#[repr(transparent)]
+pub struct Id<T> {
+    id: T,
+}
+
+#[pin_data]
+#[repr(transparent)]
+struct Handle<T> {
+    #[pin]
+    raw: Inner,
+    #[pin]
+    _pin: PhantomPinned,
+    _owner: PhantomData<T>,
+}
+
+#[pin_data]
+#[repr(C, packed)]
+struct Packed {
+    a: u8,
+    b: u8,
+}
+
+#[test]
+fn transparent_pinned() {
+    stack_pin_init!(let pinned = Pinned::transparent(Inner::new(3)));
+    assert_eq!(pinned.inner.value.get(), 3);
+}
+
+#[test]
+fn transparent_unpinned() {
+    let id = Box::init(Id::transparent(42u64)).unwrap();
+    assert_eq!(id.id, 42);
+}
+
+#[test]
+fn transparent_with_markers() {
+    stack_pin_init!(let handle = Handle::<u8>::transparent(Inner::new(5)));
+    assert_eq!(handle.raw.value.get(), 5);
+}
+
+#[test]
+fn packed_without_pinned_fields() {
+    stack_pin_init!(let packed = pin_init!(Packed { a: 1, b: 2 }));
+    assert_eq!({ packed.a }, 1);
+    assert_eq!({ packed.b }, 2);
+}
@@ -0,0 +1,165 @@
+use core::{
+    cell::Cell,
+    convert::Infallible,
+    marker::PhantomPinned,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use pinned_init::*;
+
+#[pin_data]
+struct Config {
+    name: &'static str,
+    depth: usize,
+    items: Vec<u32>,
+    #[pin]
+    counter: Counter,
+}
+
+#[pin_data]
+struct Counter {
+    count: Cell<usize>,
+    // Points to `count` after initialization.
+    this: *const Cell<usize>,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+impl Counter {
+    fn new(count: usize) -> impl PinInit<Self> {
+        pin_init!(&this in Self {
+            count: Cell::new(count),
+            // SAFETY: `this` is valid.
+            this: unsafe { core::ptr::addr_of!((*this.as_ptr()).count) },
+            _pin: PhantomPinned,
+        })
+    }
+
+    fn is_pinned(&self) -> bool {
+        core::ptr::eq(self.this, &self.count)
+    }
+}
+
+impl Config {
+    fn low_latency() -> impl PinInit<Self> {
+        pin_init!(Self {
+            name: "low latency",
+            depth: 1,
+            items: vec![1, 2, 3],
+            counter <- Counter::new(1),
+        })
+    }
+}
+
+#[test]
+fn update_fields() {
+    stack_pin_init!(let cfg = pin_init!(Config {
+        depth: 4,
+        counter <- Counter::new(2),
+        ..Config::low_latency()
+    }));
+    assert_eq!(cfg.name, "low latency");
+    assert_eq!(cfg.depth, 4);
+    assert_eq!(cfg.items, [1, 2, 3]);
+    assert_eq!(cfg.counter.count.get(), 2);
+    assert!(cfg.counter.is_pinned());
+}
+
+#[test]
+fn only_base() {
+    stack_pin_init!(let cfg = pin_init!(Config { ..Config::low_latency() }));
+    assert_eq!(cfg.depth, 1);
+    assert!(cfg.counter.is_pinned());
+}
+
+#[pin_data]
+#[derive(Debug)]
+struct Plain {
+    a: usize,
+    b: Vec<u8>,
+}
+
+#[test]
+fn init_update() {
+    let base = init!(Plain { a: 0, b: vec![0] });
+    stack_pin_init!(let val = init!(Plain { b: vec![1, 2], ..base }));
+    assert_eq!(val.a, 0);
+    assert_eq!(val.b, [1, 2]);
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+struct Tracked(usize);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(self.0, Ordering::SeqCst);
+    }
+}
+
+#[pin_data(PinnedDrop)]
+struct WithDrop {
+    a: Tracked,
+    #[pin]
+    b: Tracked,
+    c: Tracked,
+}
+
+#[pinned_drop]
+impl PinnedDrop for WithDrop {
+    fn drop(self: Pin<&mut Self>) {
+        DROPPED.fetch_add(1000, Ordering::SeqCst);
+    }
+}
+
+fn base<E>() -> impl PinInit<WithDrop, E> {
+    try_pin_init!(WithDrop {
+        a: Tracked(1),
+        b: Tracked(10),
+        c: Tracked(100),
+    }? E)
+}
+
+#[test]
+fn drops() {
+    // The replaced fields are dropped before they are initialized again.
+    {
+        stack_pin_init!(let val = pin_init!(WithDrop {
+            c: {
+                assert_eq!(DROPPED.load(Ordering::SeqCst), 0);
+                Tracked(200)
+            },
+            a: {
+                assert_eq!(DROPPED.load(Ordering::SeqCst), 100);
+                Tracked(2)
+            },
+            ..base::<Infallible>()
+        }));
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 101);
+        assert_eq!(val.b.0, 10);
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 101 + 1212);
+    // On failure, the value is dropped, but `PinnedDrop` is not run.
+    DROPPED.store(0, Ordering::SeqCst);
+    {
+        stack_try_pin_init!(let val: WithDrop = try_pin_init!(WithDrop {
+            a: Tracked(2),
+            // SAFETY: The closure always fails.
+            b <- unsafe { pin_init_from_closure(|_| Err(())) },
+            ..base()
+        }? ()));
+        assert!(val.is_err());
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1 + 10 + 100 + 2);
+    // When the base fails, nothing is dropped.
+    DROPPED.store(0, Ordering::SeqCst);
+    {
+        stack_try_pin_init!(let val: WithDrop = try_pin_init!(WithDrop {
+            a: Tracked(2),
+            // SAFETY: The closure always fails.
+            ..unsafe { pin_init_from_closure(|_| Err(())) }
+        }? ()));
+        assert!(val.is_err());
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 0);
+}
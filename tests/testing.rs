@@ -0,0 +1,312 @@
+#![cfg(feature = "testing")]
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+
+use core::{cell::Cell, convert::Infallible};
+use pinned_init::{testing::*, *};
+use std::panic;
+
+#[cfg(feature = "alloc")]
+use core::alloc::AllocError;
+
+#[derive(Debug, PartialEq)]
+struct Error;
+
+#[cfg(feature = "alloc")]
+impl From<AllocError> for Error {
+    fn from(_: AllocError) -> Self {
+        Self
+    }
+}
+
+impl From<InjectedError> for Error {
+    fn from(_: InjectedError) -> Self {
+        Self
+    }
+}
+
+std::thread_local! {
+    static ALIVE: Cell<isize> = const { Cell::new(0) };
+}
+
+/// Tracks how many values are alive on the current thread.
+struct Alive;
+
+impl Alive {
+    fn new() -> Self {
+        ALIVE.set(ALIVE.get() + 1);
+        Self
+    }
+}
+
+impl Drop for Alive {
+    fn drop(&mut self) {
+        ALIVE.set(ALIVE.get() - 1);
+    }
+}
+
+#[pin_data]
+struct Inner {
+    a: Alive,
+    #[pin]
+    b: Alive,
+}
+
+impl Inner {
+    fn new() -> impl PinInit<Self> {
+        pin_init!(Self {
+            a: Alive::new(),
+            b: Alive::new(),
+        })
+    }
+}
+
+#[pin_data]
+struct Outer {
+    first: Alive,
+    #[pin]
+    inner: Inner,
+    #[pin]
+    array: [Alive; 3],
+    last: Alive,
+}
+
+impl Outer {
+    fn new() -> impl PinInit<Self> {
+        pin_init!(Self {
+            first: Alive::new(),
+            inner <- Inner::new(),
+            array <- pin_init_array_from_fn::<_, 3, _, Infallible>(|_| Alive::new()),
+            last: Alive::new(),
+        })
+    }
+}
+
+#[test]
+fn every_point() {
+    let points = for_each_failure_point(Outer::new);
+    // Four fields of `Outer`, two of `Inner` and three elements.
+    assert_eq!(points, 9);
+    assert_eq!(ALIVE.get(), 0);
+}
+
+#[test]
+fn nth() {
+    let fail = FailAt::nth(3);
+    let res = panic::catch_unwind(|| {
+        stack_pin_init!(let outer = Outer::new());
+        let _ = outer;
+    });
+    let payload = res.err().unwrap();
+    assert_eq!(
+        payload.downcast_ref::<InjectedFailure>(),
+        Some(&InjectedFailure { point: 3 })
+    );
+    assert_eq!(fail.points(), 4);
+    assert_eq!(ALIVE.get(), 0);
+}
+
+#[test]
+fn nested() {
+    let outer = FailAt::nth(0);
+    {
+        let inner = FailAt::never();
+        stack_pin_init!(let value = Inner::new());
+        let _ = value;
+        assert_eq!(inner.points(), 2);
+    }
+    // The state of `outer` is restored.
+    assert_eq!(outer.points(), 0);
+    drop(outer);
+    stack_pin_init!(let value = Inner::new());
+    let _ = value;
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc() {
+    let _fail = FailAt::nth(0);
+    let res = panic::catch_unwind(|| Box::pin_init(Inner::new()));
+    // The allocation is the first failure point.
+    assert!(res.err().unwrap().is::<InjectedFailure>());
+    assert_eq!(ALIVE.get(), 0);
+}
+
+#[test]
+fn errors_are_not_failures() {
+    let points = for_each_failure_point(|| {
+        try_pin_init!(Inner {
+            a: Alive::new(),
+            b: Err(Error)?,
+        }? Error)
+    });
+    assert_eq!(points, 2);
+    assert_eq!(ALIVE.get(), 0);
+}
+
+fn alive() -> impl Init<Alive, Error> {
+    Alive::new()
+}
+
+impl Outer {
+    fn fallible() -> impl PinInit<Self, Error> {
+        try_pin_init!(Self {
+            first <- FallibleInit::new(alive()),
+            inner <- try_pin_init!(Inner {
+                a <- FallibleInit::new(alive()),
+                b <- FallibleInit::new(alive()),
+            }? Error),
+            array <- pin_init_array_from_fn::<_, 3, Alive, Error>(|_| FallibleInit::new(alive())),
+            last <- FallibleInit::new(alive()),
+        }? Error)
+    }
+}
+
+#[test]
+fn every_error_point() {
+    let points = for_each_error_point(Outer::fallible);
+    assert_eq!(points, 7);
+    assert_eq!(ALIVE.get(), 0);
+    let points = for_each_error_point(|| {
+        init_array_from_fn::<_, 3, Alive, Error>(|_| FallibleInit::new(alive()))
+    });
+    assert_eq!(points, 3);
+    assert_eq!(ALIVE.get(), 0);
+}
+
+#[test]
+fn err_at() {
+    let fail = ErrAt::nth(4);
+    stack_try_pin_init!(let outer: Outer = Outer::fallible());
+    assert_eq!(outer.err(), Some(Error));
+    assert_eq!(fail.points(), 5);
+    assert_eq!(ALIVE.get(), 0);
+    // Failure points are counted independently and never return errors.
+    let _fail = FailAt::never();
+    let _err = ErrAt::nth(0);
+    stack_pin_init!(let inner = Inner::new());
+    let _ = inner;
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn err_at_alloc() {
+    let _fail = ErrAt::nth(0);
+    assert_eq!(Box::try_pin_init(Outer::fallible()).err(), Some(Error));
+    assert_eq!(ALIVE.get(), 0);
+}
+
+fn broken() -> Alive {
+    panic!("real panic")
+}
+
+#[test]
+#[should_panic(expected = "real panic")]
+fn panics_are_propagated() {
+    for_each_failure_point(|| {
+        pin_init!(Inner {
+            a: Alive::new(),
+            b: broken(),
+        })
+    });
+}
+
+#[pin_data]
+struct Pair {
+    #[pin]
+    a: Tracked<u32>,
+    #[pin]
+    b: Tracked<u32>,
+}
+
+fn value(ok: bool) -> impl PinInit<u32, Error> {
+    // SAFETY: The closure initializes `slot` on success.
+    unsafe {
+        pin_init_from_closure(move |slot: *mut u32| {
+            if !ok {
+                return Err(Error);
+            }
+            slot.write(1);
+            Ok(())
+        })
+    }
+}
+
+/// A custom initializer that initializes `a` and then fails, `leak` forgets to drop `a`.
+fn pair(leak: bool) -> impl PinInit<Pair, Error> {
+    // SAFETY: `slot` is only left initialized on success, unless `leak` is set, which is still
+    // safe, but leaks `a`.
+    unsafe {
+        pin_init_from_closure(move |slot: *mut Pair| {
+            let a = core::ptr::addr_of_mut!((*slot).a);
+            TrackedInit::new(value(true)).__pinned_init(a)?;
+            let b = core::ptr::addr_of_mut!((*slot).b);
+            if let Err(e) = TrackedInit::new(value(false)).__pinned_init(b) {
+                if !leak {
+                    core::ptr::drop_in_place(a);
+                }
+                return Err(e);
+            }
+            Ok(())
+        })
+    }
+}
+
+#[test]
+fn clean_on_error() {
+    stack_try_pin_init!(let p: Pair = pair(false));
+    assert!(p.is_err());
+    assert_eq!(
+        drop_counts(),
+        DropCounts {
+            constructed: 1,
+            dropped: 1
+        }
+    );
+    assert_each_dropped_once();
+}
+
+#[test]
+#[should_panic(expected = "leaked `DropCounter`s: [0]")]
+fn leak_on_error() {
+    stack_try_pin_init!(let p: Pair = pair(true));
+    assert!(p.is_err());
+    assert_no_leaks();
+}
+
+#[test]
+fn tracked_failure_points() {
+    for_each_failure_point(|| {
+        try_pin_init!(Pair {
+            a <- TrackedInit::new(value(true)),
+            b <- TrackedInit::new(value(true)),
+        }? Error)
+    });
+    // One successful construction and one that fails at `b`.
+    assert_eq!(drop_counts().constructed, 3);
+    assert_each_dropped_once();
+
+    reset_drop_counts();
+    let tracked = Box::<Tracked<u8>>::pin_init(TrackedInit::new(7)).unwrap();
+    assert_eq!(tracked.counter().id(), 0);
+    assert_eq!(**tracked, 7);
+    drop(tracked);
+    assert_each_dropped_once();
+}
+
+#[test]
+fn reset_with_live_counter() {
+    let old = DropCounter::new();
+    reset_drop_counts();
+    let new = DropCounter::new();
+    assert_eq!(old.id(), new.id());
+    drop(old);
+    assert_eq!(
+        drop_counts(),
+        DropCounts {
+            constructed: 1,
+            dropped: 0
+        }
+    );
+    drop(new);
+    assert_each_dropped_once();
+}
@@ -0,0 +1,221 @@
+#![cfg(feature = "trace")]
+
+use core::cell::RefCell;
+use pinned_init::{trace::*, *};
+
+struct Error;
+
+std::thread_local! {
+    static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Records the events of the current thread, so the tests can run in parallel.
+fn record(event: &Event) {
+    let short = |type_name: &str| type_name.rsplit("::").next().unwrap().to_owned();
+    let field = |f: &Field| format!("{}.{}#{}", short(f.type_name), f.name, f.index);
+    let event = match event {
+        Event::Start(f) => format!("start {}", field(f)),
+        Event::Finish(f) => format!("finish {}", field(f)),
+        Event::Rollback(f) => format!("rollback {}", field(f)),
+        Event::Error { type_name } => format!("error {}", short(type_name)),
+        Event::Alloc { type_name, size } => format!("alloc {} {size}", short(type_name)),
+        _ => unreachable!(),
+    };
+    EVENTS.with(|events| events.borrow_mut().push(event));
+}
+
+/// Returns the events that `f` reports.
+fn trace(f: impl FnOnce()) -> Vec<String> {
+    set_hook(Some(record));
+    f();
+    EVENTS.with(|events| events.take())
+}
+
+fn value(ok: bool) -> Result<u32, Error> {
+    if ok {
+        Ok(0)
+    } else {
+        Err(Error)
+    }
+}
+
+#[pin_data]
+struct Inner {
+    x: u32,
+    #[pin]
+    y: u32,
+}
+
+impl Inner {
+    fn new(ok: bool) -> impl PinInit<Self, Error> {
+        try_pin_init!(Self {
+            x: 0,
+            y: value(ok)?,
+        }? Error)
+    }
+}
+
+#[pin_data]
+struct Outer {
+    a: u32,
+    #[pin]
+    inner: Inner,
+    b: u32,
+}
+
+impl Outer {
+    fn new(inner: bool, b: bool) -> impl PinInit<Self, Error> {
+        try_pin_init!(Self {
+            a: 0,
+            inner <- Inner::new(inner),
+            b: value(b)?,
+        }? Error)
+    }
+}
+
+#[test]
+fn success() {
+    let events = trace(|| {
+        stack_try_pin_init!(let outer: Outer = Outer::new(true, true));
+        assert!(outer.is_ok());
+    });
+    assert_eq!(
+        events,
+        [
+            "start Outer.a#0",
+            "finish Outer.a#0",
+            "start Outer.inner#1",
+            "start Inner.x#0",
+            "finish Inner.x#0",
+            "start Inner.y#1",
+            "finish Inner.y#1",
+            "finish Outer.inner#1",
+            "start Outer.b#2",
+            "finish Outer.b#2",
+        ]
+    );
+}
+
+#[test]
+fn rollback() {
+    let events = trace(|| {
+        stack_try_pin_init!(let outer: Outer = Outer::new(true, false));
+        assert!(outer.is_err());
+    });
+    assert_eq!(
+        events[8..],
+        [
+            "start Outer.b#2",
+            "rollback Outer.inner#1",
+            "rollback Outer.a#0",
+            "error Outer",
+        ]
+    );
+
+    let events = trace(|| {
+        stack_try_pin_init!(let outer: Outer = Outer::new(false, true));
+        assert!(outer.is_err());
+    });
+    assert_eq!(
+        events[2..],
+        [
+            "start Outer.inner#1",
+            "start Inner.x#0",
+            "finish Inner.x#0",
+            "start Inner.y#1",
+            "rollback Inner.x#0",
+            "error Inner",
+            "rollback Outer.a#0",
+            "error Outer",
+        ]
+    );
+}
+
+#[test]
+fn array() {
+    let events = trace(|| {
+        let init = init_array_from_fn(|i| {
+            // SAFETY: The closure initializes `slot` on success.
+            unsafe {
+                init_from_closure(move |slot: *mut usize| {
+                    slot.write(value(i != 2)? as usize);
+                    Ok::<_, Error>(())
+                })
+            }
+        });
+        stack_try_pin_init!(let array: [usize; 3] = init);
+        assert!(array.is_err());
+    });
+    assert_eq!(
+        events,
+        [
+            "start [usize; 3].#0",
+            "finish [usize; 3].#0",
+            "start [usize; 3].#1",
+            "finish [usize; 3].#1",
+            "start [usize; 3].#2",
+            "rollback [usize; 3].#0",
+            "rollback [usize; 3].#1",
+            "error [usize; 3]",
+        ]
+    );
+}
+
+#[test]
+fn literal() {
+    let events = trace(|| {
+        stack_try_pin_init!(let array: [u32; 2] = try_pin_init!([0, value(false)?]? Error));
+        assert!(array.is_err());
+    });
+    assert_eq!(
+        events,
+        [
+            "start [u32; 2].#0",
+            "finish [u32; 2].#0",
+            "start [u32; 2].#1",
+            "rollback [u32; 2].#0",
+            "error [u32; 2]",
+        ]
+    );
+}
+
+#[pin_data(builder)]
+struct Config {
+    #[default(1)]
+    depth: u32,
+    width: u32,
+}
+
+fn fail() -> impl Init<u32, Error> {
+    // SAFETY: The closure always fails.
+    unsafe { init_from_closure(|_| Err(Error)) }
+}
+
+#[test]
+fn builder() {
+    let events = trace(|| {
+        stack_try_pin_init!(let config: Config = Config::builder().width(fail()).build());
+        assert!(config.is_err());
+    });
+    assert_eq!(
+        events,
+        [
+            "start Config.depth#0",
+            "finish Config.depth#0",
+            "start Config.width#1",
+            "rollback Config.depth#0",
+            "error Config",
+        ]
+    );
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[test]
+fn alloc() {
+    let events = trace(|| {
+        let inner = Box::pin_init(pin_init!(Inner { x: 0, y: 1 }));
+        assert!(inner.is_ok());
+    });
+    assert_eq!(events[0], format!("alloc Inner {}", size_of::<Inner>()));
+    assert_eq!(events.len(), 5);
+}
@@ -0,0 +1,5 @@
+use pinned_init::*;
+
+fn main() {
+    let _ = async_pin_init!((1, 2));
+}
@@ -0,0 +1,10 @@
+use pinned_init::*;
+
+struct Foo {
+    a: usize,
+    b: usize,
+}
+
+fn main() {
+    let _foo = init!(Foo { a: 1, ..init!(Foo { a: 0, b: 0 }) });
+}
@@ -0,0 +1,17 @@
+use pinned_init::*;
+
+struct Error;
+
+struct Foo {
+    a: usize,
+}
+
+fn tuple() {
+    let _ = try_init!((1, 2)? Error with context);
+}
+
+fn unexpected_token() {
+    let _ = try_init!(Foo { a: 0 }? Error with context Foo);
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use pinned_init::*;
+
+#[pin_data]
+struct Foo {
+    a: usize,
+    b: usize,
+}
+
+fn main() {
+    let _ = pin_init!(Foo { a: 0, b: 1, a: 2 });
+}
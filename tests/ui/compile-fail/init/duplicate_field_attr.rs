@@ -0,0 +1,25 @@
+use pinned_init::*;
+
+struct Foo {
+    a: usize,
+}
+
+fn allow() -> impl Init<Foo> {
+    init!(Foo {
+        #[allow(unused)]
+        a: 1,
+        #[allow(unused)]
+        a: 2,
+    })
+}
+
+fn same_cfg() -> impl Init<Foo> {
+    init!(Foo {
+        #[cfg(all())]
+        a: 1,
+        #[cfg(all())]
+        a: 2,
+    })
+}
+
+fn main() {}
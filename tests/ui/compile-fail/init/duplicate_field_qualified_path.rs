@@ -0,0 +1,26 @@
+use pinned_init::*;
+
+#[pin_data]
+struct State {
+    a: usize,
+    b: usize,
+}
+
+trait Driver {
+    type State;
+}
+
+struct Counter;
+
+impl Driver for Counter {
+    type State = State;
+}
+
+fn main() {
+    let _ = pin_init!(<Counter as Driver>::State {
+        #[cfg(not(any()))]
+        a: 0,
+        #[cfg(not(any()))]
+        a: 1,
+    });
+}
@@ -0,0 +1,22 @@
+use pinned_init::*;
+
+#[pin_data]
+struct Foo {
+    a: usize,
+    b: Bar,
+}
+
+#[pin_data]
+struct Bar {
+    c: usize,
+}
+
+fn equals() -> impl PinInit<Foo> {
+    pin_init!(Foo { a = 0, b <- pin_init!(Bar { c: 1 }) })
+}
+
+fn colon_and_arrow() -> impl PinInit<Foo> {
+    pin_init!(Foo { a: 0, b: <- pin_init!(Bar { c: 1 }) })
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+use core::marker::PhantomPinned;
+use pinned_init::*;
+
+#[pin_data]
+struct Foo {
+    a: usize,
+    b: usize,
+}
+
+#[pin_data]
+struct Bar {
+    a: usize,
+    b: usize,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+fn main() {
+    let _foo = pin_init!(Foo { a: 0, b: 1, .. });
+    let _bar = pin_init!(Bar { a: 0, .. });
+    let _bar = pin_init!(Bar { a: 0, b: 1 });
+    let _foo = init!(Foo { a: 0, b: 1, .. });
+    let _bar = init!(Bar { b: 1, .. });
+}
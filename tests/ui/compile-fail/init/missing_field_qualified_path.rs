@@ -0,0 +1,23 @@
+use pinned_init::*;
+
+#[pin_data]
+struct State {
+    a: usize,
+    b: usize,
+}
+
+trait Driver {
+    type State;
+}
+
+struct Counter;
+
+impl Driver for Counter {
+    type State = State;
+}
+
+fn generic_state<D: Driver<State = State>>() -> impl PinInit<<D as Driver>::State> {
+    pin_init!(<D as Driver>::State { a: 0 })
+}
+
+fn main() {}
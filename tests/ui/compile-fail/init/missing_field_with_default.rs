@@ -0,0 +1,18 @@
+use pinned_init::*;
+
+#[pin_data]
+struct Foo {
+    a: usize,
+    #[default(0)]
+    b: usize,
+}
+
+#[pin_data]
+struct Bar {
+    a: usize,
+}
+
+fn main() {
+    let _foo = pin_init!(Foo { b: 1, ..Default });
+    let _bar = pin_init!(Bar { ..Default });
+}
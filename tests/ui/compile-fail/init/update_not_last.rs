@@ -0,0 +1,14 @@
+use pinned_init::*;
+
+#[derive(Zeroable)]
+struct Foo {
+    a: usize,
+    b: usize,
+}
+
+fn main() {
+    let _ = init!(Foo {
+        ..Zeroable::zeroed(),
+        a: 0,
+    });
+}
@@ -0,0 +1,14 @@
+use core::convert::Infallible;
+use pinned_init::*;
+
+#[pin_data(builder)]
+struct Foo {
+    a: usize,
+    #[default(0)]
+    b: usize,
+}
+
+fn main() {
+    let _foo = Foo::builder::<Infallible>().b(1).build();
+    let _foo = Foo::builder::<Infallible>().a(1).a(2).build();
+}
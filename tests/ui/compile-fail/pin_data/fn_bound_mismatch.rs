@@ -0,0 +1,14 @@
+use pinned_init::*;
+
+#[pin_data]
+struct Foo<F: for<'a> Fn(&'a [u8]) -> &'a u8, T: Into<Vec<Vec<u8>>>> {
+    f: F,
+    t: T,
+}
+
+fn main() {
+    let _ = pin_init!(Foo {
+        f: |x: &[u8]| x.len(),
+        t: vec![vec![0]],
+    });
+}
@@ -0,0 +1,10 @@
+use pinned_init::*;
+
+#[pin_data(init_type)]
+struct Foo {
+    a: usize,
+    #[cfg(any())]
+    b: usize,
+}
+
+fn main() {}
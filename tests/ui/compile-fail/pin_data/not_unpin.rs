@@ -0,0 +1,12 @@
+use pinned_init::*;
+
+#[pin_data(!Unpin)]
+struct Foo {
+    a: usize,
+}
+
+fn assert_unpin<T: Unpin>() {}
+
+fn main() {
+    assert_unpin::<Foo>();
+}
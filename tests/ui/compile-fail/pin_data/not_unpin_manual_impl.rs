@@ -0,0 +1,10 @@
+use pinned_init::*;
+
+#[pin_data(!Unpin)]
+struct Foo {
+    a: usize,
+}
+
+impl Unpin for Foo {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use core::marker::PhantomPinned;
+use pinned_init::*;
+
+#[pin_data]
+#[repr(C, packed(2))]
+struct Foo {
+    a: u64,
+    #[pin]
+    b: PhantomPinned,
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use pinned_init::*;
+
+struct Token;
+
+#[pin_data]
+#[repr(transparent)]
+struct Wrapper {
+    value: u32,
+    token: Token,
+}
+
+#[pin_data]
+#[repr(transparent)]
+struct Configured {
+    #[cfg(all())]
+    value: u32,
+}
+
+fn main() {}
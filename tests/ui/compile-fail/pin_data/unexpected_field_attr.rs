@@ -0,0 +1,9 @@
+use pinned_init::*;
+
+#[pin_data]
+struct Foo {
+    #[pin_data(skip)]
+    a: usize,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use pinned_init::*;
+
+#[pin_data(!Unpin, UnsafeUnpin)]
+struct Foo {
+    a: usize,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use pinned_init::*;
+
+#[pin_data(UnsafeUnpin)]
+struct Foo<T> {
+    #[pin]
+    a: T,
+}
+
+fn assert_unpin<T: Unpin>() {}
+
+fn main() {
+    assert_unpin::<Foo<usize>>();
+}
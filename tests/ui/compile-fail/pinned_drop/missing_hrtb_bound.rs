@@ -0,0 +1,18 @@
+use pinned_init::*;
+use std::pin::Pin;
+
+#[pin_data(PinnedDrop)]
+struct Foo<F: for<'a> Fn(&'a [u8]) -> &'a u8, T>
+where
+    for<'a> &'a T: IntoIterator<Item = &'a Vec<Vec<u8>>>,
+{
+    f: F,
+    t: T,
+}
+
+#[pinned_drop]
+impl<F: for<'a> Fn(&'a [u8]) -> &'a u8, T> PinnedDrop for Foo<F, T> {
+    fn drop(self: Pin<&mut Self>) {}
+}
+
+fn main() {}
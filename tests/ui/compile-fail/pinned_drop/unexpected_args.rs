@@ -0,0 +1,12 @@
+use pinned_init::*;
+use core::pin::Pin;
+
+#[pin_data(PinnedDrop)]
+struct Foo {}
+
+#[pinned_drop(mycore::init)]
+impl PinnedDrop for Foo {
+    fn drop(self: Pin<&mut Self>) {}
+}
+
+fn main() {}
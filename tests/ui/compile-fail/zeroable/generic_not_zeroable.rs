@@ -0,0 +1,13 @@
+use core::num::Wrapping;
+use pinned_init::*;
+
+#[derive(Zeroable)]
+struct Foo<F: Fn() -> u8, T: Into<Wrapping<Wrapping<u8>>>, const N: usize = { 4 >> 1 }> {
+    f: Option<Box<F>>,
+    t: T,
+    array: [u8; N],
+}
+
+fn main() {
+    let _ = zeroed::<Foo<fn() -> u8, Wrapping<Wrapping<u8>>>>();
+}
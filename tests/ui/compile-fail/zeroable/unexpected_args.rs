@@ -0,0 +1,9 @@
+use pinned_init::*;
+
+#[derive(Zeroable)]
+#[zeroable(crate)]
+struct Foo {
+    a: usize,
+}
+
+fn main() {}
@@ -0,0 +1,237 @@
+use core::{
+    marker::{PhantomData, PhantomPinned},
+    pin::Pin,
+};
+use pinned_init::*;
+struct Foo<
+    'a,
+    F: for<'b> Fn(&'b u8) -> &'b u8,
+    T: Into<Vec<Vec<u8>>>,
+    const N: usize = { 4 >> 1 },
+>
+where
+    for<'b> &'b T: IntoIterator<Item = &'b u8>,
+    Box<dyn Fn() -> Option<Vec<T>>>: 'a,
+{
+    f: F,
+    t: T,
+    array: [&'a u8; N],
+    _pin: PhantomPinned,
+}
+const _: () = {
+    struct __ThePinData<__Datee: ?::core::marker::Sized>(
+        ::core::marker::PhantomData<fn(*const __Datee) -> *const __Datee>,
+    );
+    #[allow(dead_code)]
+    impl<
+        'a,
+        F: for<'b> Fn(&'b u8) -> &'b u8,
+        T: Into<Vec<Vec<u8>>>,
+        const N: usize,
+    > __ThePinData<Foo<'a, F, T, N>>
+    where
+        for<'b> &'b T: IntoIterator<Item = &'b u8>,
+        Box<dyn Fn() -> Option<Vec<T>>>: 'a,
+    {
+        #[inline(always)]
+        fn _pin(&self) -> ::pinned_init::__internal::PinnedField<PhantomPinned> {
+            ::pinned_init::__internal::PinnedField::new(3)
+        }
+        #[inline(always)]
+        fn f(&self) -> ::pinned_init::__internal::UnpinnedField<F> {
+            ::pinned_init::__internal::UnpinnedField::new(0)
+        }
+        #[inline(always)]
+        fn t(&self) -> ::pinned_init::__internal::UnpinnedField<T> {
+            ::pinned_init::__internal::UnpinnedField::new(1)
+        }
+        #[inline(always)]
+        fn array(&self) -> ::pinned_init::__internal::UnpinnedField<[&'a u8; N]> {
+            ::pinned_init::__internal::UnpinnedField::new(2)
+        }
+    }
+    unsafe impl<
+        'a,
+        F: for<'b> Fn(&'b u8) -> &'b u8,
+        T: Into<Vec<Vec<u8>>>,
+        const N: usize,
+    > ::pinned_init::__internal::HasPinData for Foo<'a, F, T, N>
+    where
+        for<'b> &'b T: IntoIterator<Item = &'b u8>,
+        Box<dyn Fn() -> Option<Vec<T>>>: 'a,
+    {
+        type PinData = __ThePinData<Self>;
+        #[inline]
+        unsafe fn __pin_data() -> Self::PinData {
+            __ThePinData(::core::marker::PhantomData)
+        }
+        #[inline]
+        unsafe fn __drop_fields_except(slot: *mut Self, except: usize) {
+            if except != 0 {
+                unsafe { ::core::ptr::drop_in_place(&raw mut (*slot).f) };
+            }
+            if except != 1 {
+                unsafe { ::core::ptr::drop_in_place(&raw mut (*slot).t) };
+            }
+            if except != 2 {
+                unsafe { ::core::ptr::drop_in_place(&raw mut (*slot).array) };
+            }
+            if except != 3 {
+                unsafe { ::core::ptr::drop_in_place(&raw mut (*slot)._pin) };
+            }
+        }
+    }
+    unsafe impl<
+        __Datee: ?::core::marker::Sized + ::pinned_init::__internal::HasPinData,
+    > ::pinned_init::__internal::PinData for __ThePinData<__Datee> {
+        type Datee = __Datee;
+    }
+    #[doc(hidden)]
+    impl<
+        '__pin,
+        'a,
+        F: for<'b> Fn(&'b u8) -> &'b u8,
+        T: Into<Vec<Vec<u8>>>,
+        const N: usize,
+    > ::core::marker::Unpin for Foo<'a, F, T, N>
+    where
+        ::pinned_init::__internal::PinnedFields<
+            '__pin,
+            (PhantomPinned,),
+        >: ::core::marker::Unpin,
+        for<'b> &'b T: IntoIterator<Item = &'b u8>,
+        Box<dyn Fn() -> Option<Vec<T>>>: 'a,
+    {}
+    impl<
+        'a,
+        F: for<'b> Fn(&'b u8) -> &'b u8,
+        T: Into<Vec<Vec<u8>>>,
+        const N: usize,
+    > ::core::ops::Drop for Foo<'a, F, T, N>
+    where
+        for<'b> &'b T: IntoIterator<Item = &'b u8>,
+        Box<dyn Fn() -> Option<Vec<T>>>: 'a,
+    {
+        fn drop(&mut self) {
+            let pinned = unsafe { ::core::pin::Pin::new_unchecked(self) };
+            let token = unsafe { ::pinned_init::__internal::OnlyCallFromDrop::new() };
+            ::pinned_init::PinnedDrop::drop(pinned, token);
+        }
+    }
+};
+const _: () = {
+    #[allow(non_camel_case_types, dead_code)]
+    mod __fields {
+        pub struct f;
+        pub struct t;
+        pub struct array;
+        pub struct _pin;
+    }
+    impl ::pinned_init::__internal::FieldName for __fields::f {}
+    impl ::pinned_init::__internal::FieldName for __fields::t {}
+    impl ::pinned_init::__internal::FieldName for __fields::array {}
+    impl ::pinned_init::__internal::FieldName for __fields::_pin {}
+    #[doc(hidden)]
+    struct __Fields;
+    #[allow(private_interfaces)]
+    impl __Fields {
+        #[inline]
+        pub fn f(&self) -> ::pinned_init::__internal::Mention<__fields::f> {
+            ::pinned_init::__internal::Mention::new()
+        }
+        #[inline]
+        pub fn t(&self) -> ::pinned_init::__internal::Mention<__fields::t> {
+            ::pinned_init::__internal::Mention::new()
+        }
+        #[inline]
+        pub fn array(&self) -> ::pinned_init::__internal::Mention<__fields::array> {
+            ::pinned_init::__internal::Mention::new()
+        }
+        #[inline]
+        pub fn _pin(&self) -> ::pinned_init::__internal::Mention<__fields::_pin> {
+            ::pinned_init::__internal::Mention::new()
+        }
+        /// Only compiles if every field that is not a marker field is mentioned in
+        /// `mentions`.
+        #[inline]
+        #[allow(private_bounds)]
+        pub fn __check<__L, __J0, __J1, __J2>(&self, mentions: &__L)
+        where
+            __L: ::pinned_init::__internal::Mentions<__fields::f, __J0>,
+            __L: ::pinned_init::__internal::Mentions<__fields::t, __J1>,
+            __L: ::pinned_init::__internal::Mentions<__fields::array, __J2>,
+        {
+            let _ = mentions;
+        }
+    }
+    unsafe impl<
+        'a,
+        F: for<'b> Fn(&'b u8) -> &'b u8,
+        T: Into<Vec<Vec<u8>>>,
+        const N: usize,
+    > ::pinned_init::__internal::HasFields for Foo<'a, F, T, N>
+    where
+        for<'b> &'b T: IntoIterator<Item = &'b u8>,
+        Box<dyn Fn() -> Option<Vec<T>>>: 'a,
+    {
+        type Fields = __Fields;
+        #[inline]
+        fn __fields() -> Self::Fields {
+            __Fields
+        }
+        #[inline]
+        unsafe fn __init_markers(slot: *mut Self) {
+            unsafe { ::pinned_init::__internal::init_marker(&raw mut (*slot)._pin) };
+            let _ = slot;
+        }
+    }
+};
+unsafe impl<
+    'a,
+    F: for<'b> Fn(&'b u8) -> &'b u8,
+    T: Into<Vec<Vec<u8>>>,
+    const N: usize,
+> ::pinned_init::PinnedDrop for Foo<'a, F, T, N>
+where
+    for<'b> &'b T: IntoIterator<Item = &'b u8>,
+    Box<dyn Fn() -> Option<Vec<T>>>: 'a,
+{
+    fn drop(self: Pin<&mut Self>, _: ::pinned_init::__internal::OnlyCallFromDrop) {}
+}
+struct Bar<'a, F: Fn() -> u8, T: Into<Vec<Vec<u8>>>, const N: usize = { 4 >> 1 }>
+where
+    F: 'a,
+    fn() -> T: Copy,
+{
+    f: PhantomData<&'a F>,
+    t: *const T,
+    array: [u8; N],
+}
+#[automatically_derived]
+unsafe impl<
+    'a,
+    F: ::pinned_init::Zeroable + Fn() -> u8,
+    T: ::pinned_init::Zeroable + Into<Vec<Vec<u8>>>,
+    const N: usize,
+> ::pinned_init::Zeroable for Bar<'a, F, T, N>
+where
+    F: 'a,
+    fn() -> T: Copy,
+{}
+const _: () = {
+    fn assert_zeroable<T: ?::core::marker::Sized + ::pinned_init::Zeroable>() {}
+    fn ensure_zeroable<
+        'a,
+        F: ::pinned_init::Zeroable + Fn() -> u8,
+        T: ::pinned_init::Zeroable + Into<Vec<Vec<u8>>>,
+        const N: usize,
+    >()
+    where
+        F: 'a,
+        fn() -> T: Copy,
+    {
+        assert_zeroable::<PhantomData<&'a F>>();
+        assert_zeroable::<*const T>();
+        assert_zeroable::<[u8; N]>();
+    }
+};
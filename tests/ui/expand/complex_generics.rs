@@ -0,0 +1,39 @@
+use core::{
+    marker::{PhantomData, PhantomPinned},
+    pin::Pin,
+};
+use pinned_init::*;
+
+#[pin_data(PinnedDrop)]
+struct Foo<'a, F: for<'b> Fn(&'b u8) -> &'b u8, T: Into<Vec<Vec<u8>>>, const N: usize = { 4 >> 1 }>
+where
+    for<'b> &'b T: IntoIterator<Item = &'b u8>,
+    Box<dyn Fn() -> Option<Vec<T>>>: 'a,
+{
+    f: F,
+    t: T,
+    array: [&'a u8; N],
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[pinned_drop]
+impl<'a, F: for<'b> Fn(&'b u8) -> &'b u8, T: Into<Vec<Vec<u8>>>, const N: usize> PinnedDrop
+    for Foo<'a, F, T, N>
+where
+    for<'b> &'b T: IntoIterator<Item = &'b u8>,
+    Box<dyn Fn() -> Option<Vec<T>>>: 'a,
+{
+    fn drop(self: Pin<&mut Self>) {}
+}
+
+#[derive(Zeroable)]
+struct Bar<'a, F: Fn() -> u8, T: Into<Vec<Vec<u8>>>, const N: usize = { 4 >> 1 }>
+where
+    F: 'a,
+    fn() -> T: Copy,
+{
+    f: PhantomData<&'a F>,
+    t: *const T,
+    array: [u8; N],
+}
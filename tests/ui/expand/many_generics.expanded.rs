@@ -12,58 +12,32 @@ where
     _pin: PhantomPinned,
 }
 const _: () = {
-    struct __ThePinData<'a, 'b: 'a, T: Bar<'b> + ?Sized + 'a, const SIZE: usize>
-    where
-        T: Bar<'a, 1>,
-    {
-        __phantom: ::core::marker::PhantomData<
-            fn(Foo<'a, 'b, T, SIZE>) -> Foo<'a, 'b, T, SIZE>,
-        >,
-    }
-    impl<'a, 'b: 'a, T: Bar<'b> + ?Sized + 'a, const SIZE: usize> ::core::clone::Clone
-    for __ThePinData<'a, 'b, T, SIZE>
-    where
-        T: Bar<'a, 1>,
-    {
-        fn clone(&self) -> Self {
-            *self
-        }
-    }
-    impl<'a, 'b: 'a, T: Bar<'b> + ?Sized + 'a, const SIZE: usize> ::core::marker::Copy
-    for __ThePinData<'a, 'b, T, SIZE>
-    where
-        T: Bar<'a, 1>,
-    {}
+    struct __ThePinData<__Datee: ?::core::marker::Sized>(
+        ::core::marker::PhantomData<fn(*const __Datee) -> *const __Datee>,
+    );
     #[allow(dead_code)]
     impl<
         'a,
         'b: 'a,
         T: Bar<'b> + ?Sized + 'a,
         const SIZE: usize,
-    > __ThePinData<'a, 'b, T, SIZE>
+    > __ThePinData<Foo<'a, 'b, T, SIZE>>
     where
         T: Bar<'a, 1>,
     {
-        unsafe fn _pin<E>(
-            self,
-            slot: *mut PhantomPinned,
-            init: impl ::pinned_init::PinInit<PhantomPinned, E>,
-        ) -> ::core::result::Result<(), E> {
-            unsafe { ::pinned_init::PinInit::__pinned_init(init, slot) }
+        #[inline(always)]
+        fn _pin(&self) -> ::pinned_init::__internal::PinnedField<PhantomPinned> {
+            ::pinned_init::__internal::PinnedField::new(2)
         }
-        unsafe fn array<E>(
-            self,
-            slot: *mut [u8; 1024 * 1024],
-            init: impl ::pinned_init::Init<[u8; 1024 * 1024], E>,
-        ) -> ::core::result::Result<(), E> {
-            unsafe { ::pinned_init::Init::__init(init, slot) }
+        #[inline(always)]
+        fn array(&self) -> ::pinned_init::__internal::UnpinnedField<[u8; 1024 * 1024]> {
+            ::pinned_init::__internal::UnpinnedField::new(0)
         }
-        unsafe fn r<E>(
-            self,
-            slot: *mut &'b mut [&'a mut T; SIZE],
-            init: impl ::pinned_init::Init<&'b mut [&'a mut T; SIZE], E>,
-        ) -> ::core::result::Result<(), E> {
-            unsafe { ::pinned_init::Init::__init(init, slot) }
+        #[inline(always)]
+        fn r(
+            &self,
+        ) -> ::pinned_init::__internal::UnpinnedField<&'b mut [&'a mut T; SIZE]> {
+            ::pinned_init::__internal::UnpinnedField::new(1)
         }
     }
     unsafe impl<
@@ -75,34 +49,28 @@ const _: () = {
     where
         T: Bar<'a, 1>,
     {
-        type PinData = __ThePinData<'a, 'b, T, SIZE>;
+        type PinData = __ThePinData<Self>;
+        #[inline]
         unsafe fn __pin_data() -> Self::PinData {
-            __ThePinData {
-                __phantom: ::core::marker::PhantomData,
+            __ThePinData(::core::marker::PhantomData)
+        }
+        #[inline]
+        unsafe fn __drop_fields_except(slot: *mut Self, except: usize) {
+            if except != 0 {
+                unsafe { ::core::ptr::drop_in_place(&raw mut (*slot).array) };
+            }
+            if except != 1 {
+                unsafe { ::core::ptr::drop_in_place(&raw mut (*slot).r) };
+            }
+            if except != 2 {
+                unsafe { ::core::ptr::drop_in_place(&raw mut (*slot)._pin) };
             }
         }
     }
     unsafe impl<
-        'a,
-        'b: 'a,
-        T: Bar<'b> + ?Sized + 'a,
-        const SIZE: usize,
-    > ::pinned_init::__internal::PinData for __ThePinData<'a, 'b, T, SIZE>
-    where
-        T: Bar<'a, 1>,
-    {
-        type Datee = Foo<'a, 'b, T, SIZE>;
-    }
-    #[allow(dead_code)]
-    struct __Unpin<'__pin, 'a, 'b: 'a, T: Bar<'b> + ?Sized + 'a, const SIZE: usize>
-    where
-        T: Bar<'a, 1>,
-    {
-        __phantom_pin: ::core::marker::PhantomData<fn(&'__pin ()) -> &'__pin ()>,
-        __phantom: ::core::marker::PhantomData<
-            fn(Foo<'a, 'b, T, SIZE>) -> Foo<'a, 'b, T, SIZE>,
-        >,
-        _pin: PhantomPinned,
+        __Datee: ?::core::marker::Sized + ::pinned_init::__internal::HasPinData,
+    > ::pinned_init::__internal::PinData for __ThePinData<__Datee> {
+        type Datee = __Datee;
     }
     #[doc(hidden)]
     impl<
@@ -113,7 +81,10 @@ const _: () = {
         const SIZE: usize,
     > ::core::marker::Unpin for Foo<'a, 'b, T, SIZE>
     where
-        __Unpin<'__pin, 'a, 'b, T, SIZE>: ::core::marker::Unpin,
+        ::pinned_init::__internal::PinnedFields<
+            '__pin,
+            (PhantomPinned,),
+        >: ::core::marker::Unpin,
         T: Bar<'a, 1>,
     {}
     impl<'a, 'b: 'a, T: Bar<'b> + ?Sized + 'a, const SIZE: usize> ::core::ops::Drop
@@ -128,6 +99,65 @@ const _: () = {
         }
     }
 };
+const _: () = {
+    #[allow(non_camel_case_types, dead_code)]
+    mod __fields {
+        pub struct array;
+        pub struct r;
+        pub struct _pin;
+    }
+    impl ::pinned_init::__internal::FieldName for __fields::array {}
+    impl ::pinned_init::__internal::FieldName for __fields::r {}
+    impl ::pinned_init::__internal::FieldName for __fields::_pin {}
+    #[doc(hidden)]
+    struct __Fields;
+    #[allow(private_interfaces)]
+    impl __Fields {
+        #[inline]
+        pub fn array(&self) -> ::pinned_init::__internal::Mention<__fields::array> {
+            ::pinned_init::__internal::Mention::new()
+        }
+        #[inline]
+        pub fn r(&self) -> ::pinned_init::__internal::Mention<__fields::r> {
+            ::pinned_init::__internal::Mention::new()
+        }
+        #[inline]
+        pub fn _pin(&self) -> ::pinned_init::__internal::Mention<__fields::_pin> {
+            ::pinned_init::__internal::Mention::new()
+        }
+        /// Only compiles if every field that is not a marker field is mentioned in
+        /// `mentions`.
+        #[inline]
+        #[allow(private_bounds)]
+        pub fn __check<__L, __J0, __J1>(&self, mentions: &__L)
+        where
+            __L: ::pinned_init::__internal::Mentions<__fields::array, __J0>,
+            __L: ::pinned_init::__internal::Mentions<__fields::r, __J1>,
+        {
+            let _ = mentions;
+        }
+    }
+    unsafe impl<
+        'a,
+        'b: 'a,
+        T: Bar<'b> + ?Sized + 'a,
+        const SIZE: usize,
+    > ::pinned_init::__internal::HasFields for Foo<'a, 'b, T, SIZE>
+    where
+        T: Bar<'a, 1>,
+    {
+        type Fields = __Fields;
+        #[inline]
+        fn __fields() -> Self::Fields {
+            __Fields
+        }
+        #[inline]
+        unsafe fn __init_markers(slot: *mut Self) {
+            unsafe { ::pinned_init::__internal::init_marker(&raw mut (*slot)._pin) };
+            let _ = slot;
+        }
+    }
+};
 unsafe impl<
     'a,
     'b: 'a,
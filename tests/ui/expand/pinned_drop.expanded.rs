@@ -5,53 +5,48 @@ struct Foo {
     _pin: PhantomPinned,
 }
 const _: () = {
-    struct __ThePinData {
-        __phantom: ::core::marker::PhantomData<fn(Foo) -> Foo>,
-    }
-    impl ::core::clone::Clone for __ThePinData {
-        fn clone(&self) -> Self {
-            *self
-        }
-    }
-    impl ::core::marker::Copy for __ThePinData {}
+    struct __ThePinData<__Datee: ?::core::marker::Sized>(
+        ::core::marker::PhantomData<fn(*const __Datee) -> *const __Datee>,
+    );
     #[allow(dead_code)]
-    impl __ThePinData {
-        unsafe fn _pin<E>(
-            self,
-            slot: *mut PhantomPinned,
-            init: impl ::pinned_init::PinInit<PhantomPinned, E>,
-        ) -> ::core::result::Result<(), E> {
-            unsafe { ::pinned_init::PinInit::__pinned_init(init, slot) }
+    impl __ThePinData<Foo> {
+        #[inline(always)]
+        fn _pin(&self) -> ::pinned_init::__internal::PinnedField<PhantomPinned> {
+            ::pinned_init::__internal::PinnedField::new(1)
         }
-        unsafe fn array<E>(
-            self,
-            slot: *mut [u8; 1024 * 1024],
-            init: impl ::pinned_init::Init<[u8; 1024 * 1024], E>,
-        ) -> ::core::result::Result<(), E> {
-            unsafe { ::pinned_init::Init::__init(init, slot) }
+        #[inline(always)]
+        fn array(&self) -> ::pinned_init::__internal::UnpinnedField<[u8; 1024 * 1024]> {
+            ::pinned_init::__internal::UnpinnedField::new(0)
         }
     }
     unsafe impl ::pinned_init::__internal::HasPinData for Foo {
-        type PinData = __ThePinData;
+        type PinData = __ThePinData<Self>;
+        #[inline]
         unsafe fn __pin_data() -> Self::PinData {
-            __ThePinData {
-                __phantom: ::core::marker::PhantomData,
+            __ThePinData(::core::marker::PhantomData)
+        }
+        #[inline]
+        unsafe fn __drop_fields_except(slot: *mut Self, except: usize) {
+            if except != 0 {
+                unsafe { ::core::ptr::drop_in_place(&raw mut (*slot).array) };
+            }
+            if except != 1 {
+                unsafe { ::core::ptr::drop_in_place(&raw mut (*slot)._pin) };
             }
         }
     }
-    unsafe impl ::pinned_init::__internal::PinData for __ThePinData {
-        type Datee = Foo;
-    }
-    #[allow(dead_code)]
-    struct __Unpin<'__pin> {
-        __phantom_pin: ::core::marker::PhantomData<fn(&'__pin ()) -> &'__pin ()>,
-        __phantom: ::core::marker::PhantomData<fn(Foo) -> Foo>,
-        _pin: PhantomPinned,
+    unsafe impl<
+        __Datee: ?::core::marker::Sized + ::pinned_init::__internal::HasPinData,
+    > ::pinned_init::__internal::PinData for __ThePinData<__Datee> {
+        type Datee = __Datee;
     }
     #[doc(hidden)]
     impl<'__pin> ::core::marker::Unpin for Foo
     where
-        __Unpin<'__pin>: ::core::marker::Unpin,
+        ::pinned_init::__internal::PinnedFields<
+            '__pin,
+            (PhantomPinned,),
+        >: ::core::marker::Unpin,
     {}
     impl ::core::ops::Drop for Foo {
         fn drop(&mut self) {
@@ -61,6 +56,50 @@ const _: () = {
         }
     }
 };
+const _: () = {
+    #[allow(non_camel_case_types, dead_code)]
+    mod __fields {
+        pub struct array;
+        pub struct _pin;
+    }
+    impl ::pinned_init::__internal::FieldName for __fields::array {}
+    impl ::pinned_init::__internal::FieldName for __fields::_pin {}
+    #[doc(hidden)]
+    struct __Fields;
+    #[allow(private_interfaces)]
+    impl __Fields {
+        #[inline]
+        pub fn array(&self) -> ::pinned_init::__internal::Mention<__fields::array> {
+            ::pinned_init::__internal::Mention::new()
+        }
+        #[inline]
+        pub fn _pin(&self) -> ::pinned_init::__internal::Mention<__fields::_pin> {
+            ::pinned_init::__internal::Mention::new()
+        }
+        /// Only compiles if every field that is not a marker field is mentioned in
+        /// `mentions`.
+        #[inline]
+        #[allow(private_bounds)]
+        pub fn __check<__L, __J0>(&self, mentions: &__L)
+        where
+            __L: ::pinned_init::__internal::Mentions<__fields::array, __J0>,
+        {
+            let _ = mentions;
+        }
+    }
+    unsafe impl ::pinned_init::__internal::HasFields for Foo {
+        type Fields = __Fields;
+        #[inline]
+        fn __fields() -> Self::Fields {
+            __Fields
+        }
+        #[inline]
+        unsafe fn __init_markers(slot: *mut Self) {
+            unsafe { ::pinned_init::__internal::init_marker(&raw mut (*slot)._pin) };
+            let _ = slot;
+        }
+    }
+};
 unsafe impl ::pinned_init::PinnedDrop for Foo {
     fn drop(self: Pin<&mut Self>, _: ::pinned_init::__internal::OnlyCallFromDrop) {}
 }
@@ -12,16 +12,15 @@ fn main() {
             __InitOk,
             ::core::convert::Infallible,
         >(
-            data,
+            &data,
             move |slot| {
                 {
                     struct __InitOk;
-                    #[allow(unreachable_code, clippy::diverging_sub_expression)]
-                    let _ = || {
-                        unsafe {
-                            ::core::ptr::write(slot, Foo {});
-                        };
+                    let data = unsafe {
+                        ::pinned_init::__internal::HasInitData::__init_data_of(slot)
                     };
+                    let value = unsafe { &*slot };
+                    let Foo {} = value;
                 }
                 Ok(__InitOk)
             },
@@ -29,7 +28,10 @@ fn main() {
         let init = move |
             slot,
         | -> ::core::result::Result<(), ::core::convert::Infallible> {
-            init(slot).map(|__InitOk| ())
+            ::pinned_init::__internal::trace_result(
+                ::pinned_init::__internal::InitData::__type_name(&data),
+                init(slot).map(|__InitOk| ()),
+            )
         };
         let init = unsafe {
             ::pinned_init::init_from_closure::<_, ::core::convert::Infallible>(init)
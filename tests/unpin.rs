@@ -0,0 +1,52 @@
+use core::{cell::Cell, marker::PhantomPinned, pin::Pin};
+use pinned_init::*;
+
+fn assert_unpin<T: Unpin>() {}
+
+#[pin_data(!Unpin)]
+struct Registered {
+    id: usize,
+}
+
+#[pin_data(UnsafeUnpin)]
+struct Handle<T> {
+    #[pin]
+    inner: T,
+    count: Cell<usize>,
+}
+
+// SAFETY: `inner` is `Unpin`, so it does not rely on staying at its address.
+unsafe impl<T: Unpin> UnsafeUnpin for Handle<T> {}
+
+#[pin_data(PinnedDrop, UnsafeUnpin)]
+struct Guarded {
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[pinned_drop]
+impl PinnedDrop for Guarded {
+    fn drop(self: Pin<&mut Self>) {}
+}
+
+// SAFETY: `Guarded` does not rely on its address, `_pin` is only a marker.
+unsafe impl UnsafeUnpin for Guarded {}
+
+#[test]
+fn not_unpin() {
+    stack_pin_init!(let registered = pin_init!(Registered { id: 1 }));
+    assert_eq!(registered.id, 1);
+}
+
+#[test]
+fn unsafe_unpin() {
+    assert_unpin::<Handle<usize>>();
+    assert_unpin::<Guarded>();
+    stack_pin_init!(let handle = pin_init!(Handle {
+        inner: 7,
+        count: Cell::new(0),
+    }));
+    let handle = Pin::into_inner(handle);
+    handle.count.set(handle.inner);
+    assert_eq!(handle.count.get(), 7);
+}